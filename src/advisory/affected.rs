@@ -0,0 +1,173 @@
+use platforms::target::{Arch, OS};
+use semver::{Version, VersionReq};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// Details of what exactly is affected by an advisory (i.e. the
+/// `[advisory.affected]` table)
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Affected {
+    /// CPU architectures that the vulnerability is specific to
+    #[serde(default)]
+    pub arch: Vec<Arch>,
+
+    /// Operating systems that the vulnerability is specific to
+    #[serde(default)]
+    pub os: Vec<OS>,
+
+    /// Paths of the functions affected by the vulnerability, along with the
+    /// versions in which they are affected
+    #[serde(default)]
+    pub functions: BTreeMap<FunctionPath, Vec<VersionReq>>,
+
+    /// Cargo features of the crate, one of which must be enabled for it to
+    /// be affected (if empty, it's affected regardless of its features)
+    #[serde(default)]
+    pub features: Vec<String>,
+
+    /// Versions of the Rust toolchain the vulnerability is specific to (e.g.
+    /// for advisories about `std` in the `rust` collection)
+    pub rust_version: Option<VersionReq>,
+}
+
+impl Affected {
+    /// Get the functions which are affected in the given version of the crate,
+    /// e.g. so static analysis tools can tell whether they are ever called
+    pub fn functions_for(&self, version: &Version) -> Vec<&FunctionPath> {
+        self.functions
+            .iter()
+            .filter(|&(_, reqs)| reqs.iter().any(|req| req.matches(version)))
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Is the crate affected with the given features enabled?
+    pub fn affects_features<S: AsRef<str>>(&self, enabled_features: &[S]) -> bool {
+        self.features.is_empty()
+            || enabled_features
+                .iter()
+                .any(|enabled| self.features.iter().any(|f| f == enabled.as_ref()))
+    }
+
+    /// Is the given version of the Rust toolchain affected?
+    pub fn affects_rust_version(&self, rust_version: &Version) -> bool {
+        self.rust_version
+            .as_ref()
+            .map(|req| req.matches(rust_version))
+            .unwrap_or(true)
+    }
+
+    /// Is there nothing in this table?
+    pub fn is_empty(&self) -> bool {
+        self.arch.is_empty()
+            && self.os.is_empty()
+            && self.functions.is_empty()
+            && self.features.is_empty()
+            && self.rust_version.is_none()
+    }
+}
+
+/// Path to a function in a crate, e.g. `mycrate::module::function`
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct FunctionPath(String);
+
+impl FunctionPath {
+    /// Create a function path from the given string, ensuring it's a valid
+    /// Rust path with at least a crate name and a function name
+    pub fn new<S: Into<String>>(into_string: S) -> Result<Self, Error> {
+        let string = into_string.into();
+        let segments = string.split("::").collect::<Vec<_>>();
+
+        if segments.len() < 2 {
+            fail!(
+                ErrorKind::Parse,
+                "expected function path to include a crate name: {}",
+                string
+            );
+        }
+
+        for segment in &segments {
+            if !is_identifier(segment) {
+                fail!(ErrorKind::Parse, "invalid function path: {}", string);
+            }
+        }
+
+        Ok(FunctionPath(string))
+    }
+
+    /// Borrow this path as a string slice
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// Iterate over the segments of this path (e.g. `mycrate`, then `module`)
+    pub fn segments(&self) -> ::std::str::Split<'_, &str> {
+        self.0.split("::")
+    }
+
+    /// Name of the crate this path is in (i.e. its first segment)
+    pub fn crate_name(&self) -> &str {
+        self.segments().next().unwrap()
+    }
+}
+
+/// Is the given string a valid (non-raw) Rust identifier?
+fn is_identifier(string: &str) -> bool {
+    let mut chars = string.chars();
+
+    match chars.next() {
+        Some(c) if c == '_' || c.is_ascii_alphabetic() => {
+            (string != "_") && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        }
+        _ => false,
+    }
+}
+
+impl FromStr for FunctionPath {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Self::new(string)
+    }
+}
+
+impl fmt::Display for FunctionPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for FunctionPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for FunctionPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(String::deserialize(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FunctionPath;
+
+    #[test]
+    fn function_path_test() {
+        let path = FunctionPath::new("my_crate::module::function").unwrap();
+        assert_eq!(path.crate_name(), "my_crate");
+        assert_eq!(
+            path.segments().collect::<Vec<_>>(),
+            ["my_crate", "module", "function"]
+        );
+
+        for invalid in &["function", "my_crate::", "my-crate::function", "a::1b", "a::_"] {
+            assert!(FunctionPath::new(*invalid).is_err(), "{}", invalid);
+        }
+    }
+}
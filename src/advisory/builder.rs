@@ -0,0 +1,348 @@
+use semver::VersionReq;
+use std::collections::BTreeMap;
+
+use super::{
+    Advisory, AdvisoryId, Category, Collection, Cwe, Date, Informational, Keyword, Patch,
+    Reference, Url, VersionSpec, PLACEHOLDER_ADVISORY_ID,
+};
+use cvss::Cvss;
+use error::{Error, ErrorKind};
+use package::PackageName;
+
+/// Maximum length of crates.io crate names
+const MAX_CRATE_NAME_LEN: usize = 64;
+
+/// Date of advisories built with `AdvisoryBuilder::fixture`
+const FIXTURE_DATE: &str = "2019-01-01";
+
+/// Builder for constructing new advisories in code (e.g. for web forms or
+/// bots which file advisories), validating each field as it's set. Call
+/// `Advisory::to_markdown_string` on the built advisory to get it in the
+/// format of the advisory DB.
+#[derive(Clone, Debug, Default)]
+pub struct AdvisoryBuilder {
+    id: Option<AdvisoryId>,
+    package: Option<PackageName>,
+    date: Option<Date>,
+    informational: Option<Informational>,
+    versions: VersionSpec,
+    aliases: Vec<AdvisoryId>,
+    references: Vec<Reference>,
+    patches: Vec<Patch>,
+    categories: Vec<Category>,
+    cwe: Vec<Cwe>,
+    keywords: Vec<Keyword>,
+    url: Option<Url>,
+    cvss: Option<Cvss>,
+    title: Option<String>,
+    description: Option<String>,
+}
+
+impl AdvisoryBuilder {
+    /// Start building an advisory, which has the placeholder ID
+    /// (`RUSTSEC-0000-0000`) until one is assigned with `id`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start building an advisory with the given ID about the given crate,
+    /// with a placeholder date, title, and description, for assembling
+    /// synthetic databases in tests (see `AdvisoryDatabase::from_advisories`)
+    pub fn fixture(id: &str, package: &str) -> Result<Self, Error> {
+        Ok(Self::new()
+            .id(id)?
+            .package(package)?
+            .date(FIXTURE_DATE)?
+            .title(&format!("Test advisory for {}", package))?
+            .description("Test advisory."))
+    }
+
+    /// Set the ID of the advisory
+    pub fn id(mut self, id: &str) -> Result<Self, Error> {
+        let id = AdvisoryId::new(id)?;
+
+        if !id.is_rustsec() {
+            fail!(
+                ErrorKind::BadParam,
+                "expected a RUSTSEC advisory ID: {}",
+                id
+            );
+        }
+
+        self.id = Some(id);
+        Ok(self)
+    }
+
+    /// Set the name of the affected crate (required), which must be a valid
+    /// crates.io crate name
+    pub fn package(mut self, name: &str) -> Result<Self, Error> {
+        let valid = name.len() <= MAX_CRATE_NAME_LEN
+            && name
+                .chars()
+                .next()
+                .map(|c| c.is_ascii_alphabetic())
+                .unwrap_or(false)
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if !valid {
+            fail!(ErrorKind::BadParam, "invalid crate name: {:?}", name);
+        }
+
+        self.package = Some(PackageName::from(name));
+        Ok(self)
+    }
+
+    /// Set the date the advisory was issued (required)
+    pub fn date(mut self, date: &str) -> Result<Self, Error> {
+        self.date = Some(Date::new(date)?);
+        Ok(self)
+    }
+
+    /// Make the advisory an informational one (e.g. about an unmaintained crate)
+    pub fn informational(mut self, informational: Informational) -> Self {
+        self.informational = Some(informational);
+        self
+    }
+
+    /// Add a requirement matching versions which are patched
+    pub fn patched_version(mut self, requirement: &str) -> Result<Self, Error> {
+        self.versions.patched.push(parse_version_req(requirement)?);
+        Ok(self)
+    }
+
+    /// Add a requirement matching versions which were never affected
+    pub fn unaffected_version(mut self, requirement: &str) -> Result<Self, Error> {
+        self.versions
+            .unaffected
+            .push(parse_version_req(requirement)?);
+        Ok(self)
+    }
+
+    /// Add an ID of the advisory in another database (e.g. a CVE ID)
+    pub fn alias(mut self, id: &str) -> Result<Self, Error> {
+        self.aliases.push(AdvisoryId::new(id)?);
+        Ok(self)
+    }
+
+    /// Add a link to more information, guessing its kind from the URL
+    pub fn reference(mut self, url: &str) -> Result<Self, Error> {
+        self.references.push(Reference::new(https_url(url)?));
+        Ok(self)
+    }
+
+    /// Add a link to an upstream fix, guessing its kind from the URL
+    pub fn patch(mut self, url: &str) -> Result<Self, Error> {
+        self.patches.push(Patch::new(https_url(url)?));
+        Ok(self)
+    }
+
+    /// Add a category the vulnerability falls under
+    pub fn category(mut self, category: Category) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    /// Add the CWE ID of a weakness which leads to the vulnerability
+    pub fn cwe(mut self, cwe: &str) -> Result<Self, Error> {
+        self.cwe.push(cwe.parse()?);
+        Ok(self)
+    }
+
+    /// Add a keyword describing the vulnerability
+    pub fn keyword(mut self, keyword: &str) -> Result<Self, Error> {
+        self.keywords.push(Keyword::new(keyword)?);
+        Ok(self)
+    }
+
+    /// Set the URL with an announcement of the vulnerability
+    pub fn url(mut self, url: &str) -> Result<Self, Error> {
+        self.url = Some(https_url(url)?);
+        Ok(self)
+    }
+
+    /// Set the CVSS vector describing the severity of the vulnerability
+    pub fn cvss(mut self, vector: &str) -> Result<Self, Error> {
+        self.cvss = Some(vector.parse()?);
+        Ok(self)
+    }
+
+    /// Set the one-line title of the advisory (required)
+    pub fn title(mut self, title: &str) -> Result<Self, Error> {
+        let title = title.trim();
+
+        if title.is_empty() || title.contains('\n') {
+            fail!(
+                ErrorKind::BadParam,
+                "expected a one-line title: {:?}",
+                title
+            );
+        }
+
+        self.title = Some(title.to_owned());
+        Ok(self)
+    }
+
+    /// Set the description of the advisory, in Markdown (required)
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Build the advisory, failing if any of the required fields are missing or
+    /// it has any of the problems `Advisory::lint` reports as errors
+    pub fn build(self) -> Result<Advisory, Error> {
+        let advisory = Advisory {
+            id: match self.id {
+                Some(id) => id,
+                None => AdvisoryId::new(PLACEHOLDER_ADVISORY_ID)?,
+            },
+            package: required("package", self.package)?,
+            collection: Collection::default(),
+            date: required("date", self.date)?,
+            withdrawn: None,
+            informational: self.informational,
+            versions: self.versions,
+            affected_arch: None,
+            affected_os: None,
+            affected: None,
+            aliases: self.aliases,
+            related: vec![],
+            references: self.references,
+            patches: self.patches,
+            categories: self.categories,
+            cwe: self.cwe,
+            keywords: self.keywords,
+            url: self.url,
+            cvss: self.cvss,
+            explicit_severity: None,
+            explicit_license: None,
+            title: required("title", self.title)?,
+            description: required("description", self.description)?,
+            unknown_fields: BTreeMap::new(),
+        };
+
+        let errors = advisory
+            .lint()
+            .into_iter()
+            .filter(|lint| lint.is_error())
+            .map(|lint| lint.to_string())
+            .collect::<Vec<_>>();
+
+        if !errors.is_empty() {
+            fail!(
+                ErrorKind::BadParam,
+                "invalid advisory: {}",
+                errors.join("; ")
+            );
+        }
+
+        Ok(advisory)
+    }
+}
+
+/// Ensure a required field has been set
+fn required<T>(field: &str, value: Option<T>) -> Result<T, Error> {
+    value.ok_or_else(|| err!(ErrorKind::BadParam, "missing {}", field))
+}
+
+/// Parse a version requirement, rejecting ones which match every version
+fn parse_version_req(requirement: &str) -> Result<VersionReq, Error> {
+    let req = VersionReq::parse(requirement).map_err(|e| {
+        err!(
+            ErrorKind::BadParam,
+            "invalid version requirement {:?}: {}",
+            requirement,
+            e
+        )
+    })?;
+
+    if req.to_string() == VersionReq::any().to_string() {
+        fail!(
+            ErrorKind::BadParam,
+            "version requirement matches every version: {:?}",
+            requirement
+        );
+    }
+
+    Ok(req)
+}
+
+/// Parse a URL, ensuring it's `https://`
+fn https_url(url: &str) -> Result<Url, Error> {
+    let url = Url::new(url)?;
+
+    if !url.is_https() {
+        fail!(ErrorKind::BadParam, "expected an https:// URL: {}", url);
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdvisoryBuilder;
+    use db::AdvisoryDatabase;
+    use package::PackageName;
+
+    #[test]
+    fn builder_test() {
+        let advisory = AdvisoryBuilder::new()
+            .package("mycrate")
+            .unwrap()
+            .date("2019-02-01")
+            .unwrap()
+            .patched_version(">= 1.2.3")
+            .unwrap()
+            .patch("https://github.com/a/mycrate/commit/0123abc")
+            .unwrap()
+            .title("Use after free in MyType")
+            .unwrap()
+            .description("Some *Markdown*.")
+            .build()
+            .unwrap();
+
+        assert!(advisory.id.is_placeholder());
+        assert_eq!(advisory.package.as_str(), "mycrate");
+        assert!(advisory
+            .to_markdown_string()
+            .contains("# Use after free in MyType"));
+
+        assert!(AdvisoryBuilder::new().package("my crate").is_err());
+        assert!(AdvisoryBuilder::new().patched_version("*").is_err());
+        assert!(AdvisoryBuilder::new().url("http://example.com/").is_err());
+        assert!(AdvisoryBuilder::new().title("").is_err());
+
+        // Required fields must be set, and conflicting versions are rejected
+        assert!(AdvisoryBuilder::new()
+            .package("a")
+            .unwrap()
+            .build()
+            .is_err());
+        let conflicting = AdvisoryBuilder::new()
+            .package("a")
+            .and_then(|b| b.date("2019-02-01"))
+            .and_then(|b| b.patched_version(">= 1.0.0"))
+            .and_then(|b| b.unaffected_version(">= 0.5.0"))
+            .and_then(|b| b.title("Title"))
+            .unwrap()
+            .description("Description");
+        assert!(conflicting.build().is_err());
+    }
+
+    #[test]
+    fn fixture_test() {
+        let advisory = AdvisoryBuilder::fixture("RUSTSEC-2019-0001", "mycrate")
+            .and_then(|b| b.patched_version(">= 1.2.3"))
+            .and_then(|b| b.build())
+            .unwrap();
+
+        let db = AdvisoryDatabase::from_advisories(vec![advisory]).unwrap();
+        let advisories = db.find_by_crate(PackageName::from("mycrate"));
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].id.as_str(), "RUSTSEC-2019-0001");
+
+        assert!(AdvisoryBuilder::fixture("CVE-2019-0001", "mycrate").is_err());
+    }
+}
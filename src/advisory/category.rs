@@ -0,0 +1,95 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::Error;
+
+/// Categories of vulnerabilities, from a curated list
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Category {
+    /// Execution of arbitrary code allowing an attacker to gain control
+    CodeExecution,
+
+    /// Cryptography failures (e.g. confidentiality breakage, integrity breakage)
+    CryptoFailure,
+
+    /// Denial of service conditions, including excessive resource consumption
+    DenialOfService,
+
+    /// Disclosure of local files (i.e. directory traversal)
+    FileDisclosure,
+
+    /// Mishandled escaping allowing an attacker to inject formatted input
+    FormatInjection,
+
+    /// Memory unsafety vulnerabilities allowing an attacker to write to memory
+    MemoryCorruption,
+
+    /// Read-only memory safety vulnerabilities unintentionally exposing data
+    MemoryExposure,
+
+    /// Attackers can gain privileges they aren't supposed to have
+    PrivilegeEscalation,
+
+    /// Data races and similar concurrency bugs
+    ThreadSafety,
+
+    /// Categories we don't know about yet
+    Other(String),
+}
+
+impl Category {
+    /// Get a string representing this category
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Category::CodeExecution => "code-execution",
+            Category::CryptoFailure => "crypto-failure",
+            Category::DenialOfService => "denial-of-service",
+            Category::FileDisclosure => "file-disclosure",
+            Category::FormatInjection => "format-injection",
+            Category::MemoryCorruption => "memory-corruption",
+            Category::MemoryExposure => "memory-exposure",
+            Category::PrivilegeEscalation => "privilege-escalation",
+            Category::ThreadSafety => "thread-safety",
+            Category::Other(ref other) => other,
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Ok(match string {
+            "code-execution" => Category::CodeExecution,
+            "crypto-failure" => Category::CryptoFailure,
+            "denial-of-service" => Category::DenialOfService,
+            "file-disclosure" => Category::FileDisclosure,
+            "format-injection" => Category::FormatInjection,
+            "memory-corruption" => Category::MemoryCorruption,
+            "memory-exposure" => Category::MemoryExposure,
+            "privilege-escalation" => Category::PrivilegeEscalation,
+            "thread-safety" => Category::ThreadSafety,
+            other => Category::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Category {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// Collections of packages advisories can be about, each of which is stored
+/// in its own directory of the advisory DB (e.g. `crates/`)
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Collection {
+    /// Crates published on crates.io
+    #[default]
+    Crates,
+
+    /// The Rust toolchain itself (e.g. `std`, `cargo`, or `rustc`)
+    Rust,
+}
+
+impl Collection {
+    /// Get all of the collections
+    pub fn all() -> &'static [Self] {
+        &[Collection::Crates, Collection::Rust]
+    }
+
+    /// Get the name of this collection, which is also the name of the
+    /// directory its advisories are stored in
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Collection::Crates => "crates",
+            Collection::Rust => "rust",
+        }
+    }
+}
+
+impl FromStr for Collection {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        match string {
+            "crates" => Ok(Collection::Crates),
+            "rust" => Ok(Collection::Rust),
+            other => Err(err!(ErrorKind::Parse, "unknown collection: {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Collection {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Collection {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// Common Weakness Enumeration (CWE) identifier of a kind of weakness which
+/// leads to vulnerabilities (e.g. `CWE-416` for use-after-free)
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Cwe(u32);
+
+impl Cwe {
+    /// Create a CWE identifier from its number
+    pub fn new(number: u32) -> Self {
+        Cwe(number)
+    }
+
+    /// Get the number of this CWE identifier (e.g. `416` for `CWE-416`)
+    pub fn number(self) -> u32 {
+        self.0
+    }
+
+    /// Get the URL of the description of this weakness on cwe.mitre.org
+    pub fn url(self) -> String {
+        format!("https://cwe.mitre.org/data/definitions/{}.html", self.0)
+    }
+}
+
+impl FromStr for Cwe {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        let number = string
+            .strip_prefix("CWE-")
+            .ok_or_else(|| err!(ErrorKind::Parse, "malformed CWE ID: {}", string))?;
+
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            fail!(ErrorKind::Parse, "malformed CWE ID: {}", string);
+        }
+
+        number
+            .parse()
+            .map(Cwe)
+            .map_err(|_| err!(ErrorKind::Parse, "out-of-range CWE ID: {}", string))
+    }
+}
+
+impl fmt::Display for Cwe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CWE-{}", self.0)
+    }
+}
+
+impl Serialize for Cwe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cwe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cwe;
+
+    #[test]
+    fn cwe_test() {
+        let cwe = "CWE-416".parse::<Cwe>().unwrap();
+        assert_eq!(cwe.number(), 416);
+        assert_eq!(cwe.to_string(), "CWE-416");
+
+        for invalid in &["", "CWE-", "CWE-4a", "cwe-416", "416", "CWE-+1"] {
+            assert!(invalid.parse::<Cwe>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -1,6 +1,9 @@
 #[cfg(feature = "chrono")]
-use chrono::{self, DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc};
 use serde::{de::Error as DeError, Deserialize, Deserializer};
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+use std::{fmt, str::FromStr};
 
 use error::{Error, ErrorKind};
 
@@ -10,7 +13,9 @@ pub(crate) const YEAR_MIN: u32 = 2000;
 /// Maximum allowed year on advisory dates
 pub(crate) const YEAR_MAX: u32 = YEAR_MIN + 100;
 
-/// Dates on advisories (RFC 3339)
+/// Dates on advisories (RFC 3339 `YYYY-MM-DD`), which are checked to be
+/// real dates (e.g. not February 30th) when they're parsed. Dates are ordered
+/// chronologically.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize)]
 pub struct Date(String);
 
@@ -22,11 +27,37 @@ impl Date {
         Ok(Date(string))
     }
 
-    /// Convert an advisory RFC 3339 date into a `chrono::Date`
+    /// Create a `Date` from the given year, month, and day
+    pub fn from_ymd(year: u32, month: u32, day: u32) -> Result<Self, Error> {
+        Self::new(format!("{:04}-{:02}-{:02}", year, month, day))
+    }
+
+    /// Get the year of this date
+    pub fn year(&self) -> u32 {
+        self.0[..4].parse().unwrap()
+    }
+
+    /// Get the month of this date (1-12)
+    pub fn month(&self) -> u32 {
+        self.0[5..7].parse().unwrap()
+    }
+
+    /// Get the day of the month of this date (1-31)
+    pub fn day(&self) -> u32 {
+        self.0[8..].parse().unwrap()
+    }
+
+    /// Convert an advisory RFC 3339 date into a `DateTime` (at midnight UTC)
     #[cfg(feature = "chrono")]
-    pub fn to_chrono_date(&self) -> chrono::Date<Utc> {
-        let datetime = DateTime::parse_from_rfc3339(self.0.as_ref()).unwrap();
-        chrono::Date::from_utc(datetime.naive_utc().date(), Utc)
+    pub fn to_chrono_date(&self) -> DateTime<Utc> {
+        DateTime::from_naive_utc_and_offset(self.to_naive_date().and_time(NaiveTime::MIN), Utc)
+    }
+
+    /// Convert an advisory RFC 3339 date into a `chrono::NaiveDate`
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date(&self) -> NaiveDate {
+        // Dates are checked to exist when they're parsed
+        NaiveDate::from_ymd_opt(self.year() as i32, self.month(), self.day()).unwrap()
     }
 
     /// Borrow this date as a string reference
@@ -42,14 +73,41 @@ impl<'de> Deserialize<'de> for Date {
     }
 }
 
-impl Into<String> for Date {
-    fn into(self) -> String {
-        self.0
+impl FromStr for Date {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Self::new(string)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Get the (UTC) date of the given time, which must be in an allowed year
+#[cfg(feature = "chrono")]
+impl<'a> TryFrom<&'a DateTime<Utc>> for Date {
+    type Error = Error;
+
+    fn try_from(datetime: &'a DateTime<Utc>) -> Result<Self, Error> {
+        let year = u32::try_from(datetime.year())
+            .map_err(|_| err!(ErrorKind::Parse, "invalid year: {}", datetime.year()))?;
+
+        Self::from_ymd(year, datetime.month(), datetime.day())
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> String {
+        date.0
     }
 }
 
 macro_rules! check_date_part {
-    ($name:expr, $string:expr, $parts:expr, $len:expr, $min:expr, $max:expr) => {
+    ($name:expr, $string:expr, $parts:expr, $len:expr, $min:expr, $max:expr) => {{
         let part = $parts
             .next()
             .ok_or_else(|| err!(ErrorKind::Parse, "invalid date: {}", $string))?;
@@ -59,28 +117,43 @@ macro_rules! check_date_part {
         }
 
         match part.parse::<u32>() {
-            Ok($min...$max) => (),
-            _ => fail!(ErrorKind::Parse, "malformed {}: {}", $name, $string),
+            Ok(n @ $min..=$max) => n,
+            _ => return Err(err!(ErrorKind::Parse, "malformed {}: {}", $name, $string)),
         }
-    };
+    }};
 }
 
 /// Validate that a date is well-formed
 fn validate_date(string: &str) -> Result<(), Error> {
     let mut parts = string.split('-');
 
-    check_date_part!("year", string, parts, 4, YEAR_MIN, YEAR_MAX);
-    check_date_part!("month", string, parts, 2, 1, 12);
-    // TODO: ensure day is in range for month
-    check_date_part!("day", string, parts, 2, 1, 31);
+    let year = check_date_part!("year", string, parts, 4, YEAR_MIN, YEAR_MAX);
+    let month = check_date_part!("month", string, parts, 2, 1, 12);
+    let day = check_date_part!("day", string, parts, 2, 1, 31);
 
     if parts.next().is_some() {
         fail!(ErrorKind::Parse, "invalid date: {}", string)
     }
 
+    if day > days_in_month(year, month) {
+        fail!(ErrorKind::Parse, "no such day: {}", string)
+    }
+
     Ok(())
 }
 
+/// Get the number of days in the given month of the given year
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Date;
@@ -90,6 +163,13 @@ mod tests {
         assert!(Date::new("2000-01-01").is_ok());
         assert!(Date::new("2017-01-01").is_ok());
         assert!(Date::new("2099-12-31").is_ok());
+        assert!(Date::new("2020-02-29").is_ok());
+        assert!(Date::new("2000-02-29").is_ok());
+
+        let date = Date::from_ymd(2019, 2, 1).unwrap();
+        assert_eq!(date.as_str(), "2019-02-01");
+        assert_eq!((date.year(), date.month(), date.day()), (2019, 2, 1));
+        assert!(date < "2019-10-01".parse().unwrap());
     }
 
     #[test]
@@ -103,5 +183,27 @@ mod tests {
         assert!(Date::new("2017-01-32").is_err());
         assert!(Date::new("2017-01-").is_err());
         assert!(Date::new("2017-01-01-01").is_err());
+        assert!(Date::new("2017-02-29").is_err());
+        assert!(Date::new("2017-02-30").is_err());
+        assert!(Date::new("2017-04-31").is_err());
+        assert!(Date::new("2100-02-29").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_test() {
+        use chrono::{TimeZone, Utc};
+        use std::convert::TryFrom;
+
+        let datetime = Utc.with_ymd_and_hms(2019, 2, 1, 12, 30, 0).unwrap();
+        let date = Date::try_from(&datetime).unwrap();
+        assert_eq!(date.as_str(), "2019-02-01");
+        assert_eq!(
+            date.to_chrono_date(),
+            Utc.with_ymd_and_hms(2019, 2, 1, 0, 0, 0).unwrap()
+        );
+
+        let datetime = Utc.with_ymd_and_hms(1999, 12, 31, 0, 0, 0).unwrap();
+        assert!(Date::try_from(&datetime).is_err());
     }
 }
@@ -0,0 +1,109 @@
+//! Rendering of advisories as HTML (e.g. for rustsec.org)
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::iter;
+
+use super::Advisory;
+
+/// URL schemes which links and images in descriptions are allowed to use
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Render the title and Markdown description of an advisory as sanitized
+/// HTML: raw HTML is escaped instead of being passed through (with blocks
+/// of it becoming paragraphs), and links
+/// with schemes other than those in `ALLOWED_SCHEMES` are removed
+pub(crate) fn to_html(advisory: &Advisory) -> String {
+    let mut out = String::from("<h1>");
+    html::push_html(
+        &mut out,
+        iter::once(Event::Text(advisory.title.trim().into())),
+    );
+    out.push_str("</h1>\n");
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_ext(&advisory.description, options);
+    html::push_html(&mut out, parser.map(sanitize));
+    out
+}
+
+/// Sanitize a Markdown event
+fn sanitize(event: Event) -> Event {
+    match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::HtmlBlock) => Event::Start(Tag::Paragraph),
+        Event::End(TagEnd::HtmlBlock) => Event::End(TagEnd::Paragraph),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+/// Replace a URL with an empty one if it has a scheme which isn't allowed
+/// (e.g. `javascript:`). Relative URLs have no scheme and are left as-is.
+fn sanitize_url(url: CowStr) -> CowStr {
+    let scheme = match url.find([':', '/', '?', '#']) {
+        Some(pos) if url[pos..].starts_with(':') => url[..pos].to_ascii_lowercase(),
+        _ => return url,
+    };
+
+    if ALLOWED_SCHEMES.contains(&scheme.as_str()) {
+        url
+    } else {
+        CowStr::Borrowed("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Advisory;
+
+    #[test]
+    fn to_html_test() {
+        let advisory = Advisory::from_markdown(
+            r#"```toml
+[advisory]
+id = "RUSTSEC-2018-0001"
+package = "a"
+date = "2018-06-01"
+patched_versions = []
+```
+
+# Title <with> tags
+
+Some *Markdown* with a [link](https://example.com/) and a
+[bad link](JavaScript:alert(1)).
+
+<script>alert(1)</script>
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            advisory.to_html(),
+            "<h1>Title &lt;with&gt; tags</h1>\n\
+             <p>Some <em>Markdown</em> with a <a href=\"https://example.com/\">link</a> and a\n\
+             <a href=\"\">bad link</a>.</p>\n\
+             <p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n"
+        );
+    }
+}
@@ -1,45 +1,70 @@
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{self, Display};
+use std::str::FromStr;
 
 use super::date::{YEAR_MAX, YEAR_MIN};
+use super::Url;
 use error::{Error, ErrorKind};
 
 /// Placeholder advisory name: shouldn't be used until an ID is assigned
 pub const PLACEHOLDER_ADVISORY_ID: &str = "RUSTSEC-0000-0000";
 
-/// An identifier for an individual advisory
+/// Base URL of the canonical pages of RustSec advisories on rustsec.org
+pub const RUSTSEC_ADVISORIES_URL: &str = "https://rustsec.org/advisories";
+
+/// Characters which may appear in the segments of GitHub Security Advisory IDs
+const GHSA_ALPHABET: &str = "23456789cfghjmpqrvwx";
+
+/// An identifier for an individual advisory, ordered by kind, then year,
+/// then number (e.g. `RUSTSEC-2018-0002` comes before `RUSTSEC-2018-0010`)
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
-pub struct AdvisoryId {
+pub struct Id {
     /// An autodetected identifier kind
     kind: AdvisoryIdKind,
 
     /// Year this vulnerability was published
     year: Option<u32>,
 
+    /// Number of this advisory within its year
+    number: Option<u64>,
+
     /// The actual string representing the identifier
     string: String,
 }
 
-impl AdvisoryId {
-    /// Create an `AdvisoryId` from the given string
+/// Advisory identifiers (an alias of `Id`)
+pub type AdvisoryId = Id;
+
+impl Id {
+    /// Create an `Id` from the given string
     pub fn new<S: Into<String>>(into_string: S) -> Result<Self, Error> {
         let string = into_string.into();
 
         if string == PLACEHOLDER_ADVISORY_ID {
-            return Ok(AdvisoryId::default());
+            return Ok(Id::default());
         }
 
         let kind = AdvisoryIdKind::detect(&string);
 
         // Ensure known advisory types are well-formed
-        let year = match kind {
+        let (year, number) = match kind {
             AdvisoryIdKind::RUSTSEC | AdvisoryIdKind::CVE | AdvisoryIdKind::TALOS => {
-                Some(parse_year(&string)?)
+                let (year, number) = parse_year_and_number(&string)?;
+                (Some(year), Some(number))
             }
-            _ => None,
+            AdvisoryIdKind::GHSA => {
+                validate_ghsa(&string)?;
+                (None, None)
+            }
+            AdvisoryIdKind::Unknown => (None, None),
         };
 
-        Ok(Self { kind, year, string })
+        Ok(Self {
+            kind,
+            year,
+            number,
+            string,
+        })
     }
 
     /// Get a string reference to this advisory ID
@@ -73,6 +98,11 @@ impl AdvisoryId {
         }
     }
 
+    /// Is this advisory ID a GitHub Security Advisory?
+    pub fn is_ghsa(&self) -> bool {
+        matches!(self.kind, AdvisoryIdKind::GHSA)
+    }
+
     /// Is this an unknown kind of advisory ID?
     pub fn is_unknown(&self) -> bool {
         match self.kind {
@@ -86,6 +116,12 @@ impl AdvisoryId {
         self.year
     }
 
+    /// Get the number of this advisory within its year (e.g. 1 for
+    /// `RUSTSEC-2018-0001`), if it has one
+    pub fn number(&self) -> Option<u64> {
+        self.number
+    }
+
     /// Get a URL to a web page with more information on this advisory
     pub fn url(&self) -> Option<String> {
         match self.kind {
@@ -100,6 +136,10 @@ impl AdvisoryId {
                 "https://cve.mitre.org/cgi-bin/cvename.cgi?name={}",
                 &self.string
             )),
+            AdvisoryIdKind::GHSA => Some(format!(
+                "https://github.com/advisories/{}",
+                &self.string
+            )),
             AdvisoryIdKind::TALOS => Some(format!(
                 "https://www.talosintelligence.com/reports/{}",
                 &self.string
@@ -107,43 +147,69 @@ impl AdvisoryId {
             AdvisoryIdKind::Unknown => None,
         }
     }
+
+    /// Get the canonical link to this advisory's page on rustsec.org, if it's
+    /// a (non-placeholder) RustSec advisory
+    pub fn permalink(&self) -> Option<Url> {
+        self.permalink_with(RUSTSEC_ADVISORIES_URL)
+    }
+
+    /// Get the link to this advisory's page on a mirror of rustsec.org with
+    /// the given base URL (e.g. `https://mirror.example.com/advisories`)
+    pub fn permalink_with(&self, base_url: &str) -> Option<Url> {
+        if !self.is_rustsec() || self.is_placeholder() {
+            return None;
+        }
+
+        let base_url = base_url.trim_end_matches('/');
+        Url::new(format!("{}/{}.html", base_url, &self.string)).ok()
+    }
 }
 
-impl AsRef<AdvisoryId> for AdvisoryId {
-    fn as_ref(&self) -> &AdvisoryId {
+impl AsRef<Id> for Id {
+    fn as_ref(&self) -> &Id {
         self
     }
 }
 
-impl Default for AdvisoryId {
-    fn default() -> AdvisoryId {
-        AdvisoryId {
+impl Default for Id {
+    fn default() -> Id {
+        Id {
             kind: AdvisoryIdKind::RUSTSEC,
             year: None,
+            number: None,
             string: PLACEHOLDER_ADVISORY_ID.into(),
         }
     }
 }
 
-impl Display for AdvisoryId {
+impl FromStr for Id {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Self::new(string)
+    }
+}
+
+impl Display for Id {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.string.fmt(f)
     }
 }
 
-impl Into<String> for AdvisoryId {
-    fn into(self) -> String {
-        self.string
+impl From<Id> for String {
+    fn from(id: Id) -> String {
+        id.string
     }
 }
 
-impl Serialize for AdvisoryId {
+impl Serialize for Id {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.serialize_str(&self.string)
     }
 }
 
-impl<'de> Deserialize<'de> for AdvisoryId {
+impl<'de> Deserialize<'de> for Id {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         Self::new(String::deserialize(deserializer)?)
             .map_err(|e| D::Error::custom(format!("{}", e)))
@@ -159,6 +225,9 @@ pub enum AdvisoryIdKind {
     /// Common Vulnerabilities and Exposures
     CVE,
 
+    /// GitHub Security Advisories
+    GHSA,
+
     /// Cisco Talos identifiers
     TALOS,
 
@@ -173,6 +242,8 @@ impl AdvisoryIdKind {
             AdvisoryIdKind::RUSTSEC
         } else if string.starts_with("CVE-") {
             AdvisoryIdKind::CVE
+        } else if string.starts_with("GHSA-") {
+            AdvisoryIdKind::GHSA
         } else if string.starts_with("TALOS-") {
             AdvisoryIdKind::TALOS
         } else {
@@ -181,8 +252,8 @@ impl AdvisoryIdKind {
     }
 }
 
-/// Parse the year from an advisory identifier
-fn parse_year(advisory_id: &str) -> Result<u32, Error> {
+/// Parse the year and number from an advisory identifier
+fn parse_year_and_number(advisory_id: &str) -> Result<(u32, u64), Error> {
     let mut parts = advisory_id.split('-');
     parts.next().unwrap();
 
@@ -202,27 +273,45 @@ fn parse_year(advisory_id: &str) -> Result<u32, Error> {
         ),
     };
 
-    if let Some(num) = parts.next() {
-        if num.parse::<u32>().is_err() {
-            fail!(ErrorKind::Parse, "malformed advisory ID: {}", advisory_id);
+    let number = match parts.next() {
+        Some(num) => num
+            .parse::<u64>()
+            .map_err(|_| err!(ErrorKind::Parse, "malformed advisory ID: {}", advisory_id))?,
+        None => {
+            fail!(ErrorKind::Parse, "incomplete advisory ID: {}", advisory_id);
         }
-    } else {
-        fail!(ErrorKind::Parse, "incomplete advisory ID: {}", advisory_id);
-    }
+    };
 
     if parts.next().is_some() {
         fail!(ErrorKind::Parse, "malformed advisory ID: {}", advisory_id);
     }
 
-    Ok(year)
+    Ok((year, number))
+}
+
+/// Ensure a GitHub Security Advisory ID is well-formed (e.g. `GHSA-xxxx-xxxx-xxxx`)
+fn validate_ghsa(advisory_id: &str) -> Result<(), Error> {
+    let segments = advisory_id.split('-').skip(1).collect::<Vec<_>>();
+
+    let well_formed = segments.len() == 3
+        && segments.iter().all(|segment| {
+            segment.len() == 4 && segment.chars().all(|c| GHSA_ALPHABET.contains(c))
+        });
+
+    if !well_formed {
+        fail!(ErrorKind::Parse, "malformed GHSA ID: {}", advisory_id);
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AdvisoryId, AdvisoryIdKind, PLACEHOLDER_ADVISORY_ID};
+    use super::{AdvisoryId, AdvisoryIdKind, Id, PLACEHOLDER_ADVISORY_ID};
 
     const EXAMPLE_RUSTSEC_ID: &str = "RUSTSEC-2018-0001";
     const EXAMPLE_CVE_ID: &str = "CVE-2017-1000168";
+    const EXAMPLE_GHSA_ID: &str = "GHSA-4mmc-49vf-jmcp";
     const EXAMPLE_TALOS_ID: &str = "TALOS-2017-0468";
     const EXAMPLE_UNKNOWN_ID: &str = "Anonymous-42";
 
@@ -235,6 +324,17 @@ mod tests {
             rustsec_id.url().unwrap(),
             "https://rustsec.org/advisories/RUSTSEC-2018-0001"
         );
+        assert_eq!(
+            rustsec_id.permalink().unwrap().as_str(),
+            "https://rustsec.org/advisories/RUSTSEC-2018-0001.html"
+        );
+        assert_eq!(
+            rustsec_id
+                .permalink_with("https://mirror.example.com/advisories/")
+                .unwrap()
+                .as_str(),
+            "https://mirror.example.com/advisories/RUSTSEC-2018-0001.html"
+        );
     }
 
     // The RUSTSEC-0000-0000 ID is a placeholder we need to treat as valid
@@ -244,6 +344,7 @@ mod tests {
         assert!(rustsec_id.is_rustsec());
         assert!(rustsec_id.year().is_none());
         assert!(rustsec_id.url().is_none());
+        assert!(rustsec_id.permalink().is_none());
     }
 
     #[test]
@@ -251,12 +352,27 @@ mod tests {
         let cve_id = AdvisoryId::new(EXAMPLE_CVE_ID).unwrap();
         assert!(cve_id.is_cve());
         assert_eq!(cve_id.year().unwrap(), 2017);
+        assert_eq!(cve_id.number().unwrap(), 1_000_168);
         assert_eq!(
             cve_id.url().unwrap(),
             "https://cve.mitre.org/cgi-bin/cvename.cgi?name=CVE-2017-1000168"
         );
     }
 
+    #[test]
+    fn ghsa_id_test() {
+        let ghsa_id = AdvisoryId::new(EXAMPLE_GHSA_ID).unwrap();
+        assert!(ghsa_id.is_ghsa());
+        assert!(ghsa_id.year().is_none());
+        assert_eq!(
+            ghsa_id.url().unwrap(),
+            "https://github.com/advisories/GHSA-4mmc-49vf-jmcp"
+        );
+
+        assert!(AdvisoryId::new("GHSA-4mmc-49vf").is_err());
+        assert!(AdvisoryId::new("GHSA-4MMC-49VF-JMCP").is_err());
+    }
+
     #[test]
     fn talos_id_test() {
         let talos_id = AdvisoryId::new(EXAMPLE_TALOS_ID).unwrap();
@@ -268,6 +384,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn id_kind_test() {
+        for &(id, kind) in &[
+            (EXAMPLE_RUSTSEC_ID, AdvisoryIdKind::RUSTSEC),
+            (EXAMPLE_CVE_ID, AdvisoryIdKind::CVE),
+            (EXAMPLE_GHSA_ID, AdvisoryIdKind::GHSA),
+            (EXAMPLE_TALOS_ID, AdvisoryIdKind::TALOS),
+            (EXAMPLE_UNKNOWN_ID, AdvisoryIdKind::Unknown),
+        ] {
+            assert_eq!(id.parse::<Id>().unwrap().kind(), kind);
+        }
+
+        assert!("CVE-2017-".parse::<Id>().is_err());
+        assert!("RUSTSEC-2018-0001-1".parse::<Id>().is_err());
+    }
+
+    #[test]
+    fn id_ordering_test() {
+        let mut ids = [
+            "RUSTSEC-2019-0001",
+            "RUSTSEC-2018-0010",
+            "RUSTSEC-2018-0002",
+            "RUSTSEC-2018-10000",
+        ]
+        .iter()
+        .map(|id| id.parse::<Id>().unwrap())
+        .collect::<Vec<_>>();
+
+        ids.sort();
+
+        assert_eq!(
+            ids.iter().map(|id| id.as_str()).collect::<Vec<_>>(),
+            [
+                "RUSTSEC-2018-0002",
+                "RUSTSEC-2018-0010",
+                "RUSTSEC-2018-10000",
+                "RUSTSEC-2019-0001"
+            ]
+        );
+    }
+
     #[test]
     fn unknown_id_test() {
         let unknown_id = AdvisoryId::new(EXAMPLE_UNKNOWN_ID).unwrap();
@@ -0,0 +1,76 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::Error;
+
+/// Kinds of informational advisories, i.e. ones which don't describe a
+/// vulnerability but are still worth warning about
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Informational {
+    /// Security-related notices about a crate
+    Notice,
+
+    /// Crates which are no longer maintained
+    Unmaintained,
+
+    /// Crates whose APIs are unsound (i.e. allow undefined behavior from safe code)
+    Unsound,
+
+    /// Kinds of informational advisories we don't know about yet
+    Other(String),
+}
+
+impl Informational {
+    /// Get a string representing this kind of informational advisory
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Informational::Notice => "notice",
+            Informational::Unmaintained => "unmaintained",
+            Informational::Unsound => "unsound",
+            Informational::Other(ref other) => other,
+        }
+    }
+
+    /// Is this an unmaintained crate advisory?
+    pub fn is_unmaintained(&self) -> bool {
+        *self == Informational::Unmaintained
+    }
+
+    /// Is this an unsound crate advisory?
+    pub fn is_unsound(&self) -> bool {
+        *self == Informational::Unsound
+    }
+}
+
+impl FromStr for Informational {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Ok(match string {
+            "notice" => Informational::Notice,
+            "unmaintained" => Informational::Unmaintained,
+            "unsound" => Informational::Unsound,
+            other => Informational::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for Informational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Informational {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Informational {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
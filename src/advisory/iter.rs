@@ -2,7 +2,7 @@ use std::collections::btree_map;
 
 use super::{Advisory, AdvisoryId};
 
-/// Advisory iterator
+/// Advisory iterator, yielding advisories in order of their IDs
 pub struct Iter<'a>(pub(crate) btree_map::Iter<'a, AdvisoryId, Advisory>);
 
 impl<'a> Iterator for Iter<'a> {
@@ -22,3 +22,36 @@ impl<'a> ExactSizeIterator for Iter<'a> {
         self.0.len()
     }
 }
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<&'a Advisory> {
+        self.0.next_back().map(|(_, adv)| adv)
+    }
+}
+
+/// Owning advisory iterator, yielding advisories in order of their IDs
+pub struct IntoIter(pub(crate) btree_map::IntoIter<AdvisoryId, Advisory>);
+
+impl Iterator for IntoIter {
+    type Item = Advisory;
+
+    fn next(&mut self) -> Option<Advisory> {
+        self.0.next().map(|(_, adv)| adv)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Advisory> {
+        self.0.next_back().map(|(_, adv)| adv)
+    }
+}
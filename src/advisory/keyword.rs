@@ -1,16 +1,32 @@
 use serde::{de::Error as DeError, Deserialize, Deserializer};
 
-use error::Error;
+use error::{Error, ErrorKind};
 
 /// Keywords on advisories, similar to Cargo keywords
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
 pub struct Keyword(String);
 
 impl Keyword {
-    /// Create a new keyword
-    // TODO: validate keywords according to Cargo-like rules
+    /// Create a new keyword, ensuring it follows Cargo-like rules: it must
+    /// start with a letter or digit and contain only ASCII letters, digits,
+    /// `-`, `_`, or `+`
     pub fn new<S: Into<String>>(keyword: S) -> Result<Self, Error> {
-        Ok(Keyword(keyword.into()))
+        let keyword = keyword.into();
+
+        let valid = keyword
+            .chars()
+            .next()
+            .map(|c| c.is_ascii_alphanumeric())
+            .unwrap_or(false)
+            && keyword
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '+');
+
+        if !valid {
+            fail!(ErrorKind::Parse, "invalid keyword: {:?}", keyword);
+        }
+
+        Ok(Keyword(keyword))
     }
 
     /// Borrow this keyword as a string slice
@@ -25,3 +41,17 @@ impl<'de> Deserialize<'de> for Keyword {
             .map_err(|e| D::Error::custom(format!("{}", e)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Keyword;
+
+    #[test]
+    fn keyword_test() {
+        assert_eq!(Keyword::new("use-after-free").unwrap().as_str(), "use-after-free");
+
+        for invalid in &["", "-ssl", "remote code execution", "\"quoted\""] {
+            assert!(Keyword::new(*invalid).is_err(), "{}", invalid);
+        }
+    }
+}
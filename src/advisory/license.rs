@@ -0,0 +1,64 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// Licenses advisories can be distributed under, identified by their SPDX
+/// expressions. Advisories are CC0-licensed unless they say otherwise (e.g.
+/// ones imported from a database which requires attribution).
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum License {
+    /// Creative Commons Zero v1.0 Universal (i.e. public domain)
+    #[default]
+    Cc0,
+
+    /// Creative Commons Attribution 4.0 International
+    CcBy40,
+}
+
+impl License {
+    /// Get the SPDX expression for this license
+    pub fn as_str(self) -> &'static str {
+        match self {
+            License::Cc0 => "CC0-1.0",
+            License::CcBy40 => "CC-BY-4.0",
+        }
+    }
+
+    /// Does redistributing content under this license require attribution?
+    pub fn requires_attribution(self) -> bool {
+        self == License::CcBy40
+    }
+}
+
+impl FromStr for License {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        match string {
+            "CC0-1.0" => Ok(License::Cc0),
+            "CC-BY-4.0" => Ok(License::CcBy40),
+            other => Err(err!(ErrorKind::Parse, "unknown license: {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for License {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
@@ -0,0 +1,444 @@
+//! Linter for advisories, checking for problems beyond those which prevent
+//! them from parsing (e.g. for use by advisory DB contribution tooling)
+
+#[cfg(feature = "chrono")]
+use chrono::Utc;
+use semver::{Version, VersionReq};
+use std::collections::BTreeMap;
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "chrono")]
+use super::Date;
+use super::{Advisory, AdvisoryId, Collection};
+use error::Error;
+
+/// How serious a lint is
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum LintLevel {
+    /// Problems which should be fixed, but don't make the advisory invalid
+    Warning,
+
+    /// Problems which make the advisory invalid
+    Error,
+}
+
+/// A problem found in an advisory by the linter
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lint {
+    /// How serious the problem is
+    pub level: LintLevel,
+
+    /// Name of the field with the problem (e.g. `date`)
+    pub field: &'static str,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Lint {
+    /// Is this an error (rather than a warning)?
+    pub fn is_error(&self) -> bool {
+        self.level == LintLevel::Error
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = match self.level {
+            LintLevel::Warning => "warning",
+            LintLevel::Error => "error",
+        };
+
+        write!(f, "{}: {}: {}", level, self.field, self.message)
+    }
+}
+
+/// Lint an advisory file, checking its name and location match the advisory
+/// in it along with everything `Advisory::lint` checks. Fails if the
+/// file can't be read or parsed.
+pub fn lint_file<P: AsRef<Path>>(path: P) -> Result<Vec<Lint>, Error> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    let advisory = if path.extension() == Some(OsStr::new("md")) {
+        Advisory::from_markdown(&contents)?
+    } else {
+        Advisory::from_toml(&contents)?
+    };
+
+    let mut lints = lint_path(&advisory, path);
+    lints.extend(lint(&advisory));
+    Ok(lints)
+}
+
+/// Check an advisory file is named after the advisory and is in the
+/// directory of its package, within a collection's directory (see
+/// `Advisory::lint_path`)
+pub(crate) fn lint_path(advisory: &Advisory, path: &Path) -> Vec<Lint> {
+    let mut lints = vec![];
+    let extension = if path.extension() == Some(OsStr::new("md")) {
+        "md"
+    } else {
+        "toml"
+    };
+    let expected_filename = format!("{}.{}", advisory.id, extension);
+
+    if path.file_name() != Some(OsStr::new(&expected_filename)) {
+        lints.push(error(
+            "id",
+            format!("expected file to be named {}", expected_filename),
+        ));
+    }
+
+    let parent_dir = path.parent().and_then(|parent| parent.file_name());
+
+    if parent_dir != Some(OsStr::new(advisory.package.as_str())) {
+        lints.push(error(
+            "package",
+            format!("expected file to be in the {} directory", advisory.package),
+        ));
+    }
+
+    let collection_dir = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str);
+
+    if collection_dir.and_then(|dir| dir.parse::<Collection>().ok()).is_none() {
+        let dirs = Collection::all()
+            .iter()
+            .map(|collection| format!("{}/", collection))
+            .collect::<Vec<_>>();
+
+        lints.push(error(
+            "package",
+            format!(
+                "expected the {} directory to be in one of: {}",
+                advisory.package,
+                dirs.join(", ")
+            ),
+        ));
+    }
+
+    lints
+}
+
+/// Lint an advisory (see `Advisory::lint`)
+pub(crate) fn lint(advisory: &Advisory) -> Vec<Lint> {
+    let mut lints = vec![];
+
+    if !advisory.id.is_rustsec() {
+        lints.push(error("id", format!("not a RUSTSEC ID: {}", advisory.id)));
+    } else if let Some(year) = advisory.id.year() {
+        if !advisory.date.as_str().starts_with(&year.to_string()) {
+            lints.push(warning(
+                "date",
+                format!("doesn't match the year in {}", advisory.id),
+            ));
+        }
+    }
+
+    if advisory.title.trim().is_empty() {
+        lints.push(error("title", "missing title".to_owned()));
+    }
+
+    if advisory.description.trim().is_empty() {
+        lints.push(error("description", "missing description".to_owned()));
+    }
+
+    #[cfg(feature = "chrono")]
+    {
+        // Every allowed date is in the past once today is after `YEAR_MAX`
+        if let Ok(today) = Date::try_from(&Utc::now()) {
+            if advisory.date > today {
+                lints.push(error("date", "in the future".to_owned()));
+            }
+        }
+    }
+
+    if let Some(ref withdrawn) = advisory.withdrawn {
+        if withdrawn < &advisory.date {
+            lints.push(error("withdrawn", "before the advisory's date".to_owned()));
+        }
+    }
+
+    if advisory.versions.patched.is_empty()
+        && !advisory.is_informational()
+        && !advisory.is_withdrawn()
+    {
+        lints.push(warning(
+            "patched_versions",
+            "no patched versions (is this crate unmaintained?)".to_owned(),
+        ));
+    }
+
+    if let Some(version) = overlapping_version(advisory) {
+        lints.push(error(
+            "unaffected_versions",
+            format!("overlaps with patched_versions (e.g. at {})", version),
+        ));
+    }
+
+    if let Some(ref url) = advisory.url {
+        if !url.is_https() {
+            lints.push(error("url", format!("expected an https:// URL: {}", url)));
+        }
+    }
+
+    for reference in &advisory.references {
+        if !reference.url.is_https() {
+            lints.push(error(
+                "references",
+                format!("expected an https:// URL: {}", reference.url),
+            ));
+        }
+    }
+
+    for alias in &advisory.aliases {
+        if alias.is_unknown() {
+            lints.push(warning("aliases", format!("unknown kind of ID: {}", alias)));
+        }
+    }
+
+    for (i, keyword) in advisory.keywords.iter().enumerate() {
+        if advisory.keywords[..i].contains(keyword) {
+            lints.push(warning(
+                "keywords",
+                format!("duplicate keyword: {}", keyword.as_str()),
+            ));
+        }
+    }
+
+    lints
+}
+
+/// Find likely duplicates among advisories (see
+/// `AdvisoryDatabase::lint_duplicates`), reporting each on the advisory with
+/// the later ID
+pub(crate) fn lint_duplicates<'a, I>(advisories: I) -> BTreeMap<AdvisoryId, Vec<Lint>>
+where
+    I: IntoIterator<Item = &'a Advisory>,
+{
+    let mut advisories = advisories
+        .into_iter()
+        .filter(|advisory| !advisory.is_withdrawn())
+        .collect::<Vec<_>>();
+
+    advisories.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut lints: BTreeMap<AdvisoryId, Vec<Lint>> = BTreeMap::new();
+
+    for (i, advisory) in advisories.iter().enumerate() {
+        for earlier in &advisories[..i] {
+            let shared_alias = advisory
+                .aliases
+                .iter()
+                .find(|alias| !alias.is_unknown() && earlier.aliases.contains(alias));
+
+            let same_package =
+                advisory.package == earlier.package && advisory.collection == earlier.collection;
+
+            let overlapping = same_package && overlapping_advisories(advisory, earlier);
+
+            let lint = match shared_alias {
+                Some(alias) if overlapping => error(
+                    "aliases",
+                    format!(
+                        "likely duplicate of {} (same alias {} and overlapping affected versions)",
+                        earlier.id, alias
+                    ),
+                ),
+                Some(alias) => error(
+                    "aliases",
+                    format!("{} is also an alias of {}", alias, earlier.id),
+                ),
+                None if overlapping && advisory.title.trim() == earlier.title.trim() => warning(
+                    "title",
+                    format!(
+                        "likely duplicate of {} (same title and overlapping affected versions)",
+                        earlier.id
+                    ),
+                ),
+                None => continue,
+            };
+
+            lints.entry(advisory.id.clone()).or_default().push(lint);
+        }
+    }
+
+    lints
+}
+
+/// Is some version of the package affected by both advisories? Only the
+/// versions their requirements mention (and `0.0.0`) are checked.
+fn overlapping_advisories(a: &Advisory, b: &Advisory) -> bool {
+    let mut versions = vec![Version::new(0, 0, 0)];
+
+    for advisory in &[a, b] {
+        let spec = &advisory.versions;
+        let reqs = spec.patched.iter().chain(spec.unaffected.iter());
+        versions.extend(reqs.flat_map(mentioned_versions));
+    }
+
+    versions
+        .iter()
+        .any(|version| a.versions.is_vulnerable(version) && b.versions.is_vulnerable(version))
+}
+
+/// Create an error-level lint
+fn error(field: &'static str, message: String) -> Lint {
+    Lint {
+        level: LintLevel::Error,
+        field,
+        message,
+    }
+}
+
+/// Create a warning-level lint
+fn warning(field: &'static str, message: String) -> Lint {
+    Lint {
+        level: LintLevel::Warning,
+        field,
+        message,
+    }
+}
+
+/// Find a version which is matched by both the patched and unaffected
+/// version requirements, if any. Only the versions the requirements mention
+/// are checked, which finds overlaps between ranges sharing a boundary.
+fn overlapping_version(advisory: &Advisory) -> Option<Version> {
+    let versions = &advisory.versions;
+
+    versions
+        .patched
+        .iter()
+        .chain(versions.unaffected.iter())
+        .flat_map(mentioned_versions)
+        .find(|version| versions.is_patched(version) && versions.is_unaffected(version))
+}
+
+/// Versions mentioned in a requirement (e.g. `1.2.0` and `2.0.0` for
+/// `>= 1.2, < 2`), padded with zeros where they're partial
+fn mentioned_versions(req: &VersionReq) -> Vec<Version> {
+    req.to_string()
+        .split(',')
+        .filter_map(|predicate| {
+            let version = predicate.trim().trim_start_matches(|c: char| !c.is_ascii_digit());
+            let mut parts = version.splitn(3, '.').collect::<Vec<_>>();
+
+            while parts.len() < 3 {
+                parts.push("0");
+            }
+
+            Version::parse(&parts.join(".")).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Advisory;
+    use super::LintLevel;
+
+    #[test]
+    fn lint_test() {
+        let advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2018-0001"
+package = "a"
+date = "2019-06-01"
+withdrawn = "2019-05-01"
+url = "http://example.com/"
+aliases = ["EXAMPLE-1"]
+patched_versions = [">= 1.2.0"]
+unaffected_versions = ["<= 1.2.0"]
+title = "Title"
+description = ""
+"#,
+        )
+        .unwrap();
+
+        let lints = advisory
+            .lint()
+            .iter()
+            .map(|lint| (lint.level, lint.field))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lints,
+            [
+                (LintLevel::Warning, "date"),
+                (LintLevel::Error, "description"),
+                (LintLevel::Error, "withdrawn"),
+                (LintLevel::Error, "unaffected_versions"),
+                (LintLevel::Error, "url"),
+                (LintLevel::Warning, "aliases"),
+            ]
+        );
+
+        assert!(advisory
+            .lint_path("advisory-db/crates/a/RUSTSEC-2018-0001.toml")
+            .is_empty());
+
+        let lints = advisory
+            .lint_path("advisory-db/b/RUSTSEC-2018-0002.md")
+            .iter()
+            .map(|lint| lint.field)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lints, ["id", "package", "package"]);
+    }
+
+    #[test]
+    fn duplicates_test() {
+        let advisory = |id: &str, package: &str, alias: &str, patched: &str| {
+            Advisory::from_toml(&format!(
+                r#"[advisory]
+id = "{}"
+package = "{}"
+date = "2019-06-01"
+aliases = ["{}"]
+patched_versions = ["{}"]
+title = "Title"
+description = "Description"
+"#,
+                id, package, alias, patched
+            ))
+            .unwrap()
+        };
+
+        let advisories = [
+            advisory("RUSTSEC-2019-0001", "a", "CVE-2019-0001", ">= 1.0.0"),
+            advisory("RUSTSEC-2019-0002", "a", "CVE-2019-0001", ">= 2.0.0"),
+            advisory("RUSTSEC-2019-0003", "b", "CVE-2019-0001", ">= 1.0.0"),
+            advisory("RUSTSEC-2019-0004", "a", "CVE-2019-0004", ">= 1.0.0"),
+        ];
+
+        let lints = super::lint_duplicates(advisories.iter());
+        let messages = |id: &str| {
+            lints[&id.parse().unwrap()]
+                .iter()
+                .map(|lint| (lint.level, lint.message.as_str()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(!lints.contains_key(&"RUSTSEC-2019-0001".parse().unwrap()));
+        assert_eq!(
+            messages("RUSTSEC-2019-0002"),
+            [(
+                LintLevel::Error,
+                "likely duplicate of RUSTSEC-2019-0001 \
+                 (same alias CVE-2019-0001 and overlapping affected versions)"
+            )]
+        );
+        assert_eq!(messages("RUSTSEC-2019-0003").len(), 2);
+        assert_eq!(messages("RUSTSEC-2019-0004").len(), 2);
+        assert_eq!(messages("RUSTSEC-2019-0004")[0].0, LintLevel::Warning);
+    }
+}
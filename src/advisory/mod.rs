@@ -1,22 +1,66 @@
 //! Security advisories in the RustSec database
 
-use platforms::target::{Arch, OS};
-use semver::VersionReq;
+use platforms::{
+    self,
+    target::{Arch, OS},
+    Platform,
+};
+use semver::Version;
+use std::collections::BTreeMap;
+use std::path::Path;
+use toml;
 
+#[cfg(feature = "cve")]
+use cve;
+use cvss;
+use error::Error;
+#[cfg(feature = "osv")]
+use osv;
 use package::PackageName;
 
+mod affected;
+mod builder;
+mod category;
+mod collection;
+mod cwe;
 mod date;
+#[cfg(feature = "html")]
+mod html;
 mod id;
+mod informational;
 mod iter;
 mod keyword;
+mod license;
+mod linter;
+mod parser;
+mod patch;
+mod reference;
+mod url;
+#[cfg(any(feature = "cve", feature = "osv"))]
+pub(crate) mod ranges;
+mod versions;
+mod writer;
 
+pub use self::affected::{Affected, FunctionPath};
+pub use self::builder::AdvisoryBuilder;
+pub use self::category::Category;
+pub use self::collection::Collection;
+pub use self::cwe::Cwe;
 pub use self::date::*;
 pub use self::id::*;
-pub use self::iter::Iter;
+pub use self::informational::Informational;
+pub use self::iter::{IntoIter, Iter};
 pub use self::keyword::Keyword;
+pub use self::license::License;
+pub use self::linter::{lint_file, Lint, LintLevel};
+pub(crate) use self::linter::lint_duplicates;
+pub use self::patch::{Patch, PatchKind};
+pub use self::reference::{Reference, ReferenceKind};
+pub use self::url::Url;
+pub use self::versions::VersionSpec;
 
 /// An individual security advisory pertaining to a single vulnerability
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Advisory {
     /// Security advisory ID (e.g. RUSTSEC-YYYY-NNNN)
     pub id: AdvisoryId,
@@ -24,15 +68,26 @@ pub struct Advisory {
     /// Name of affected crate
     pub package: PackageName,
 
+    /// Collection the affected package is in (i.e. a crates.io crate or part
+    /// of the Rust toolchain), determined by which directory of the advisory
+    /// DB the advisory is stored in rather than given in the advisory itself
+    #[serde(skip)]
+    pub collection: Collection,
+
     /// Date this advisory was officially issued
     pub date: Date,
 
-    /// Versions which are patched and not vulnerable (expressed as semantic version requirements)
-    pub patched_versions: Vec<VersionReq>,
+    /// Date this advisory was withdrawn (i.e. retracted), if it has been
+    pub withdrawn: Option<Date>,
 
-    /// Versions which were never affected in the first place
-    #[serde(default)]
-    pub unaffected_versions: Vec<VersionReq>,
+    /// Kind of informational advisory this is, if it doesn't describe a
+    /// vulnerability (e.g. an unmaintained crate)
+    pub informational: Option<Informational>,
+
+    /// Versions which are patched or were never affected (i.e. the
+    /// `patched_versions` and `unaffected_versions` fields)
+    #[serde(flatten)]
+    pub versions: VersionSpec,
 
     /// CPU architectures that this vulnerability is specific to
     pub affected_arch: Option<Vec<Arch>>,
@@ -40,26 +95,262 @@ pub struct Advisory {
     /// Operating systems that this vulnerability is specific to
     pub affected_os: Option<Vec<OS>>,
 
-    /// Advisory IDs in other databases which point to the same advisory
+    /// Details of what exactly is affected (e.g. which functions)
+    pub affected: Option<Affected>,
+
+    /// Advisory IDs in other databases (e.g. CVE or GHSA IDs) which point to the same advisory
     #[serde(default)]
     pub aliases: Vec<AdvisoryId>,
 
-    /// Advisory IDs which are related to this advisory
+    /// Advisory IDs in other crates or ecosystems for vulnerabilities which
+    /// stem from the same root cause as this one (unlike `aliases`, these are
+    /// distinct vulnerabilities)
+    #[serde(default)]
+    pub related: Vec<AdvisoryId>,
+
+    /// Links to more information about this advisory beyond its `url` (e.g.
+    /// the issue it was reported in, the PR fixing it, or an upstream
+    /// advisory). Advisory IDs given here are converted to their URLs.
+    #[serde(default)]
+    pub references: Vec<Reference>,
+
+    /// Links to the upstream fixes for this advisory (e.g. the commit fixing
+    /// it, the PR it was fixed in, or backports of the fix)
+    #[serde(default)]
+    pub patches: Vec<Patch>,
+
+    /// Categories this vulnerability falls under, from a curated list
+    #[serde(default)]
+    pub categories: Vec<Category>,
+
+    /// CWE identifiers of the weaknesses which lead to this vulnerability
+    /// (e.g. `CWE-416` for use-after-free)
     #[serde(default)]
-    pub references: Vec<AdvisoryId>,
+    pub cwe: Vec<Cwe>,
 
     /// Freeform keywords which succinctly describe this vulnerability (e.g. "ssl", "rce", "xss")
     #[serde(default)]
     pub keywords: Vec<Keyword>,
 
     /// URL with an announcement (e.g. blog post, PR, disclosure issue, CVE)
-    pub url: Option<String>,
+    pub url: Option<Url>,
+
+    /// CVSS v3 or v4 vector describing the severity of the vulnerability
+    pub cvss: Option<cvss::Cvss>,
+
+    /// Severity of the vulnerability, if given explicitly rather than
+    /// derived from `cvss` (see `Advisory::severity`)
+    #[serde(rename = "severity")]
+    pub explicit_severity: Option<cvss::Severity>,
+
+    /// SPDX expression for the license this advisory is distributed under,
+    /// if it isn't the default of CC0 (see `Advisory::license`)
+    #[serde(rename = "license")]
+    pub explicit_license: Option<License>,
 
     /// One-liner description of a vulnerability
     pub title: String,
 
     /// Extended description of a vulnerability
     pub description: String,
+
+    /// Fields this version of the library doesn't know about (e.g. ones added
+    /// to the advisory format since), kept so they survive re-serialization
+    #[serde(flatten)]
+    pub(crate) unknown_fields: BTreeMap<String, toml::Value>,
+}
+
+// TOML values are only `PartialEq` since they may be floats, but NaN is
+// never a meaningful value for an advisory field
+impl Eq for Advisory {}
+
+impl Advisory {
+    /// Parse an advisory in the legacy format (i.e. a `.toml` file)
+    pub fn from_toml(string: &str) -> Result<Self, Error> {
+        parser::parse_toml(string)
+    }
+
+    /// Parse an advisory in the V3 format (i.e. a `.md` file with TOML front
+    /// matter in a ```` ```toml ```` block, followed by a `# Title` and description)
+    pub fn from_markdown(string: &str) -> Result<Self, Error> {
+        parser::parse_markdown(string)
+    }
+
+    /// Parse an advisory in the V3 format, leaving its description empty (see
+    /// `LoadOptions::lazy_descriptions`)
+    pub(crate) fn from_markdown_metadata(string: &str) -> Result<Self, Error> {
+        parser::parse_markdown_with(string, false)
+    }
+
+    /// Generate a skeleton for a new advisory about the given package in the
+    /// V3 Markdown format, with a placeholder ID and comments describing
+    /// each of the fields still to be filled in
+    pub fn template(package: &PackageName, date: &Date) -> String {
+        writer::template(package, date)
+    }
+
+    /// Convert an OSV entry about a crates.io package into an advisory. The
+    /// versions outside of the entry's affected ranges become patched
+    /// versions, except for those before the first range, which become
+    /// unaffected versions.
+    #[cfg(feature = "osv")]
+    pub fn from_osv(entry: &osv::Entry) -> Result<Self, Error> {
+        osv::to_advisory(entry)
+    }
+
+    /// Get the canonical link to this advisory on rustsec.org (for use in
+    /// reports), unlike `url` which links to more information elsewhere
+    pub fn permalink(&self) -> Option<Url> {
+        self.id.permalink()
+    }
+
+    /// Get the link to this advisory on a mirror of rustsec.org with the given
+    /// base URL (see `Id::permalink_with`)
+    pub fn permalink_with(&self, base_url: &str) -> Option<Url> {
+        self.id.permalink_with(base_url)
+    }
+
+    /// Has this advisory been withdrawn?
+    pub fn is_withdrawn(&self) -> bool {
+        self.withdrawn.is_some()
+    }
+
+    /// Date this advisory was last modified, as far as can be told from the
+    /// advisory itself (i.e. when it was withdrawn, if it has been, and
+    /// otherwise when it was issued)
+    pub fn modified(&self) -> &Date {
+        self.withdrawn
+            .as_ref()
+            .filter(|withdrawn| **withdrawn > self.date)
+            .unwrap_or(&self.date)
+    }
+
+    /// Is this an informational advisory rather than a vulnerability?
+    pub fn is_informational(&self) -> bool {
+        self.informational.is_some()
+    }
+
+    /// Get the CPU architectures this vulnerability is specific to (from
+    /// either `affected_arch` or `affected.arch`), which is empty if it
+    /// affects all of them
+    pub fn affected_arch(&self) -> Vec<Arch> {
+        let mut arch = self.affected_arch.clone().unwrap_or_default();
+        arch.extend(self.affected.iter().flat_map(|affected| affected.arch.iter()));
+        arch
+    }
+
+    /// Get the operating systems this vulnerability is specific to (from
+    /// either `affected_os` or `affected.os`), which is empty if it affects
+    /// all of them
+    pub fn affected_os(&self) -> Vec<OS> {
+        let mut os = self.affected_os.clone().unwrap_or_default();
+        os.extend(self.affected.iter().flat_map(|affected| affected.os.iter()));
+        os
+    }
+
+    /// Does this vulnerability affect the given platform?
+    pub fn affects_platform(&self, platform: &Platform) -> bool {
+        let arch = self.affected_arch();
+        let os = self.affected_os();
+
+        (arch.is_empty() || arch.contains(&platform.target_arch))
+            && (os.is_empty() || os.contains(&platform.target_os))
+    }
+
+    /// Does this vulnerability affect the platform with the given target
+    /// triple (e.g. `x86_64-pc-windows-msvc`)? Unknown targets are assumed
+    /// to be affected, since that can't be ruled out
+    pub fn affects_target(&self, target_triple: &str) -> bool {
+        match platforms::find(target_triple) {
+            Some(platform) => self.affects_platform(platform),
+            None => true,
+        }
+    }
+
+    /// Is the crate affected with the given Cargo features enabled? (i.e. is
+    /// one of them in `affected.features`, or is that empty?)
+    pub fn affects_features<S: AsRef<str>>(&self, enabled_features: &[S]) -> bool {
+        self.affected
+            .as_ref()
+            .map(|affected| affected.affects_features(enabled_features))
+            .unwrap_or(true)
+    }
+
+    /// Does this vulnerability affect the given version of the Rust toolchain
+    /// (according to `affected.rust_version`)?
+    pub fn affects_rust_version(&self, rust_version: &Version) -> bool {
+        self.affected
+            .as_ref()
+            .map(|affected| affected.affects_rust_version(rust_version))
+            .unwrap_or(true)
+    }
+
+    /// Get the severity of this advisory: the explicitly given one if present,
+    /// otherwise the one derived from its CVSS score
+    pub fn severity(&self) -> Option<cvss::Severity> {
+        self.explicit_severity
+            .or_else(|| self.cvss.as_ref().map(|cvss| cvss.severity()))
+    }
+
+    /// Get the license this advisory is distributed under: the explicitly
+    /// given one if present, otherwise CC0
+    pub fn license(&self) -> License {
+        self.explicit_license.unwrap_or_default()
+    }
+
+    /// Get the fields of this advisory which this version of the library
+    /// doesn't know about, keyed by name
+    pub fn unknown_fields(&self) -> &BTreeMap<String, toml::Value> {
+        &self.unknown_fields
+    }
+
+    /// Check this advisory for problems (e.g. missing fields, dates which
+    /// don't make sense, or overlapping version requirements)
+    pub fn lint(&self) -> Vec<Lint> {
+        linter::lint(self)
+    }
+
+    /// Check this advisory's file at the given path is named after it (e.g.
+    /// `RUSTSEC-2019-0001.md`) and is in its package's directory within a
+    /// collection's (e.g. `crates/mycrate/`)
+    pub fn lint_path<P: AsRef<Path>>(&self, path: P) -> Vec<Lint> {
+        linter::lint_path(self, path.as_ref())
+    }
+
+    /// Serialize this advisory in the legacy TOML format, formatted in the
+    /// style of the advisory DB
+    pub fn to_toml_string(&self) -> String {
+        writer::to_toml(self)
+    }
+
+    /// Serialize this advisory in the V3 Markdown format, formatted in the
+    /// style of the advisory DB
+    pub fn to_markdown_string(&self) -> String {
+        writer::to_markdown(self)
+    }
+
+    /// Convert this advisory into a CVE JSON 5 record for submission by the
+    /// CNA with the given organization UUID. Fails if the advisory doesn't
+    /// have a CVE ID (i.e. as an alias), or has been withdrawn.
+    #[cfg(feature = "cve")]
+    pub fn to_cve_record(&self, org_id: &str) -> Result<cve::Record, Error> {
+        cve::from_advisory(self, org_id)
+    }
+
+    /// Convert this advisory into an OSV entry, with the versions it affects
+    /// expressed as `SEMVER` events. Fails if any of its version
+    /// requirements can't be expressed that way.
+    #[cfg(feature = "osv")]
+    pub fn to_osv(&self) -> Result<osv::Entry, Error> {
+        osv::from_advisory(self)
+    }
+
+    /// Render this advisory's title and Markdown description as sanitized
+    /// HTML, i.e. with raw HTML escaped and unsafe links removed
+    #[cfg(feature = "html")]
+    pub fn to_html(&self) -> String {
+        html::to_html(self)
+    }
 }
 
 /// Wrapper struct around advisories since they're each in a table
@@ -0,0 +1,138 @@
+//! Parsers for the advisory file formats: legacy `.toml` files, and V3 `.md`
+//! files containing TOML front matter followed by a Markdown description
+
+use toml;
+
+use super::{Advisory, AdvisoryWrapper};
+use error::{Error, ErrorKind};
+
+/// Fence which starts the TOML front matter of a V3 advisory
+const FRONT_MATTER_START: &str = "```toml";
+
+/// Fence which ends the TOML front matter of a V3 advisory
+const FRONT_MATTER_END: &str = "```";
+
+/// Parse a legacy advisory, i.e. a TOML document with an `[advisory]` table
+pub(crate) fn parse_toml(string: &str) -> Result<Advisory, Error> {
+    let AdvisoryWrapper { advisory } = toml::from_str(string)?;
+    Ok(advisory)
+}
+
+/// Parse a V3 advisory, i.e. fenced TOML front matter followed by a Markdown
+/// description whose first line is a `# Title` heading
+pub(crate) fn parse_markdown(string: &str) -> Result<Advisory, Error> {
+    parse_markdown_with(string, true)
+}
+
+/// Parse a V3 advisory, leaving its description empty unless asked for (it's
+/// usually the bulk of the advisory, and most uses only need the metadata)
+pub(crate) fn parse_markdown_with(string: &str, with_description: bool) -> Result<Advisory, Error> {
+    let string = string.trim_start();
+
+    if !string.starts_with(FRONT_MATTER_START) {
+        fail!(
+            ErrorKind::Parse,
+            "expected advisory to start with {}",
+            FRONT_MATTER_START
+        );
+    }
+
+    let front_matter_and_body = &string[FRONT_MATTER_START.len()..];
+
+    let front_matter_len = front_matter_and_body
+        .find(&format!("\n{}", FRONT_MATTER_END))
+        .ok_or_else(|| err!(ErrorKind::Parse, "unterminated advisory front matter"))?;
+
+    let front_matter = &front_matter_and_body[..front_matter_len];
+    let body = front_matter_and_body[front_matter_len + 1 + FRONT_MATTER_END.len()..].trim();
+
+    if !body.starts_with("# ") {
+        fail!(
+            ErrorKind::Parse,
+            "expected advisory description to start with a # title"
+        );
+    }
+
+    let (title, description) = match body.find('\n') {
+        Some(pos) if with_description => (&body[2..pos], body[pos..].trim()),
+        Some(pos) => (&body[2..pos], ""),
+        None => (&body[2..], ""),
+    };
+
+    let mut value: toml::Value = toml::from_str(front_matter)?;
+
+    {
+        let advisory = value
+            .get_mut("advisory")
+            .and_then(|advisory| advisory.as_table_mut())
+            .ok_or_else(|| err!(ErrorKind::Parse, "missing [advisory] table"))?;
+
+        for &(key, field) in &[("title", title.trim()), ("description", description)] {
+            if advisory.contains_key(key) {
+                fail!(
+                    ErrorKind::Parse,
+                    "{} must be given in the Markdown body of the advisory",
+                    key
+                );
+            }
+
+            advisory.insert(key.to_owned(), toml::Value::String(field.to_owned()));
+        }
+    }
+
+    let AdvisoryWrapper { advisory } = value.try_into()?;
+    Ok(advisory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_markdown, parse_toml};
+
+    #[test]
+    fn parse_markdown_test() {
+        let advisory = parse_markdown(
+            "```toml
+[advisory]
+id = \"RUSTSEC-2018-0001\"
+package = \"a\"
+date = \"2018-06-01\"
+patched_versions = [\">= 1.0\"]
+```
+
+# Memory safety issue
+
+Some *Markdown*.
+
+More Markdown.
+",
+        )
+        .unwrap();
+
+        assert_eq!(advisory.id.as_str(), "RUSTSEC-2018-0001");
+        assert_eq!(advisory.title, "Memory safety issue");
+        assert_eq!(advisory.description, "Some *Markdown*.\n\nMore Markdown.");
+        assert_eq!(advisory.severity(), None);
+
+        let legacy = parse_toml(
+            "[advisory]
+id = \"RUSTSEC-2018-0001\"
+package = \"a\"
+date = \"2018-06-01\"
+patched_versions = [\">= 1.0\"]
+title = \"Memory safety issue\"
+description = \"\"\"
+Some *Markdown*.
+
+More Markdown.
+\"\"\"
+",
+        )
+        .unwrap();
+
+        assert_eq!(advisory.id, legacy.id);
+        assert_eq!(advisory.title, legacy.title);
+
+        assert!(parse_markdown("[advisory]\nid = \"RUSTSEC-2018-0001\"").is_err());
+        assert!(parse_markdown("```toml\n[advisory]\n```\nNo title").is_err());
+    }
+}
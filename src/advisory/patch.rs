@@ -0,0 +1,176 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::Url;
+use error::{Error, ErrorKind};
+
+/// Link to an upstream fix for an advisory (e.g. the commit fixing it, or the
+/// PR it was fixed in), so remediation tooling can point users at the fix
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize)]
+pub struct Patch {
+    /// What kind of fix the link is to
+    pub kind: PatchKind,
+
+    /// URL of the fix
+    pub url: Url,
+}
+
+impl Patch {
+    /// Create a patch linking to the given URL, guessing its kind from the URL
+    /// (i.e. GitHub `/pull/` and GitLab `/merge_requests/` URLs are PRs)
+    pub fn new(url: Url) -> Self {
+        Patch {
+            kind: PatchKind::guess(url.as_str()),
+            url,
+        }
+    }
+
+    /// Get the ID of the commit this patch links to, if it's a `/commit/` URL
+    pub fn commit_id(&self) -> Option<&str> {
+        let (_, rest) = self.split("/commit/")?;
+        let id = rest.split(['/', '?', '#']).next()?;
+
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(id)
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of the PR (or merge request) this patch links to
+    pub fn pull_request_number(&self) -> Option<u64> {
+        let (_, rest) = self
+            .split("/pull/")
+            .or_else(|| self.split("/merge_requests/"))?;
+
+        rest.split(['/', '?', '#']).next()?.parse().ok()
+    }
+
+    /// Get the URL of the repository this patch was made to, if it's a commit
+    /// or PR URL (e.g. `https://github.com/owner/repo`)
+    pub fn repository_url(&self) -> Option<&str> {
+        ["/-/commit/", "/commit/", "/pull/", "/-/merge_requests/"]
+            .iter()
+            .filter_map(|separator| self.split(separator))
+            .map(|(repository, _)| repository)
+            .next()
+    }
+
+    /// Can this patch be written as just its URL, i.e. is its kind the one
+    /// which would be guessed from its URL?
+    pub(crate) fn is_guessable(&self) -> bool {
+        self.kind == PatchKind::guess(self.url.as_str())
+    }
+
+    /// Split this patch's URL at the first occurrence of the given separator
+    fn split(&self, separator: &str) -> Option<(&str, &str)> {
+        let url = self.url.as_str();
+        let index = url.find(separator)?;
+        Some((&url[..index], &url[index + separator.len()..]))
+    }
+}
+
+/// Patches can be given as a URL (with the kind guessed from it) or a
+/// `{ kind, url }` table
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPatch {
+    Url(Url),
+    Table { kind: PatchKind, url: Url },
+}
+
+impl<'de> Deserialize<'de> for Patch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match RawPatch::deserialize(deserializer)? {
+            RawPatch::Url(url) => Patch::new(url),
+            RawPatch::Table { kind, url } => Patch { kind, url },
+        })
+    }
+}
+
+/// Kinds of patches
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum PatchKind {
+    /// A commit fixing the vulnerability upstream
+    Commit,
+
+    /// A PR (or merge request) fixing the vulnerability upstream
+    PullRequest,
+
+    /// A commit backporting the fix to an older release series
+    Backport,
+}
+
+impl PatchKind {
+    /// Get a string representing this kind of patch
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PatchKind::Commit => "commit",
+            PatchKind::PullRequest => "pull-request",
+            PatchKind::Backport => "backport",
+        }
+    }
+
+    /// Guess the kind of the patch with the given URL from its path (which
+    /// can't tell backports apart from other commits)
+    pub fn guess(url: &str) -> Self {
+        if url.contains("/pull/") || url.contains("/merge_requests/") {
+            PatchKind::PullRequest
+        } else {
+            PatchKind::Commit
+        }
+    }
+}
+
+impl FromStr for PatchKind {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        match string {
+            "commit" => Ok(PatchKind::Commit),
+            "pull-request" => Ok(PatchKind::PullRequest),
+            "backport" => Ok(PatchKind::Backport),
+            other => Err(err!(ErrorKind::Parse, "unknown kind of patch: {:?}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for PatchKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PatchKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Patch, PatchKind, Url};
+
+    #[test]
+    fn patch_test() {
+        let commit = Patch::new(Url::new("https://github.com/a/b/commit/0123abc").unwrap());
+        assert_eq!(commit.kind, PatchKind::Commit);
+        assert_eq!(commit.commit_id(), Some("0123abc"));
+        assert_eq!(commit.pull_request_number(), None);
+        assert_eq!(commit.repository_url(), Some("https://github.com/a/b"));
+
+        let pr = Patch::new(Url::new("https://gitlab.com/a/b/-/merge_requests/42").unwrap());
+        assert_eq!(pr.kind, PatchKind::PullRequest);
+        assert_eq!(pr.commit_id(), None);
+        assert_eq!(pr.pull_request_number(), Some(42));
+        assert_eq!(pr.repository_url(), Some("https://gitlab.com/a/b"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Conversion of semver requirements into the ranges of versions they
+//! match, so the versions affected by an advisory can be expressed as
+//! ranges (e.g. OSV `SEMVER` events)
+
+use semver::{Identifier, Version, VersionReq};
+
+use error::{Error, ErrorKind};
+
+/// Half-open range of versions, from `start` (inclusive) up to `end`
+/// (exclusive), or with no upper bound if `end` is `None`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Range {
+    pub start: Version,
+    pub end: Option<Version>,
+}
+
+impl Range {
+    /// Range of all versions, including pre-releases
+    fn all() -> Self {
+        Range {
+            start: min_version(),
+            end: None,
+        }
+    }
+
+    /// Intersect this range with another
+    fn intersect(self, other: Range) -> Self {
+        let start = ::std::cmp::max(self.start, other.start);
+
+        let end = match (self.end, other.end) {
+            (Some(a), Some(b)) => Some(::std::cmp::min(a, b)),
+            (a, b) => a.or(b),
+        };
+
+        Range { start, end }
+    }
+
+    /// Does this range not contain any versions?
+    fn is_empty(&self) -> bool {
+        self.end
+            .as_ref()
+            .map(|end| *end <= self.start)
+            .unwrap_or(false)
+    }
+}
+
+/// The lowest possible version, i.e. `0.0.0-0`
+pub(crate) fn min_version() -> Version {
+    Version {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        pre: vec![Identifier::Numeric(0)],
+        build: vec![],
+    }
+}
+
+/// Compute the ranges of versions which aren't matched by any of the given
+/// requirements (i.e. the versions affected by an advisory, given the
+/// patched and unaffected ones), sorted and non-overlapping
+pub(crate) fn unmatched_ranges<'a, I>(reqs: I) -> Result<Vec<Range>, Error>
+where
+    I: Iterator<Item = &'a VersionReq>,
+{
+    let mut matched = vec![];
+
+    for req in reqs {
+        let range = req_range(req)?;
+
+        if !range.is_empty() {
+            matched.push(range);
+        }
+    }
+
+    matched.sort_by(|a, b| a.start.cmp(&b.start));
+
+    let mut unmatched = vec![];
+    let mut cursor = min_version();
+
+    for range in matched {
+        if range.start > cursor {
+            unmatched.push(Range {
+                start: cursor.clone(),
+                end: Some(range.start.clone()),
+            });
+        }
+
+        match range.end {
+            Some(end) => {
+                if end > cursor {
+                    cursor = end;
+                }
+            }
+            None => return Ok(unmatched),
+        }
+    }
+
+    unmatched.push(Range {
+        start: cursor,
+        end: None,
+    });
+
+    Ok(unmatched)
+}
+
+/// Compute the range of versions matched by a requirement, i.e. the
+/// intersection of the ranges of each of its comma-separated predicates
+fn req_range(req: &VersionReq) -> Result<Range, Error> {
+    let string = req.to_string();
+    let mut range = Range::all();
+
+    for predicate in string.split(',') {
+        range =
+            range.intersect(predicate_range(predicate.trim()).ok_or_else(|| {
+                err!(ErrorKind::Parse, "unsupported version requirement: {}", req)
+            })?);
+    }
+
+    Ok(range)
+}
+
+/// Compute the range of versions matched by a single predicate
+/// (e.g. `>= 1.2`), following the matching rules of the `semver` crate
+fn predicate_range(predicate: &str) -> Option<Range> {
+    let (op, rest) = [">=", "<=", ">", "<", "=", "~", "^"]
+        .iter()
+        .find(|op| predicate.starts_with(**op))
+        .map(|op| (*op, predicate[op.len()..].trim()))
+        .unwrap_or(("*", predicate));
+
+    if rest == "*" {
+        return Some(Range::all());
+    }
+
+    let partial = Partial::parse(rest)?;
+    let lower = partial.lower();
+    let next = partial.next();
+
+    let (start, end) = match op {
+        ">=" => (lower, None),
+        ">" => (next, None),
+        "<" => (min_version(), Some(lower)),
+        "<=" => (min_version(), Some(next)),
+        "=" | "*" => (lower, Some(next)),
+        "~" => match partial.minor {
+            Some(minor) => (lower, Some(release(partial.major, minor + 1, 0))),
+            None => (lower, Some(release(partial.major + 1, 0, 0))),
+        },
+        "^" => match (partial.major, partial.minor, partial.patch) {
+            (0, None, _) => (lower, Some(release(1, 0, 0))),
+            (0, Some(0), None) => (lower, Some(release(0, 1, 0))),
+            (0, Some(0), Some(patch)) => (lower, Some(release(0, 0, patch + 1))),
+            (0, Some(minor), _) => (lower, Some(release(0, minor + 1, 0))),
+            (major, _, _) => (lower, Some(release(major + 1, 0, 0))),
+        },
+        _ => unreachable!(),
+    };
+
+    Some(Range { start, end })
+}
+
+/// A possibly partial version from a predicate (e.g. `1.2`, or `1.*`)
+struct Partial {
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Partial {
+    /// Parse a partial version, returning `None` for anything we don't
+    /// understand
+    fn parse(string: &str) -> Option<Self> {
+        let (numbers, pre) = match string.find('-') {
+            Some(pos) => (&string[..pos], Some(&string[pos + 1..])),
+            None => (string, None),
+        };
+
+        let mut parts = numbers
+            .split('.')
+            .take_while(|part| *part != "*")
+            .map(|part| part.parse::<u64>().ok());
+
+        let major = parts.next()??;
+        let minor = parts.next().map_or(Some(None), |part| part.map(Some))?;
+        let patch = parts.next().map_or(Some(None), |part| part.map(Some))?;
+
+        let pre = pre
+            .map(|pre| Version::parse(&format!("0.0.0-{}", pre)).map(|v| v.pre))
+            .unwrap_or_else(|| Ok(vec![]))
+            .ok()?;
+
+        Some(Partial {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+
+    /// Lowest version matching this partial version (i.e. padded with zeros)
+    fn lower(&self) -> Version {
+        Version {
+            major: self.major,
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+            build: vec![],
+        }
+    }
+
+    /// Lowest version greater than all versions matching this one
+    fn next(&self) -> Version {
+        match (self.minor, self.patch) {
+            (None, _) => release(self.major + 1, 0, 0),
+            (Some(minor), None) => release(self.major, minor + 1, 0),
+            (Some(_), Some(_)) if !self.pre.is_empty() => {
+                // The lowest pre-release after e.g. `1.0.0-alpha` is `1.0.0-alpha.0`
+                let mut version = self.lower();
+                version.pre.push(Identifier::Numeric(0));
+                version
+            }
+            (Some(minor), Some(patch)) => release(self.major, minor, patch + 1),
+        }
+    }
+}
+
+/// Create a release (i.e. non-pre-release) version
+fn release(major: u64, minor: u64, patch: u64) -> Version {
+    Version::new(major, minor, patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{min_version, unmatched_ranges, Range};
+    use semver::{Version, VersionReq};
+
+    fn ranges(reqs: &[&str]) -> Vec<(String, Option<String>)> {
+        let reqs = reqs
+            .iter()
+            .map(|req| VersionReq::parse(req).unwrap())
+            .collect::<Vec<_>>();
+
+        unmatched_ranges(reqs.iter())
+            .unwrap()
+            .into_iter()
+            .map(|Range { start, end }| (start.to_string(), end.map(|end| end.to_string())))
+            .collect()
+    }
+
+    fn to(start: &str, end: Option<&str>) -> (String, Option<String>) {
+        (start.to_owned(), end.map(|end| end.to_owned()))
+    }
+
+    #[test]
+    fn unmatched_ranges_test() {
+        let min = min_version().to_string();
+        assert_eq!(min, "0.0.0-0");
+        assert!(Version::parse(&min).unwrap() < Version::parse("0.0.0").unwrap());
+
+        assert_eq!(ranges(&[]), [to(&min, None)]);
+        assert_eq!(ranges(&[">= 1.2.3"]), [to(&min, Some("1.2.3"))]);
+        assert_eq!(ranges(&[">= 1.2", "< 0.5"]), [to("0.5.0", Some("1.2.0"))]);
+        assert_eq!(
+            ranges(&["^0.3.2", "> 1.0.1"]),
+            [to(&min, Some("0.3.2")), to("0.4.0", Some("1.0.2"))]
+        );
+        assert_eq!(
+            ranges(&[">= 2.0.1", "~1.5.3", "<= 1.2"]),
+            [to("1.3.0", Some("1.5.3")), to("1.6.0", Some("2.0.1"))]
+        );
+        assert_eq!(ranges(&["*"]), []);
+        assert_eq!(
+            ranges(&[">= 1.0.0-beta.2"]),
+            [to(&min, Some("1.0.0-beta.2"))]
+        );
+    }
+}
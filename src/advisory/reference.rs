@@ -0,0 +1,164 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::{AdvisoryId, Url};
+use error::Error;
+
+/// Link to more information about an advisory (e.g. the issue it was
+/// reported in, or the PR which fixed it)
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize)]
+pub struct Reference {
+    /// What kind of information the link is to
+    pub kind: ReferenceKind,
+
+    /// URL of the link
+    pub url: Url,
+}
+
+impl Reference {
+    /// Create a reference to the given URL, guessing its kind from the URL
+    /// (e.g. GitHub `/pull/` URLs are fixes)
+    pub fn new(url: Url) -> Self {
+        Reference {
+            kind: ReferenceKind::guess(url.as_str()),
+            url,
+        }
+    }
+
+    /// Can this reference be written as just its URL, i.e. is its kind the
+    /// one which would be guessed from its URL?
+    pub(crate) fn is_guessable(&self) -> bool {
+        self.kind == ReferenceKind::guess(self.url.as_str())
+    }
+}
+
+/// References can be given as a URL (with the kind guessed from it), an
+/// advisory ID (for backwards compatibility), or a `{ kind, url }` table
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawReference {
+    Url(String),
+    Table { kind: ReferenceKind, url: Url },
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RawReference::deserialize(deserializer)? {
+            RawReference::Url(string) => {
+                if !string.contains(':') {
+                    let id = AdvisoryId::new(string.as_str())
+                        .map_err(|e| D::Error::custom(format!("{}", e)))?;
+
+                    let url = id.url().ok_or_else(|| {
+                        D::Error::custom(format!("invalid reference: {}", string))
+                    })?;
+
+                    return Ok(Reference {
+                        kind: ReferenceKind::Advisory,
+                        url: Url::new(url).map_err(|e| D::Error::custom(format!("{}", e)))?,
+                    });
+                }
+
+                Url::new(string)
+                    .map(Reference::new)
+                    .map_err(|e| D::Error::custom(format!("{}", e)))
+            }
+            RawReference::Table { kind, url } => Ok(Reference { kind, url }),
+        }
+    }
+}
+
+/// Kinds of references, matching the reference types of OSV entries
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum ReferenceKind {
+    /// An advisory about the vulnerability (e.g. in another database, or
+    /// published by the upstream project)
+    Advisory,
+
+    /// An article or blog post about the vulnerability
+    Article,
+
+    /// Discussion of the vulnerability (e.g. on a mailing list)
+    Discussion,
+
+    /// A fix for the vulnerability (e.g. a PR or commit)
+    Fix,
+
+    /// The report of the vulnerability (e.g. an issue)
+    Report,
+
+    /// Any other web page
+    Web,
+
+    /// Kinds of references we don't know about yet
+    Other(String),
+}
+
+impl ReferenceKind {
+    /// Get a string representing this kind of reference
+    pub fn as_str(&self) -> &str {
+        match *self {
+            ReferenceKind::Advisory => "advisory",
+            ReferenceKind::Article => "article",
+            ReferenceKind::Discussion => "discussion",
+            ReferenceKind::Fix => "fix",
+            ReferenceKind::Report => "report",
+            ReferenceKind::Web => "web",
+            ReferenceKind::Other(ref other) => other,
+        }
+    }
+
+    /// Guess the kind of the reference with the given URL from its path,
+    /// which works for the common cases of GitHub/GitLab URLs and
+    /// advisory databases
+    pub fn guess(url: &str) -> Self {
+        if url.contains("/advisories/") || url.contains("cvename.cgi") {
+            ReferenceKind::Advisory
+        } else if url.contains("/pull/")
+            || url.contains("/commit/")
+            || url.contains("/merge_requests/")
+        {
+            ReferenceKind::Fix
+        } else if url.contains("/issues/") {
+            ReferenceKind::Report
+        } else {
+            ReferenceKind::Web
+        }
+    }
+}
+
+impl FromStr for ReferenceKind {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Ok(match string {
+            "advisory" => ReferenceKind::Advisory,
+            "article" => ReferenceKind::Article,
+            "discussion" => ReferenceKind::Discussion,
+            "fix" => ReferenceKind::Fix,
+            "report" => ReferenceKind::Report,
+            "web" => ReferenceKind::Web,
+            other => ReferenceKind::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ReferenceKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReferenceKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// URL of a web page (e.g. an advisory's announcement), which is checked to
+/// be a well-formed `http://` or `https://` URL when it's parsed
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Url(String);
+
+impl Url {
+    /// Parse a URL, ensuring it's an `http://` or `https://` URL with a host
+    /// and no whitespace or control characters
+    pub fn new<S: Into<String>>(into_string: S) -> Result<Self, Error> {
+        let string = into_string.into();
+
+        let rest = match string
+            .strip_prefix("https://")
+            .or_else(|| string.strip_prefix("http://"))
+        {
+            Some(rest) => rest,
+            None => fail!(ErrorKind::Parse, "not an http(s):// URL: {}", string),
+        };
+
+        let host = rest.split(['/', '?', '#']).next().unwrap();
+
+        if host.is_empty() || string.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            fail!(ErrorKind::Parse, "malformed URL: {}", string);
+        }
+
+        Ok(Url(string))
+    }
+
+    /// Borrow this URL as a string slice
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// Get the host (and port, if any) of this URL, e.g. `github.com`
+    pub fn host(&self) -> &str {
+        let rest = &self.0[self.0.find("://").unwrap() + 3..];
+        rest.split(['/', '?', '#']).next().unwrap()
+    }
+
+    /// Is this an `https://` URL?
+    pub fn is_https(&self) -> bool {
+        self.0.starts_with("https://")
+    }
+}
+
+impl AsRef<str> for Url {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl FromStr for Url {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Error> {
+        Self::new(string)
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Url> for String {
+    fn from(url: Url) -> String {
+        url.0
+    }
+}
+
+impl Serialize for Url {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Url {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(String::deserialize(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Url;
+
+    #[test]
+    fn url_test() {
+        let url = Url::new("https://github.com/a/b/issues/1").unwrap();
+        assert_eq!(url.host(), "github.com");
+        assert!(url.is_https());
+        assert!(!Url::new("http://example.com?q").unwrap().is_https());
+
+        for invalid in &[
+            "",
+            "example.com",
+            "ftp://example.com/",
+            "https://",
+            "https:///a",
+        ] {
+            assert!(Url::new(*invalid).is_err(), "{}", invalid);
+        }
+
+        assert!(Url::new("https://example.com/a b").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use semver::{Version, VersionReq};
+
+/// Versions of a crate which are and aren't affected by an advisory
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VersionSpec {
+    /// Versions which are patched and not vulnerable (expressed as semantic version requirements)
+    #[serde(rename = "patched_versions")]
+    pub patched: Vec<VersionReq>,
+
+    /// Versions which were never affected in the first place
+    #[serde(rename = "unaffected_versions", default)]
+    pub unaffected: Vec<VersionReq>,
+}
+
+impl VersionSpec {
+    /// Is the given version of the crate vulnerable, i.e. neither patched
+    /// nor unaffected?
+    pub fn is_vulnerable(&self, version: &Version) -> bool {
+        !self.is_patched(version) && !self.is_unaffected(version)
+    }
+
+    /// Is the given version matched by one of the patched version requirements?
+    pub fn is_patched(&self, version: &Version) -> bool {
+        self.patched.iter().any(|req| req.matches(version))
+    }
+
+    /// Is the given version matched by one of the unaffected version requirements?
+    pub fn is_unaffected(&self, version: &Version) -> bool {
+        self.unaffected.iter().any(|req| req.matches(version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionSpec;
+    use semver::{Version, VersionReq};
+
+    #[test]
+    fn is_vulnerable_test() {
+        let versions = VersionSpec {
+            patched: vec![VersionReq::parse(">= 1.2.0").unwrap()],
+            unaffected: vec![VersionReq::parse("< 0.5.0").unwrap()],
+        };
+
+        for &(version, vulnerable) in &[
+            ("0.4.9", false),
+            ("0.5.0", true),
+            ("1.1.9", true),
+            ("1.2.0", false),
+        ] {
+            assert_eq!(
+                versions.is_vulnerable(&Version::parse(version).unwrap()),
+                vulnerable,
+                "{}",
+                version
+            );
+        }
+    }
+}
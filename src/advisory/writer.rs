@@ -0,0 +1,473 @@
+//! Serialization of advisories back into files, formatted in the style of the
+//! advisory DB (i.e. fields in a fixed order, one per line, with empty
+//! optional fields omitted) so edits produce minimal diffs
+
+use std::fmt::Write;
+use toml::{self, Value};
+
+use super::{Advisory, Date, Patch, Reference, Url, PLACEHOLDER_ADVISORY_ID};
+use package::PackageName;
+
+/// Serialize an advisory in the legacy TOML format
+pub(crate) fn to_toml(advisory: &Advisory) -> String {
+    let mut out = String::from("[advisory]\n");
+    write_front_matter(advisory, &mut out);
+    write_field(&mut out, "title", &basic_string(&advisory.title));
+    write_field(
+        &mut out,
+        "description",
+        &multiline_string(&advisory.description),
+    );
+    write_tables(advisory, &mut out);
+    out
+}
+
+/// Serialize an advisory in the V3 Markdown format
+pub(crate) fn to_markdown(advisory: &Advisory) -> String {
+    let mut out = String::from("```toml\n[advisory]\n");
+    write_front_matter(advisory, &mut out);
+    write_tables(advisory, &mut out);
+    out.push_str("```\n\n");
+    writeln!(out, "# {}", advisory.title.trim()).unwrap();
+
+    let description = advisory.description.trim();
+
+    if !description.is_empty() {
+        writeln!(out, "\n{}", description).unwrap();
+    }
+
+    out
+}
+
+/// Generate a skeleton for a new advisory in the V3 Markdown format, with
+/// the optional fields commented out and explained
+pub(crate) fn template(package: &PackageName, date: &Date) -> String {
+    let mut out = String::from("```toml\n[advisory]\n");
+    write_field(&mut out, "id", &basic_string(PLACEHOLDER_ADVISORY_ID));
+    write_field(&mut out, "package", &basic_string(package.as_str()));
+    write_field(&mut out, "date", &basic_string(date.as_str()));
+    out.push_str(TEMPLATE_BODY);
+    out
+}
+
+/// Everything in an advisory template after its `date` field
+const TEMPLATE_BODY: &str = r#"
+# URL to a long-form description of this issue, e.g. a GitHub issue/PR,
+# a change log entry, or a blog post announcing the release (optional)
+#url = "https://github.com/mystuff/mycrate/issues/123"
+
+# CVSS v3 or v4 vector describing the severity of this issue (optional)
+#cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:H/I:H/A:H"
+
+# IDs of this vulnerability in other databases, e.g. CVE or GHSA (optional)
+#aliases = ["CVE-2018-XXXX"]
+
+# Categories this vulnerability falls under (optional), from:
+# code-execution, crypto-failure, denial-of-service, file-disclosure,
+# format-injection, memory-corruption, memory-exposure,
+# privilege-escalation, thread-safety
+#categories = ["memory-corruption"]
+
+# Upstream fixes for this vulnerability, e.g. fix commits or PRs, or
+# `{ kind = "backport", url = "..." }` for backports of the fix (optional)
+#patches = ["https://github.com/mystuff/mycrate/commit/0123abc"]
+
+# CWE IDs of the weaknesses which lead to this vulnerability (optional)
+#cwe = ["CWE-787"]
+
+# Freeform keywords, similar to Cargo keywords (optional)
+#keywords = ["ssl", "mitm"]
+
+# Versions which were never vulnerable (optional)
+#unaffected_versions = ["< 1.1.0"]
+
+# Versions which include fixes for this vulnerability (mandatory, but may
+# be empty if there is no fix yet)
+patched_versions = []
+
+# Functions affected by this vulnerability, along with the versions they're
+# affected in (optional)
+#[advisory.affected.functions]
+#"mycrate::MyType::method" = ["< 1.2.0"]
+```
+
+# Title of the advisory
+
+Description of the vulnerability, in Markdown. Include a summary of the
+issue, who is affected, and how to fix or work around it.
+"#;
+
+/// Write all of the fields of the advisory except its title and description
+fn write_front_matter(advisory: &Advisory, out: &mut String) {
+    write_field(out, "id", &basic_string(advisory.id.as_str()));
+    write_field(out, "package", &basic_string(advisory.package.as_str()));
+    write_field(out, "date", &basic_string(advisory.date.as_str()));
+
+    if let Some(ref withdrawn) = advisory.withdrawn {
+        write_field(out, "withdrawn", &basic_string(withdrawn.as_str()));
+    }
+
+    if let Some(ref informational) = advisory.informational {
+        write_field(out, "informational", &basic_string(informational.as_str()));
+    }
+
+    if let Some(ref url) = advisory.url {
+        write_field(out, "url", &basic_string(url.as_str()));
+    }
+
+    if let Some(ref cvss) = advisory.cvss {
+        write_field(out, "cvss", &basic_string(&cvss.to_string()));
+    }
+
+    if let Some(severity) = advisory.explicit_severity {
+        write_field(out, "severity", &basic_string(severity.as_str()));
+    }
+
+    if let Some(license) = advisory.explicit_license {
+        write_field(out, "license", &basic_string(license.as_str()));
+    }
+
+    write_array(out, "aliases", advisory.aliases.iter().map(|id| id.as_str()));
+    write_array(out, "related", advisory.related.iter().map(|id| id.as_str()));
+    write_references(out, &advisory.references);
+    write_patches(out, &advisory.patches);
+    write_array(
+        out,
+        "categories",
+        advisory.categories.iter().map(|c| c.as_str()),
+    );
+    write_array(out, "cwe", advisory.cwe.iter().map(|cwe| cwe.to_string()));
+    write_array(out, "keywords", advisory.keywords.iter().map(|k| k.as_str()));
+
+    if let Some(ref arch) = advisory.affected_arch {
+        write_array(out, "affected_arch", arch.iter().map(|arch| arch.as_str()));
+    }
+
+    if let Some(ref os) = advisory.affected_os {
+        write_array(out, "affected_os", os.iter().map(|os| os.as_str()));
+    }
+
+    write_array(
+        out,
+        "unaffected_versions",
+        advisory.versions.unaffected.iter().map(|req| req.to_string()),
+    );
+
+    // Patched versions are mandatory, so they're written even if empty
+    let patched_versions = advisory
+        .versions
+        .patched
+        .iter()
+        .map(|req| basic_string(&req.to_string()))
+        .collect::<Vec<_>>();
+
+    write_field(
+        out,
+        "patched_versions",
+        &format!("[{}]", patched_versions.join(", ")),
+    );
+
+    let unknown_values = unknown_fields(advisory, false);
+
+    if !unknown_values.is_empty() {
+        out.push_str(&toml::to_string(&unknown_values).unwrap());
+    }
+}
+
+/// Write the subtables of the advisory, which must come after all of its
+/// other fields
+fn write_tables(advisory: &Advisory, out: &mut String) {
+    if let Some(ref affected) = advisory.affected {
+        if !affected.arch.is_empty()
+            || !affected.os.is_empty()
+            || !affected.features.is_empty()
+            || affected.rust_version.is_some()
+        {
+            out.push_str("\n[advisory.affected]\n");
+            write_array(out, "arch", affected.arch.iter().map(|arch| arch.as_str()));
+            write_array(out, "os", affected.os.iter().map(|os| os.as_str()));
+            write_array(out, "features", affected.features.iter());
+
+            if let Some(ref req) = affected.rust_version {
+                write_field(out, "rust_version", &basic_string(&req.to_string()));
+            }
+        }
+
+        if !affected.functions.is_empty() {
+            out.push_str("\n[advisory.affected.functions]\n");
+
+            for (path, reqs) in &affected.functions {
+                let reqs = reqs
+                    .iter()
+                    .map(|req| basic_string(&req.to_string()))
+                    .collect::<Vec<_>>();
+
+                write_field(
+                    out,
+                    &basic_string(path.as_str()),
+                    &format!("[{}]", reqs.join(", ")),
+                );
+            }
+        }
+    }
+
+    let unknown_tables = unknown_fields(advisory, true);
+
+    if !unknown_tables.is_empty() {
+        let mut wrapper = toml::value::Table::new();
+        wrapper.insert("advisory".to_owned(), Value::Table(unknown_tables));
+        out.push('\n');
+        out.push_str(&toml::to_string(&wrapper).unwrap());
+    }
+}
+
+/// Get the unknown fields of the advisory which are (or aren't) tables or
+/// arrays of tables, which have to be written after all of the other fields
+fn unknown_fields(advisory: &Advisory, tables: bool) -> toml::value::Table {
+    advisory
+        .unknown_fields
+        .iter()
+        .filter(|&(_, value)| {
+            let is_table = match *value {
+                Value::Table(_) => true,
+                Value::Array(ref array) => array.iter().any(Value::is_table),
+                _ => false,
+            };
+
+            is_table == tables
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Write a `key = value` line
+fn write_field(out: &mut String, key: &str, value: &str) {
+    writeln!(out, "{} = {}", key, value).unwrap();
+}
+
+/// Write an array of strings, unless it's empty
+fn write_array<I, S>(out: &mut String, key: &str, values: I)
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let values = values
+        .map(|value| basic_string(value.as_ref()))
+        .collect::<Vec<_>>();
+
+    if !values.is_empty() {
+        write_field(out, key, &format!("[{}]", values.join(", ")));
+    }
+}
+
+/// Write the references of an advisory (see `write_links`)
+fn write_references(out: &mut String, references: &[Reference]) {
+    let links = references
+        .iter()
+        .map(|r| (r.kind.as_str(), &r.url, r.is_guessable()))
+        .collect::<Vec<_>>();
+
+    write_links(out, "references", &links);
+}
+
+/// Write the patches of an advisory (see `write_links`)
+fn write_patches(out: &mut String, patches: &[Patch]) {
+    let links = patches
+        .iter()
+        .map(|p| (p.kind.as_str(), &p.url, p.is_guessable()))
+        .collect::<Vec<_>>();
+
+    write_links(out, "patches", &links);
+}
+
+/// Write links of some kind, as an array of URLs if all of their kinds
+/// can be guessed from their URLs, or of `{ kind, url }` tables if not
+/// (since TOML arrays can't mix the two)
+fn write_links(out: &mut String, key: &str, links: &[(&str, &Url, bool)]) {
+    if links.iter().all(|&(_, _, guessable)| guessable) {
+        write_array(out, key, links.iter().map(|&(_, url, _)| url));
+        return;
+    }
+
+    let tables = links
+        .iter()
+        .map(|&(kind, url, _)| {
+            format!(
+                "{{ kind = {}, url = {} }}",
+                basic_string(kind),
+                basic_string(url.as_str())
+            )
+        })
+        .collect::<Vec<_>>();
+
+    write_field(out, key, &format!("[{}]", tables.join(", ")));
+}
+
+/// Encode a TOML basic string (i.e. `"..."`)
+fn basic_string(string: &str) -> String {
+    let mut out = String::from("\"");
+    escape(string, &mut out, false);
+    out.push('"');
+    out
+}
+
+/// Encode a TOML multi-line basic string (i.e. `"""..."""`)
+fn multiline_string(string: &str) -> String {
+    let mut out = String::from("\"\"\"\n");
+    escape(string.trim(), &mut out, true);
+    out.push_str("\n\"\"\"");
+    out
+}
+
+/// Escape a string for inclusion in a TOML basic string, leaving newlines
+/// as-is if it's a multi-line string
+fn escape(string: &str, out: &mut String, multiline: bool) {
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' if multiline => out.push('\n'),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => write!(out, "\\u{:04X}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Advisory, Category, Date, License, PatchKind, ReferenceKind};
+    use cvss::Severity;
+    use package::PackageName;
+
+    const ADVISORY: &str = r#"[advisory]
+id = "RUSTSEC-2018-0001"
+package = "a"
+date = "2018-06-01"
+withdrawn = "2018-07-01"
+informational = "unsound"
+url = "https://example.com/"
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+severity = "high"
+license = "CC-BY-4.0"
+aliases = ["CVE-2018-1000001", "GHSA-4mmc-49vf-jmcp"]
+related = ["CVE-2018-1000002"]
+references = ["https://github.com/a/a/issues/1", "https://github.com/a/a/pull/2"]
+patches = ["https://github.com/a/a/commit/0a1b2c3", "https://github.com/a/a/pull/3"]
+categories = ["memory-corruption", "thread-safety"]
+cwe = ["CWE-119", "CWE-362"]
+keywords = ["memory-safety", "c++"]
+affected_os = ["linux"]
+patched_versions = [">= 1.0.0"]
+unknown = "value"
+title = "Memory safety issue"
+description = """
+Some *Markdown*.
+
+With a \\ backslash and \"\"\" quotes.
+"""
+
+[advisory.affected]
+arch = ["x86_64"]
+features = ["unsafe-impl"]
+rust_version = "< 1.30.0"
+
+[advisory.affected.functions]
+"a::b::c" = ["< 1.0.0"]
+
+[advisory.unknown_table]
+key = 1
+"#;
+
+    #[test]
+    fn round_trip_test() {
+        let advisory = Advisory::from_toml(ADVISORY).unwrap();
+        assert_eq!(advisory.to_toml_string(), ADVISORY);
+
+        // An explicit severity takes precedence over the CVSS one (critical)
+        assert_eq!(advisory.severity(), Some(Severity::High));
+
+        let mut derived = advisory.clone();
+        derived.explicit_severity = None;
+        assert_eq!(derived.severity(), Some(Severity::Critical));
+
+        assert_eq!(advisory.license(), License::CcBy40);
+        assert!(Advisory::from_toml(&ADVISORY.replace("CC-BY-4.0", "MIT")).is_err());
+
+        // Unknown fields are kept, and written back in the same places
+        assert_eq!(advisory.unknown_fields().len(), 2);
+        assert_eq!(advisory.unknown_fields()["unknown"].as_str(), Some("value"));
+
+        // References whose kinds can't be guessed are written as tables
+        assert_eq!(advisory.references[1].kind, ReferenceKind::Fix);
+        let mut article = advisory.clone();
+        article.references[0].kind = ReferenceKind::Article;
+        let toml = article.to_toml_string();
+        assert!(toml.contains(r#"{ kind = "article", url = "https://github.com/a/a/issues/1" }"#));
+        assert_eq!(Advisory::from_toml(&toml).unwrap(), article);
+
+        // So are patches
+        assert_eq!(advisory.patches[0].commit_id(), Some("0a1b2c3"));
+        assert_eq!(advisory.patches[1].kind, PatchKind::PullRequest);
+        let mut backport = advisory.clone();
+        backport.patches[0].kind = PatchKind::Backport;
+        let toml = backport.to_toml_string();
+        assert!(toml.contains(r#"patches = [{ kind = "backport", url = "#));
+        assert_eq!(Advisory::from_toml(&toml).unwrap(), backport);
+
+        // Advisory IDs are converted to their URLs
+        let legacy = ADVISORY.replace(
+            r#""https://github.com/a/a/issues/1", "https://github.com/a/a/pull/2""#,
+            r#""RUSTSEC-2018-0002""#,
+        );
+        let legacy = Advisory::from_toml(&legacy).unwrap();
+        assert_eq!(legacy.references[0].kind, ReferenceKind::Advisory);
+        assert_eq!(
+            legacy.references[0].url.as_str(),
+            "https://rustsec.org/advisories/RUSTSEC-2018-0002"
+        );
+
+        assert!(advisory.is_withdrawn());
+        assert!(advisory.informational.as_ref().unwrap().is_unsound());
+        let affected = advisory.affected.as_ref().unwrap();
+        let version = "0.9.0".parse().unwrap();
+        assert_eq!(affected.functions_for(&version)[0].as_str(), "a::b::c");
+
+        // Legacy `affected_os` is combined with `affected.arch`
+        assert!(advisory.affects_target("x86_64-unknown-linux-gnu"));
+        assert!(!advisory.affects_target("x86_64-pc-windows-msvc"));
+        assert!(!advisory.affects_target("aarch64-unknown-linux-gnu"));
+
+        assert!(advisory.affects_features(&["std", "unsafe-impl"]));
+        assert!(!advisory.affects_features(&["std"]));
+        assert!(advisory.affects_rust_version(&"1.29.2".parse().unwrap()));
+        assert!(!advisory.affects_rust_version(&"1.30.0".parse().unwrap()));
+
+        assert_eq!(advisory.categories[0], Category::MemoryCorruption);
+        assert!(advisory.aliases[0].is_cve());
+        assert!(advisory.aliases[1].is_ghsa());
+
+        let markdown = advisory.to_markdown_string();
+        assert!(markdown.contains("```\n\n# Memory safety issue\n\nSome *Markdown*.\n"));
+
+        // Markdown descriptions don't have a trailing newline
+        let mut expected = advisory.clone();
+        expected.description = advisory.description.trim().to_owned();
+        assert_eq!(Advisory::from_markdown(&markdown).unwrap(), expected);
+    }
+
+    #[test]
+    fn template_test() {
+        let date = Date::new("2019-02-01").unwrap();
+        let template = Advisory::template(&PackageName::from("mycrate"), &date);
+        let advisory = Advisory::from_markdown(&template).unwrap();
+
+        assert!(advisory.id.is_placeholder());
+        assert_eq!(advisory.package.as_str(), "mycrate");
+        assert_eq!(advisory.date, date);
+        assert!(advisory.versions.patched.is_empty());
+        assert_eq!(advisory.title, "Title of the advisory");
+    }
+}
@@ -0,0 +1,90 @@
+//! Verification of cosign signatures on archives
+//!
+//! These are the signatures made by `cosign sign-blob --key`: a base64-encoded
+//! ECDSA P-256 signature over the SHA-256 digest of the archive. Keyless
+//! signatures (made with certificates issued by Sigstore's Fulcio CA) are
+//! unsupported.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use p256::{
+    ecdsa::{signature::Verifier, Signature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+};
+use std::str;
+
+use error::{Error, ErrorKind};
+
+/// cosign public key trusted to sign archives of the advisory DB
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CosignKey(VerifyingKey);
+
+impl CosignKey {
+    /// Parse a PEM-encoded ECDSA P-256 public key (e.g. `cosign.pub`, as
+    /// created by `cosign generate-key-pair`)
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        VerifyingKey::from_public_key_pem(pem.trim())
+            .map(CosignKey)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid cosign key: {}", e))
+    }
+
+    /// Is the given (base64-encoded) cosign signature over `data` made by this key?
+    pub(crate) fn verify(&self, signature: &[u8], data: &[u8]) -> bool {
+        let signature = match decode_signature(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+
+        self.0.verify(data, &signature).is_ok()
+    }
+}
+
+/// Verify the given (base64-encoded) cosign signature over `data` was made
+/// by one of the given keys
+pub(crate) fn verify(keys: &[CosignKey], signature: &[u8], data: &[u8]) -> Result<(), Error> {
+    decode_signature(signature)?;
+
+    if !keys.iter().any(|key| key.verify(signature, data)) {
+        fail!(
+            ErrorKind::Repo,
+            "archive signature not made by any of the trusted cosign keys"
+        );
+    }
+
+    Ok(())
+}
+
+/// Decode a base64-encoded, DER-encoded ECDSA signature
+fn decode_signature(signature: &[u8]) -> Result<Signature, Error> {
+    let der = str::from_utf8(signature)
+        .ok()
+        .and_then(|base64| BASE64.decode(base64.trim()).ok())
+        .ok_or_else(|| err!(ErrorKind::Parse, "invalid cosign signature encoding"))?;
+
+    Signature::from_der(&der)
+        .map_err(|e| err!(ErrorKind::Parse, "invalid cosign signature: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify, CosignKey};
+    use std::slice;
+
+    /// Key and signature made with `openssl dgst -sha256 -sign`, which is
+    /// equivalent to `cosign sign-blob --key`
+    const KEY: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAET6Q4Tx1r2/+/kuyNyrTT6+6raR5q
+cvbGXCPwKCA+jH/XTGgtUN6JW+LIy+XB0pK/7q8P61ASqn76ao6PxX0ccg==
+-----END PUBLIC KEY-----
+";
+
+    const SIGNATURE: &[u8] =
+        b"MEUCIQCeXS60nW2junoKsNEiLKvAaYJkfEuRRnKInCLblstd8wIgF5JfkMrUnAtDl5WLj2GWfSZkSwMRN+DkqRjhVc8iYDU=\n";
+
+    #[test]
+    fn verify_test() {
+        let key = CosignKey::from_pem(KEY).unwrap();
+        assert!(verify(slice::from_ref(&key), SIGNATURE, b"advisory-db archive").is_ok());
+        assert!(verify(slice::from_ref(&key), SIGNATURE, b"advisory-db archivf").is_err());
+        assert!(verify(&[key], b"not base64!", b"advisory-db archive").is_err());
+    }
+}
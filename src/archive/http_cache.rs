@@ -0,0 +1,113 @@
+//! Validators for making conditional requests for previously fetched archives
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use ureq;
+
+use error::Error;
+
+/// Suffix of the file (alongside the unpacked archive) the validators are kept in
+const HTTP_CACHE_SUFFIX: &str = ".http-cache";
+
+/// `ETag` and `Last-Modified` headers from the response an archive was
+/// unpacked from, used to avoid downloading it again if it's unchanged
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct HttpCache {
+    /// Key identifying how the archive was fetched (e.g. its URL)
+    key: String,
+
+    /// `ETag` of the archive
+    etag: Option<String>,
+
+    /// `Last-Modified` time of the archive
+    last_modified: Option<String>,
+}
+
+impl HttpCache {
+    /// Get the validators from the given response to the request with the given key
+    pub(crate) fn from_response(key: &str, response: &ureq::Response) -> Self {
+        HttpCache {
+            key: key.to_owned(),
+            etag: response.header("ETag").map(str::to_owned),
+            last_modified: response.header("Last-Modified").map(str::to_owned),
+        }
+    }
+
+    /// Load the validators for the archive unpacked at the given path, if any
+    pub(crate) fn load(archive_path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(cache_path(archive_path)?).ok()?;
+        let mut cache = HttpCache::default();
+
+        for line in contents.lines() {
+            let mut parts = line.splitn(2, ": ");
+
+            match (parts.next(), parts.next()) {
+                (Some("Key"), Some(value)) => cache.key = value.to_owned(),
+                (Some("ETag"), Some(value)) => cache.etag = Some(value.to_owned()),
+                (Some("Last-Modified"), Some(value)) => {
+                    cache.last_modified = Some(value.to_owned())
+                }
+                _ => return None,
+            }
+        }
+
+        Some(cache)
+    }
+
+    /// Save the validators for the archive unpacked at the given path
+    /// (or remove any saved previously if there are none)
+    pub(crate) fn save(&self, archive_path: &Path) -> Result<(), Error> {
+        let cache_path = match cache_path(archive_path) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if self.key.is_empty() || (self.etag.is_none() && self.last_modified.is_none()) {
+            if cache_path.exists() {
+                fs::remove_file(&cache_path)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut contents = format!("Key: {}\n", self.key);
+
+        if let Some(ref etag) = self.etag {
+            contents.push_str(&format!("ETag: {}\n", etag));
+        }
+
+        if let Some(ref last_modified) = self.last_modified {
+            contents.push_str(&format!("Last-Modified: {}\n", last_modified));
+        }
+
+        fs::write(&cache_path, contents)?;
+        Ok(())
+    }
+
+    /// Add conditional headers to the given request with the given key (if
+    /// it's how the archive these validators are for was fetched)
+    pub(crate) fn apply(&self, key: &str, mut request: ureq::Request) -> ureq::Request {
+        if self.key != key {
+            return request;
+        }
+
+        if let Some(ref etag) = self.etag {
+            request = request.set("If-None-Match", etag);
+        }
+
+        if let Some(ref last_modified) = self.last_modified {
+            request = request.set("If-Modified-Since", last_modified);
+        }
+
+        request
+    }
+}
+
+/// Path to the file the validators for the archive at the given path are kept in
+fn cache_path(archive_path: &Path) -> Option<PathBuf> {
+    let mut name = archive_path.file_name()?.to_owned();
+    name.push(HTTP_CACHE_SUFFIX);
+    Some(archive_path.with_file_name(name))
+}
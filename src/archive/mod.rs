@@ -0,0 +1,297 @@
+//! Git-free fetching of the advisory DB as a tarball over HTTPS
+//!
+//! This is intended for environments where Git isn't usable at all. Note that
+//! unlike `Repository::fetch`, archives aren't signed by default, so the
+//! integrity of the downloaded advisories relies solely on HTTPS unless
+//! they're fetched with `Archive::fetch_signed` (or `Archive::fetch_tuf`).
+
+mod cosign;
+mod http_cache;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+#[cfg(feature = "tuf")]
+mod tuf;
+
+pub use self::cosign::CosignKey;
+#[cfg(feature = "snapshot")]
+pub use self::snapshot::Snapshot;
+#[cfg(feature = "tuf")]
+pub use self::tuf::TufConfig;
+
+use flate2::read::GzDecoder;
+use std::{
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+use tar;
+use ureq;
+
+use self::http_cache::HttpCache;
+use error::{Error, ErrorKind};
+use repository::{self, DirectoryLock, Repository};
+
+/// Location of a tarball of the RustSec advisory database for crates.io
+pub const ADVISORY_DB_ARCHIVE_URL: &str =
+    "https://github.com/RustSec/advisory-db/archive/master.tar.gz";
+
+/// Directory under ~/.cargo where the unpacked advisory-db archive will be kept
+const ADVISORY_DB_ARCHIVE_DIRECTORY: &str = "advisory-db-archive";
+
+/// Suffix of the temporary directory archives are unpacked into before
+/// replacing any previously unpacked archive
+const UNPACK_DIRECTORY_SUFFIX: &str = ".unpack";
+
+/// Function verifying a downloaded archive (to be unpacked into the given path)
+type Verifier<'a> = dyn Fn(&ureq::Agent, &Path, &[u8]) -> Result<(), Error> + 'a;
+
+/// Advisory DB downloaded as a tarball and unpacked on disk
+#[derive(Debug)]
+pub struct Archive {
+    /// Path to the unpacked archive
+    path: PathBuf,
+
+    /// Was the archive downloaded (as opposed to unchanged since it was last fetched)?
+    modified: bool,
+}
+
+impl Archive {
+    /// Location of the default unpacked `advisory-db` archive for crates.io
+    pub fn default_path() -> PathBuf {
+        Repository::default_path().with_file_name(ADVISORY_DB_ARCHIVE_DIRECTORY)
+    }
+
+    /// Fetch the default archive
+    pub fn fetch_default_archive() -> Result<Self, Error> {
+        Self::fetch(ADVISORY_DB_ARCHIVE_URL, Archive::default_path())
+    }
+
+    /// Download the gzipped tarball at the given URL and unpack it into the given path,
+    /// replacing any previously unpacked archive.
+    ///
+    /// If the server provided an `ETag` or `Last-Modified` header when the
+    /// archive was previously fetched, a conditional request is made, and the
+    /// previously unpacked archive is used if it's unchanged (see `is_modified`).
+    pub fn fetch<P: Into<PathBuf>>(url: &str, into_path: P) -> Result<Self, Error> {
+        Self::fetch_archive(url, into_path.into(), None, Some(url))
+    }
+
+    /// Download the gzipped tarball at the given URL along with its cosign
+    /// signature (as made by `cosign sign-blob --key`) at `signature_url`,
+    /// and unpack it into the given path once the signature has been verified
+    /// to be made by one of the trusted keys.
+    ///
+    /// Conditional requests are made as with `Archive::fetch`, in which case
+    /// the previously unpacked (and verified) archive is used if it's unchanged.
+    pub fn fetch_signed<P: Into<PathBuf>>(
+        url: &str,
+        signature_url: &str,
+        trusted_keys: &[CosignKey],
+        into_path: P,
+    ) -> Result<Self, Error> {
+        if trusted_keys.is_empty() {
+            fail!(ErrorKind::BadParam, "no trusted cosign keys given");
+        }
+
+        if !signature_url.starts_with("https://") {
+            fail!(
+                ErrorKind::BadParam,
+                "expected {} to start with https://",
+                signature_url
+            );
+        }
+
+        let verify: &Verifier = &|agent, _path, archive| {
+            let mut signature = vec![];
+            agent
+                .get(signature_url)
+                .call()?
+                .into_reader()
+                .read_to_end(&mut signature)?;
+
+            cosign::verify(trusted_keys, &signature, archive)
+        };
+
+        // Archives are only reused if they were fetched with the same signature
+        let cache_key = format!("{} {}", url, signature_url);
+        Self::fetch_archive(url, into_path.into(), Some(verify), Some(&cache_key))
+    }
+
+    /// Download the gzipped tarball at the given URL and unpack it into the
+    /// given path once it has been verified against the TUF metadata
+    /// described by the given config.
+    ///
+    /// The newest verified metadata is kept alongside the unpacked archive
+    /// (in a directory with a `.tuf` suffix), which protects against rollback
+    /// to older metadata on subsequent fetches. Conditional requests aren't
+    /// made, as the metadata must be checked for expiry on every fetch.
+    #[cfg(feature = "tuf")]
+    pub fn fetch_tuf<P: Into<PathBuf>>(
+        url: &str,
+        config: &TufConfig,
+        into_path: P,
+    ) -> Result<Self, Error> {
+        let verify: &Verifier = &|agent, path, archive| tuf::verify(agent, config, path, archive);
+        Self::fetch_archive(url, into_path.into(), Some(verify), None)
+    }
+
+    /// Fetch the archive at the given URL into the given path, verifying it
+    /// with the given function (if any) before unpacking it, and making a
+    /// conditional request if there's a cache key identifying how it was fetched
+    fn fetch_archive(
+        url: &str,
+        path: PathBuf,
+        verify: Option<&Verifier>,
+        cache_key: Option<&str>,
+    ) -> Result<Self, Error> {
+        if !url.starts_with("https://") {
+            fail!(
+                ErrorKind::BadParam,
+                "expected {} to start with https://",
+                url
+            );
+        }
+
+        let unpack_path = match path.file_name() {
+            Some(name) => {
+                let mut unpack_name = name.to_owned();
+                unpack_name.push(UNPACK_DIRECTORY_SUFFIX);
+                path.with_file_name(unpack_name)
+            }
+            None => fail!(ErrorKind::BadParam, "invalid directory: {}", path.display()),
+        };
+
+        if let Some(parent) = path.parent() {
+            if !parent.is_dir() {
+                fail!(ErrorKind::BadParam, "not a directory: {}", parent.display());
+            }
+        }
+
+        // Serialize concurrent fetches into the same directory
+        let _lock = DirectoryLock::acquire(&path)?;
+
+        // Honor the `HTTPS_PROXY`/`ALL_PROXY` environment variables like `Repository::fetch`
+        let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
+        let mut request = agent.get(url);
+
+        if let Some(cache_key) = cache_key {
+            if path.is_dir() {
+                if let Some(cache) = HttpCache::load(&path) {
+                    request = cache.apply(cache_key, request);
+                }
+            }
+        }
+
+        let response = request.call()?;
+
+        if response.status() == 304 {
+            let mut archive = Self::open(path)?;
+            archive.modified = false;
+            return Ok(archive);
+        }
+
+        let cache = HttpCache::from_response(cache_key.unwrap_or_default(), &response);
+
+        if unpack_path.exists() {
+            fs::remove_dir_all(&unpack_path)?;
+        }
+
+        match verify {
+            // Verified archives are downloaded in full, so they're only unpacked once verified
+            Some(verify) => {
+                let mut archive = vec![];
+                response.into_reader().read_to_end(&mut archive)?;
+
+                verify(&agent, &path, &archive)?;
+                unpack(archive.as_slice(), &unpack_path)?;
+            }
+            None => unpack(response.into_reader(), &unpack_path)?,
+        }
+
+        if path.exists() {
+            fs::remove_dir_all(&path)?;
+        }
+
+        fs::rename(&unpack_path, &path)?;
+        cache.save(&path)?;
+
+        let mut archive = Self::open(path)?;
+        archive.modified = true;
+        Ok(archive)
+    }
+
+    /// Open a previously unpacked archive at the given path
+    pub fn open<P: Into<PathBuf>>(into_path: P) -> Result<Self, Error> {
+        let path = into_path.into();
+
+        if !path.is_dir() {
+            fail!(ErrorKind::BadParam, "not a directory: {}", path.display());
+        }
+
+        Ok(Archive {
+            path,
+            modified: false,
+        })
+    }
+
+    /// Path to the unpacked archive
+    pub fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
+
+    /// Was this archive downloaded and unpacked by the fetch which returned
+    /// it, as opposed to being unchanged since it was previously fetched?
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Iterate over all of the advisories in this archive, in every collection
+    pub(crate) fn crate_advisories(&self) -> Result<repository::Iter, Error> {
+        repository::Iter::new(&self.path)
+    }
+}
+
+/// Unpack a gzipped tarball into the given directory, stripping the leading
+/// directory component GitHub adds to archives (e.g. `advisory-db-master/`)
+fn unpack<R: Read>(reader: R, dest: &Path) -> Result<(), Error> {
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        // Only regular files and directories are needed (e.g. skip symlinks)
+        match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Directory => (),
+            _ => continue,
+        }
+
+        let entry_path = entry.path()?.into_owned();
+
+        let mut relative_path = PathBuf::new();
+
+        for component in entry_path.components().skip(1) {
+            match component {
+                Component::Normal(part) => relative_path.push(part),
+                _ => fail!(
+                    ErrorKind::Parse,
+                    "invalid path in archive: {}",
+                    entry_path.display()
+                ),
+            }
+        }
+
+        if relative_path.as_os_str().is_empty() {
+            continue;
+        }
+
+        let entry_dest = dest.join(relative_path);
+
+        if let Some(parent) = entry_dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        entry.unpack(&entry_dest)?;
+    }
+
+    Ok(())
+}
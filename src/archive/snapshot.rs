@@ -0,0 +1,127 @@
+//! Signed snapshots of the advisory DB as a single compressed JSON document
+//!
+//! A snapshot is a gzipped JSON object of the form:
+//!
+//! ```json
+//! { "payload": "...", "signature": "..." }
+//! ```
+//!
+//! where `signature` is a cosign signature (see `CosignKey`) over the bytes
+//! of `payload`, and `payload` is itself a JSON object containing the time the
+//! snapshot was generated and the advisories in it:
+//!
+//! ```json
+//! { "generated_at": "2019-03-01T00:00:00Z", "advisories": [...] }
+//! ```
+//!
+//! SQLite snapshots are unsupported.
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde_json;
+use std::io::Read;
+use ureq;
+
+use super::cosign::{self, CosignKey};
+use advisory::Advisory;
+use error::{Error, ErrorKind};
+use repository::FreshnessPolicy;
+
+/// Signed snapshot of an advisory DB, as published to a CDN
+#[derive(Debug)]
+pub struct Snapshot {
+    /// Time the snapshot was generated
+    generated_at: DateTime<Utc>,
+
+    /// Advisories in the snapshot
+    advisories: Vec<Advisory>,
+}
+
+/// Signed snapshot, before its signature has been verified
+#[derive(Deserialize)]
+struct Envelope {
+    /// JSON-encoded `Payload`
+    payload: String,
+
+    /// Base64-encoded cosign signature over `payload`
+    signature: String,
+}
+
+/// Contents of a snapshot
+#[derive(Deserialize)]
+struct Payload {
+    /// Time the snapshot was generated
+    generated_at: DateTime<Utc>,
+
+    /// Advisories in the snapshot
+    advisories: Vec<Advisory>,
+}
+
+impl Snapshot {
+    /// Download the snapshot at the given URL, verifying it was signed by one
+    /// of the trusted keys
+    pub fn fetch(url: &str, trusted_keys: &[CosignKey]) -> Result<Self, Error> {
+        if !url.starts_with("https://") {
+            fail!(
+                ErrorKind::BadParam,
+                "expected {} to start with https://",
+                url
+            );
+        }
+
+        // Honor the `HTTPS_PROXY`/`ALL_PROXY` environment variables like `Archive::fetch`
+        let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
+
+        let mut bytes = vec![];
+        agent
+            .get(url)
+            .call()?
+            .into_reader()
+            .read_to_end(&mut bytes)?;
+
+        Self::from_gzipped(&bytes, trusted_keys)
+    }
+
+    /// Parse a gzipped snapshot, verifying it was signed by one of the trusted keys
+    pub fn from_gzipped(bytes: &[u8], trusted_keys: &[CosignKey]) -> Result<Self, Error> {
+        if trusted_keys.is_empty() {
+            fail!(ErrorKind::BadParam, "no trusted cosign keys given");
+        }
+
+        let mut json = vec![];
+        GzDecoder::new(bytes).read_to_end(&mut json)?;
+
+        let envelope: Envelope = serde_json::from_slice(&json)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid snapshot: {}", e))?;
+
+        cosign::verify(
+            trusted_keys,
+            envelope.signature.as_bytes(),
+            envelope.payload.as_bytes(),
+        )?;
+
+        let payload: Payload = serde_json::from_str(&envelope.payload)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid snapshot payload: {}", e))?;
+
+        Ok(Snapshot {
+            generated_at: payload.generated_at,
+            advisories: payload.advisories,
+        })
+    }
+
+    /// Time the snapshot was generated
+    pub fn generated_at(&self) -> DateTime<Utc> {
+        self.generated_at
+    }
+
+    /// Ensure the snapshot was generated recently, according to the given
+    /// policy, returning a warning if it's stale and the policy is to warn
+    pub fn ensure_fresh(&self, policy: &FreshnessPolicy) -> Result<Option<String>, Error> {
+        policy.ensure_fresh(self.generated_at, "generated at")
+    }
+
+    /// Advisories in the snapshot
+    pub(crate) fn advisories(&self) -> &[Advisory] {
+        &self.advisories
+    }
+}
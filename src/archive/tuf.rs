@@ -0,0 +1,559 @@
+//! Verification of archives using The Update Framework (TUF)
+//!
+//! Starting from a trusted `root.json`, newer root metadata is fetched (each
+//! of which must be signed by both the previous and its own root keys), then
+//! `targets.json` is verified and the archive checked against its length and
+//! SHA-256 hash. Expired metadata is rejected (protecting against freeze
+//! attacks), as is metadata older than that previously verified (protecting
+//! against rollback attacks).
+//!
+//! The `snapshot` and `timestamp` roles and delegated targets are unsupported.
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek;
+use hex;
+use p256::{self, pkcs8::DecodePublicKey};
+use serde_json::{self, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use ureq;
+
+use error::{Error, ErrorKind};
+
+/// Where to find the TUF metadata for an archive, and the root of trust for it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TufConfig {
+    /// URL of the directory containing the TUF metadata (i.e. `root.json`,
+    /// `2.root.json`, ..., and `targets.json`)
+    pub metadata_url: String,
+
+    /// Name of the archive in `targets.json`
+    pub target_name: String,
+
+    /// Initial trusted `root.json` (e.g. shipped with the application), which
+    /// is only used until a newer root has been verified
+    pub trusted_root: Vec<u8>,
+}
+
+/// Signed TUF metadata
+struct Metadata {
+    /// The signed portion of the metadata
+    signed: Value,
+
+    /// Signatures over the canonical JSON encoding of `signed`
+    signatures: Vec<(String, Vec<u8>)>,
+}
+
+/// Contents of `root.json`
+#[derive(Deserialize)]
+struct Root {
+    /// Version of the root metadata, which increases by 1 each time it changes
+    version: u64,
+
+    /// Time after which the root metadata must not be trusted
+    expires: DateTime<Utc>,
+
+    /// Public keys used by the roles, by key ID
+    keys: BTreeMap<String, Key>,
+
+    /// Keys and thresholds trusted for each role
+    roles: BTreeMap<String, Role>,
+}
+
+/// Public key in root metadata
+#[derive(Deserialize)]
+struct Key {
+    /// Type of the key (e.g. `ed25519`)
+    keytype: String,
+
+    /// The key itself
+    keyval: KeyValue,
+}
+
+/// Public portion of a key in root metadata
+#[derive(Deserialize)]
+struct KeyValue {
+    /// Hex-encoded (Ed25519) or PEM-encoded (ECDSA) public key
+    public: String,
+}
+
+/// Keys trusted for a role
+#[derive(Deserialize)]
+struct Role {
+    /// IDs of the keys trusted to sign the role's metadata
+    keyids: Vec<String>,
+
+    /// Number of those keys which must sign it
+    threshold: usize,
+}
+
+/// Contents of `targets.json`
+#[derive(Deserialize)]
+struct Targets {
+    /// Version of the targets metadata
+    version: u64,
+
+    /// Time after which the targets metadata must not be trusted
+    expires: DateTime<Utc>,
+
+    /// Information about each target, by name
+    targets: BTreeMap<String, Target>,
+}
+
+/// Information about a target in `targets.json`
+#[derive(Deserialize)]
+struct Target {
+    /// Length of the target in bytes
+    length: u64,
+
+    /// Hex-encoded hashes of the target, by algorithm
+    hashes: BTreeMap<String, String>,
+}
+
+/// Verify the given archive (fetched into the given path) against the TUF
+/// metadata described by the config, updating the metadata trusted for that path
+pub(crate) fn verify(
+    agent: &ureq::Agent,
+    config: &TufConfig,
+    path: &Path,
+    archive: &[u8],
+) -> Result<(), Error> {
+    if !config.metadata_url.starts_with("https://") {
+        fail!(
+            ErrorKind::BadParam,
+            "expected {} to start with https://",
+            config.metadata_url
+        );
+    }
+
+    let state_dir = state_dir(path);
+    fs::create_dir_all(&state_dir)?;
+
+    let root_path = state_dir.join("root.json");
+    let targets_path = state_dir.join("targets.json");
+
+    // Trust the newest root previously verified for this path, if any
+    let root_bytes = if root_path.exists() {
+        fs::read(&root_path)?
+    } else {
+        config.trusted_root.clone()
+    };
+
+    let metadata = Metadata::parse(&root_bytes)?;
+    let mut root: Root = metadata.signed_as("root")?;
+    metadata.verify(&root, "root")?;
+
+    // Update the root, one version at a time, until there is no newer one
+    loop {
+        let url = format!("{}/{}.root.json", metadata_base(config), root.version + 1);
+
+        let new_root_bytes = match download(agent, &url) {
+            Ok(bytes) => bytes,
+            Err(None) => break,
+            Err(Some(e)) => return Err(e),
+        };
+
+        let metadata = Metadata::parse(&new_root_bytes)?;
+        let new_root: Root = metadata.signed_as("root")?;
+        metadata.verify(&root, "root")?;
+        metadata.verify(&new_root, "root")?;
+
+        if new_root.version != root.version + 1 {
+            fail!(
+                ErrorKind::Repo,
+                "expected root version {}, got {}",
+                root.version + 1,
+                new_root.version
+            );
+        }
+
+        fs::write(&root_path, &new_root_bytes)?;
+        root = new_root;
+    }
+
+    ensure_unexpired("root", root.expires)?;
+
+    let targets_url = format!("{}/targets.json", metadata_base(config));
+    let targets_bytes = download(agent, &targets_url).map_err(|e| {
+        e.unwrap_or_else(|| err!(ErrorKind::Repo, "no TUF metadata at {}", targets_url))
+    })?;
+
+    let metadata = Metadata::parse(&targets_bytes)?;
+    let targets: Targets = metadata.signed_as("targets")?;
+    metadata.verify(&root, "targets")?;
+
+    if targets_path.exists() {
+        let previous: Targets = Metadata::parse(&fs::read(&targets_path)?)?.signed_as("targets")?;
+
+        if targets.version < previous.version {
+            fail!(
+                ErrorKind::Repo,
+                "rollback detected: targets version {} is older than previously verified version {}",
+                targets.version,
+                previous.version
+            );
+        }
+    }
+
+    ensure_unexpired("targets", targets.expires)?;
+    fs::write(&targets_path, &targets_bytes)?;
+
+    let target = targets.targets.get(&config.target_name).ok_or_else(|| {
+        err!(
+            ErrorKind::Repo,
+            "no TUF target named {}",
+            config.target_name
+        )
+    })?;
+
+    target.verify(archive)
+}
+
+impl Metadata {
+    /// Parse signed metadata from JSON
+    fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let mut value: Value = serde_json::from_slice(bytes)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid TUF metadata: {}", e))?;
+
+        let signed = value
+            .get_mut("signed")
+            .map(Value::take)
+            .ok_or_else(|| err!(ErrorKind::Parse, "TUF metadata isn't signed"))?;
+
+        let mut signatures = vec![];
+
+        for signature in value
+            .get("signatures")
+            .and_then(Value::as_array)
+            .ok_or_else(|| err!(ErrorKind::Parse, "TUF metadata isn't signed"))?
+        {
+            let keyid = signature.get("keyid").and_then(Value::as_str);
+            let sig = signature.get("sig").and_then(Value::as_str);
+
+            if let (Some(keyid), Some(sig)) = (keyid, sig) {
+                if let Ok(sig) = hex::decode(sig) {
+                    signatures.push((keyid.to_owned(), sig));
+                }
+            }
+        }
+
+        Ok(Metadata { signed, signatures })
+    }
+
+    /// Deserialize the signed portion of the metadata, ensuring it's of the given type
+    fn signed_as<T>(&self, metadata_type: &str) -> Result<T, Error>
+    where
+        for<'de> T: ::serde::Deserialize<'de>,
+    {
+        if self.signed.get("_type").and_then(Value::as_str) != Some(metadata_type) {
+            fail!(
+                ErrorKind::Parse,
+                "expected TUF {} metadata",
+                metadata_type
+            );
+        }
+
+        serde_json::from_value(self.signed.clone())
+            .map_err(|e| err!(ErrorKind::Parse, "invalid TUF {} metadata: {}", metadata_type, e))
+    }
+
+    /// Ensure this metadata is signed by the threshold of keys the given root
+    /// trusts for the given role
+    fn verify(&self, root: &Root, role_name: &str) -> Result<(), Error> {
+        let role = root
+            .roles
+            .get(role_name)
+            .ok_or_else(|| err!(ErrorKind::Parse, "no TUF {} role in root", role_name))?;
+
+        let mut message = vec![];
+        canonical_json(&self.signed, &mut message)?;
+
+        // Each key counts towards the threshold at most once
+        let signed_by = self
+            .signatures
+            .iter()
+            .filter(|(keyid, _)| role.keyids.contains(keyid))
+            .filter(|(keyid, signature)| {
+                root.keys
+                    .get(keyid)
+                    .map(|key| key.verify(signature, &message))
+                    .unwrap_or(false)
+            })
+            .map(|(keyid, _)| keyid)
+            .collect::<BTreeSet<_>>();
+
+        if role.threshold == 0 || signed_by.len() < role.threshold {
+            fail!(
+                ErrorKind::Repo,
+                "TUF {} metadata signed by {} of the {} required keys",
+                role_name,
+                signed_by.len(),
+                role.threshold
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Key {
+    /// Is the given signature over `message` made by this key?
+    fn verify(&self, signature: &[u8], message: &[u8]) -> bool {
+        match self.keytype.as_str() {
+            "ed25519" => {
+                let key = hex::decode(&self.keyval.public)
+                    .ok()
+                    .and_then(|bytes| {
+                        let mut key = [0u8; 32];
+
+                        if bytes.len() != key.len() {
+                            return None;
+                        }
+
+                        key.copy_from_slice(&bytes);
+                        ed25519_dalek::VerifyingKey::from_bytes(&key).ok()
+                    });
+
+                match (key, ed25519_dalek::Signature::from_slice(signature)) {
+                    (Some(key), Ok(signature)) => key.verify_strict(message, &signature).is_ok(),
+                    _ => false,
+                }
+            }
+            "ecdsa" | "ecdsa-sha2-nistp256" => {
+                use p256::ecdsa::signature::Verifier;
+
+                let key = p256::ecdsa::VerifyingKey::from_public_key_pem(&self.keyval.public);
+
+                match (key, p256::ecdsa::Signature::from_der(signature)) {
+                    (Ok(key), Ok(signature)) => key.verify(message, &signature).is_ok(),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Target {
+    /// Ensure the given data matches this target's length and SHA-256 hash
+    fn verify(&self, data: &[u8]) -> Result<(), Error> {
+        if data.len() as u64 != self.length {
+            fail!(
+                ErrorKind::Repo,
+                "expected TUF target of {} bytes, got {}",
+                self.length,
+                data.len()
+            );
+        }
+
+        let expected = self
+            .hashes
+            .get("sha256")
+            .ok_or_else(|| err!(ErrorKind::Repo, "no SHA-256 hash for TUF target"))?;
+
+        if hex::encode(Sha256::digest(data)) != expected.to_ascii_lowercase() {
+            fail!(ErrorKind::Repo, "TUF target hash mismatch");
+        }
+
+        Ok(())
+    }
+}
+
+/// Directory in which the TUF metadata verified for an archive unpacked at
+/// the given path is kept (alongside it)
+fn state_dir(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".tuf");
+    path.with_file_name(name)
+}
+
+/// Get the metadata URL without any trailing slash
+fn metadata_base(config: &TufConfig) -> &str {
+    config.metadata_url.trim_end_matches('/')
+}
+
+/// Download the file at the given URL, or `Err(None)` if it doesn't exist
+fn download(agent: &ureq::Agent, url: &str) -> Result<Vec<u8>, Option<Error>> {
+    let response = match agent.get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(404, _)) => return Err(None),
+        Err(e) => return Err(Some(e.into())),
+    };
+
+    let mut bytes = vec![];
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|e| Some(e.into()))?;
+
+    Ok(bytes)
+}
+
+/// Ensure the given metadata hasn't expired
+fn ensure_unexpired(metadata_type: &str, expires: DateTime<Utc>) -> Result<(), Error> {
+    if expires <= Utc::now() {
+        fail!(
+            ErrorKind::Repo,
+            "TUF {} metadata expired at {}",
+            metadata_type,
+            expires
+        );
+    }
+
+    Ok(())
+}
+
+/// Encode the given JSON value as canonical JSON (i.e. with sorted keys, no
+/// insignificant whitespace, and only `"` and `\` escaped in strings) as TUF
+/// signatures are made over
+fn canonical_json(value: &Value, out: &mut Vec<u8>) -> Result<(), Error> {
+    match *value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(b) => out.extend_from_slice(if b { b"true" } else { b"false" }),
+        Value::Number(ref n) => {
+            if !n.is_i64() && !n.is_u64() {
+                fail!(ErrorKind::Parse, "non-integer number in TUF metadata: {}", n);
+            }
+
+            out.extend_from_slice(n.to_string().as_bytes());
+        }
+        Value::String(ref s) => canonical_string(s, out),
+        Value::Array(ref values) => {
+            out.push(b'[');
+
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                canonical_json(value, out)?;
+            }
+
+            out.push(b']');
+        }
+        Value::Object(ref map) => {
+            out.push(b'{');
+
+            let sorted = map.iter().collect::<BTreeMap<_, _>>();
+
+            for (i, (key, value)) in sorted.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                canonical_string(key, out);
+                out.push(b':');
+                canonical_json(value, out)?;
+            }
+
+            out.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode the given string as a canonical JSON string
+fn canonical_string(s: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+
+    for byte in s.bytes() {
+        if byte == b'"' || byte == b'\\' {
+            out.push(b'\\');
+        }
+
+        out.push(byte);
+    }
+
+    out.push(b'"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_json, Metadata, Root};
+    use serde_json;
+
+    /// Root metadata signed by both of its Ed25519 root keys, generated with
+    /// Python's `cryptography` package
+    const ROOT: &str = r#"{
+    "signed": {
+        "_type": "root",
+        "spec_version": "1.0.0",
+        "version": 1,
+        "expires": "2030-01-01T00:00:00Z",
+        "consistent_snapshot": false,
+        "keys": {
+            "k0": {
+                "keytype": "ed25519",
+                "scheme": "ed25519",
+                "keyval": {
+                    "public": "8a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c"
+                }
+            },
+            "k1": {
+                "keytype": "ed25519",
+                "scheme": "ed25519",
+                "keyval": {
+                    "public": "8139770ea87d175f56a35466c34c7ecccb8d8a91b4ee37a25df60f5b8fc9b394"
+                }
+            }
+        },
+        "roles": {
+            "root": {
+                "keyids": [
+                    "k0",
+                    "k1"
+                ],
+                "threshold": 2
+            },
+            "targets": {
+                "keyids": [
+                    "k0"
+                ],
+                "threshold": 1
+            }
+        }
+    },
+    "signatures": [
+        {
+            "keyid": "k0",
+            "sig": "5f23183e412eed56d3d5dae576b16d40468625148be2396596d7617e3312336af93afa933e86457febb7d6a85dab9a27a3d72358a54fd3d2d23e706ad619a304"
+        },
+        {
+            "keyid": "k1",
+            "sig": "00767efbb8fbca20acb01b192141ab534bdc80fddf555c375d3f69001d79421893fe712d01d5ce000ca53fb0ee8f3cbe3efdbd99eceec075c0b8f46512b3550f"
+        }
+    ]
+}"#;
+
+    #[test]
+    fn canonical_json_test() {
+        let value = serde_json::from_str(r#"{ "b": [1, true, null], "a": "q\"\\\n" }"#).unwrap();
+        let mut out = vec![];
+        canonical_json(&value, &mut out).unwrap();
+        assert_eq!(out, b"{\"a\":\"q\\\"\\\\\n\",\"b\":[1,true,null]}".to_vec());
+    }
+
+    #[test]
+    fn verify_test() {
+        let mut metadata = Metadata::parse(ROOT.as_bytes()).unwrap();
+        let root: Root = metadata.signed_as("root").unwrap();
+        assert!(metadata.signed_as::<Root>("targets").is_err());
+        assert!(metadata.verify(&root, "root").is_ok());
+        assert!(metadata.verify(&root, "targets").is_ok());
+
+        // The same key signing twice doesn't meet the threshold of 2
+        let first = metadata.signatures[0].clone();
+        metadata.signatures[1] = first;
+        assert!(metadata.verify(&root, "root").is_err());
+
+        metadata.signatures[1].1[0] ^= 1;
+        metadata.signatures.remove(0);
+        assert!(metadata.verify(&root, "targets").is_err());
+    }
+}
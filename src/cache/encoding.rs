@@ -0,0 +1,676 @@
+//! Compact binary encoding of cached databases, which (unlike JSON) can be
+//! read back without any escaping, number parsing, or whitespace to skip
+//!
+//! Each value is a tag byte saying what kind of value it is, followed by its
+//! contents: integers (and lengths) are LEB128 variable-length integers, with
+//! signed integers zigzag-encoded, floats are little-endian, and strings and
+//! byte strings are prefixed with their length. Sequences and maps continue
+//! until an `END` tag. The encoding is self-describing (as flattened fields,
+//! such as those of advisories, require), so structs are encoded as maps of
+//! their fields, and enum variants as either their name or a single-entry map
+//! from their name to their contents.
+
+use serde::{
+    de::{self, IntoDeserializer},
+    forward_to_deserialize_any, ser, Deserialize, Serialize,
+};
+use std::{error, fmt, str};
+
+/// The unit value, i.e. `()`
+const UNIT: u8 = 0;
+
+/// An absent optional value
+const NONE: u8 = 1;
+
+/// A present optional value, followed by the value
+const SOME: u8 = 2;
+
+/// The boolean `false`
+const FALSE: u8 = 3;
+
+/// The boolean `true`
+const TRUE: u8 = 4;
+
+/// An unsigned integer
+const UINT: u8 = 5;
+
+/// A signed integer
+const INT: u8 = 6;
+
+/// A floating point number
+const FLOAT: u8 = 7;
+
+/// A string
+const STR: u8 = 8;
+
+/// A byte string
+const BYTES: u8 = 9;
+
+/// The start of a sequence, followed by its elements
+const SEQ: u8 = 10;
+
+/// The start of a map, followed by its keys and values in turn
+const MAP: u8 = 11;
+
+/// The end of a sequence or map
+const END: u8 = 12;
+
+/// Encode the given value
+pub(super) fn to_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, EncodingError> {
+    let mut encoder = Encoder { output: vec![] };
+    value.serialize(&mut encoder)?;
+    Ok(encoder.output)
+}
+
+/// Decode a value which takes up all of the given bytes
+pub(super) fn from_slice<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T, EncodingError> {
+    let mut decoder = Decoder { input };
+    let value = T::deserialize(&mut decoder)?;
+
+    if !decoder.input.is_empty() {
+        return Err(EncodingError::new("trailing data after value"));
+    }
+
+    Ok(value)
+}
+
+/// Error encoding or decoding a value
+#[derive(Debug)]
+pub(super) struct EncodingError(String);
+
+impl EncodingError {
+    fn new(message: &str) -> Self {
+        EncodingError(message.to_owned())
+    }
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for EncodingError {}
+
+impl ser::Error for EncodingError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        EncodingError(message.to_string())
+    }
+}
+
+impl de::Error for EncodingError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        EncodingError(message.to_string())
+    }
+}
+
+/// Serializer which encodes values
+struct Encoder {
+    output: Vec<u8>,
+}
+
+impl Encoder {
+    fn write_uint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.output.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.output.push(value as u8);
+    }
+
+    fn write_bytes(&mut self, tag: u8, bytes: &[u8]) {
+        self.output.push(tag);
+        self.write_uint(bytes.len() as u64);
+        self.output.extend_from_slice(bytes);
+    }
+}
+
+impl ser::Serializer for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, value: bool) -> Result<(), EncodingError> {
+        self.output.push(if value { TRUE } else { FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), EncodingError> {
+        self.serialize_i64(value.into())
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), EncodingError> {
+        self.serialize_i64(value.into())
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), EncodingError> {
+        self.serialize_i64(value.into())
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), EncodingError> {
+        self.output.push(INT);
+        self.write_uint(((value << 1) ^ (value >> 63)) as u64);
+        Ok(())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), EncodingError> {
+        self.serialize_u64(value.into())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), EncodingError> {
+        self.serialize_u64(value.into())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), EncodingError> {
+        self.serialize_u64(value.into())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), EncodingError> {
+        self.output.push(UINT);
+        self.write_uint(value);
+        Ok(())
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), EncodingError> {
+        self.serialize_f64(value.into())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), EncodingError> {
+        self.output.push(FLOAT);
+        self.output
+            .extend_from_slice(&value.to_bits().to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), EncodingError> {
+        self.serialize_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), EncodingError> {
+        self.write_bytes(STR, value.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), EncodingError> {
+        self.write_bytes(BYTES, value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), EncodingError> {
+        self.output.push(NONE);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), EncodingError> {
+        self.output.push(SOME);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), EncodingError> {
+        self.output.push(UNIT);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), EncodingError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), EncodingError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), EncodingError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), EncodingError> {
+        self.output.push(MAP);
+        self.write_bytes(STR, variant.as_bytes());
+        value.serialize(&mut *self)?;
+        self.output.push(END);
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, EncodingError> {
+        self.output.push(SEQ);
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, EncodingError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self, EncodingError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, EncodingError> {
+        self.output.push(MAP);
+        self.write_bytes(STR, variant.as_bytes());
+        self.output.push(SEQ);
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, EncodingError> {
+        self.output.push(MAP);
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self, EncodingError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, EncodingError> {
+        self.output.push(MAP);
+        self.write_bytes(STR, variant.as_bytes());
+        self.output.push(MAP);
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), EncodingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), EncodingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), EncodingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), EncodingError> {
+        value.serialize(&mut **self)
+    }
+
+    /// End both the sequence of fields and the map containing it
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.extend_from_slice(&[END, END]);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), EncodingError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), EncodingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodingError> {
+        self.write_bytes(STR, key.as_bytes());
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Encoder {
+    type Ok = ();
+    type Error = EncodingError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodingError> {
+        self.write_bytes(STR, key.as_bytes());
+        value.serialize(&mut **self)
+    }
+
+    /// End both the map of fields and the map containing it
+    fn end(self) -> Result<(), EncodingError> {
+        self.output.extend_from_slice(&[END, END]);
+        Ok(())
+    }
+}
+
+/// Deserializer which decodes values
+struct Decoder<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Decoder<'de> {
+    fn peek(&self) -> Result<u8, EncodingError> {
+        self.input.first().cloned().ok_or_else(end_of_data)
+    }
+
+    fn read(&mut self, len: usize) -> Result<&'de [u8], EncodingError> {
+        if self.input.len() < len {
+            return Err(end_of_data());
+        }
+
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn read_tag(&mut self) -> Result<u8, EncodingError> {
+        self.read(1).map(|bytes| bytes[0])
+    }
+
+    fn expect_tag(&mut self, tag: u8) -> Result<(), EncodingError> {
+        if self.read_tag()? != tag {
+            return Err(EncodingError::new("unexpected value"));
+        }
+
+        Ok(())
+    }
+
+    fn read_uint(&mut self) -> Result<u64, EncodingError> {
+        let mut value = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_tag()?;
+            value |= u64::from(byte & 0x7f) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(EncodingError::new("integer too long"))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8], EncodingError> {
+        let len = self.read_uint()?;
+
+        if len > self.input.len() as u64 {
+            return Err(end_of_data());
+        }
+
+        self.read(len as usize)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, EncodingError> {
+        str::from_utf8(self.read_bytes()?).map_err(|_| EncodingError::new("invalid UTF-8"))
+    }
+}
+
+/// Error for values which are cut off
+fn end_of_data() -> EncodingError {
+    EncodingError::new("unexpected end of data")
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'de> {
+    type Error = EncodingError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, EncodingError> {
+        match self.read_tag()? {
+            UNIT => visitor.visit_unit(),
+            NONE => visitor.visit_none(),
+            SOME => visitor.visit_some(self),
+            FALSE => visitor.visit_bool(false),
+            TRUE => visitor.visit_bool(true),
+            UINT => visitor.visit_u64(self.read_uint()?),
+            INT => {
+                let value = self.read_uint()?;
+                visitor.visit_i64((value >> 1) as i64 ^ -((value & 1) as i64))
+            }
+            FLOAT => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(self.read(8)?);
+                visitor.visit_f64(f64::from_bits(u64::from_le_bytes(bytes)))
+            }
+            STR => visitor.visit_borrowed_str(self.read_str()?),
+            BYTES => visitor.visit_borrowed_bytes(self.read_bytes()?),
+            SEQ => {
+                let value = visitor.visit_seq(Compound { decoder: self })?;
+                self.expect_tag(END)?;
+                Ok(value)
+            }
+            MAP => {
+                let value = visitor.visit_map(Compound { decoder: self })?;
+                self.expect_tag(END)?;
+                Ok(value)
+            }
+            tag => Err(EncodingError(format!("unknown tag: {}", tag))),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, EncodingError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EncodingError> {
+        match self.read_tag()? {
+            STR => visitor.visit_enum(self.read_str()?.into_deserializer()),
+            MAP => {
+                let value = visitor.visit_enum(Compound { decoder: self })?;
+                self.expect_tag(END)?;
+                Ok(value)
+            }
+            _ => Err(EncodingError::new("expected an enum variant")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+/// Access to the contents of a sequence, map, or enum variant
+struct Compound<'a, 'de: 'a> {
+    decoder: &'a mut Decoder<'de>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Compound<'a, 'de> {
+    type Error = EncodingError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, EncodingError> {
+        if self.decoder.peek()? == END {
+            return Ok(None);
+        }
+
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Compound<'a, 'de> {
+    type Error = EncodingError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, EncodingError> {
+        if self.decoder.peek()? == END {
+            return Ok(None);
+        }
+
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, EncodingError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Compound<'a, 'de> {
+    type Error = EncodingError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), EncodingError> {
+        let variant = seed.deserialize(&mut *self.decoder)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Compound<'a, 'de> {
+    type Error = EncodingError;
+
+    fn unit_variant(self) -> Result<(), EncodingError> {
+        self.decoder.expect_tag(UNIT)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, EncodingError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, EncodingError> {
+        de::Deserializer::deserialize_seq(&mut *self.decoder, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EncodingError> {
+        de::Deserializer::deserialize_map(&mut *self.decoder, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_slice, to_vec};
+    use toml;
+
+    #[test]
+    fn round_trip_test() {
+        let value: toml::Value = toml::from_str(
+            r#"
+string = "value"
+integers = [0, 127, 128, -1, -9223372036854775808, 9223372036854775807]
+float = -1.5
+boolean = true
+datetime = 2019-06-01T00:00:00Z
+
+[table]
+nested = { array = [["a"], []] }
+"#,
+        )
+        .unwrap();
+
+        let encoded = to_vec(&value).unwrap();
+        assert_eq!(from_slice::<toml::Value>(&encoded).unwrap(), value);
+
+        let pairs = Some(vec![(1, 'a')]);
+        let decoded: Option<Vec<(u8, char)>> = from_slice(&to_vec(&pairs).unwrap()).unwrap();
+        assert_eq!(decoded, pairs);
+
+        // Truncated or padded values aren't accepted
+        assert!(from_slice::<toml::Value>(&encoded[..encoded.len() - 1]).is_err());
+        assert!(from_slice::<toml::Value>(&[&encoded[..], &[0]].concat()).is_err());
+    }
+}
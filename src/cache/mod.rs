@@ -0,0 +1,187 @@
+//! Persistent cache of parsed advisory databases, so advisory files don't
+//! have to be parsed again on every run when the advisory DB hasn't changed
+//!
+//! The cache is a file starting with `MAGIC` and `FORMAT_VERSION`, followed by
+//! the gzipped database in a compact binary encoding (see the `encoding`
+//! module), keyed by the commit of the advisory DB repository it was loaded
+//! from (and the version of this library which loaded it). Caches in any
+//! other format, with any other key, or which can't be read, are ignored and
+//! replaced.
+
+mod encoding;
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use db::AdvisoryDatabase;
+use error::{Error, ErrorKind};
+use repository::Repository;
+
+/// Bytes every cache file starts with
+const MAGIC: &[u8] = b"RUSTSEC-CACHE";
+
+/// Version of the format of cache files, which is incremented whenever the
+/// encoding changes incompatibly
+const FORMAT_VERSION: u8 = 1;
+
+/// Suffix of the file (alongside the repository) the cache is kept in
+const CACHE_SUFFIX: &str = ".cache";
+
+/// Persistent cache of a parsed advisory database
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cache {
+    /// Path to the cache file
+    path: PathBuf,
+}
+
+/// Contents of a cache file
+#[derive(Deserialize, Serialize)]
+struct Contents<D> {
+    /// Key identifying what the database was loaded from
+    key: String,
+
+    /// Cached database
+    database: D,
+}
+
+impl Cache {
+    /// Use the cache file at the given path
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Cache { path: path.into() }
+    }
+
+    /// Use the cache file alongside the given repository (e.g.
+    /// `~/.cargo/advisory-db.cache` for `~/.cargo/advisory-db`)
+    pub fn for_repository(repo: &Repository) -> Self {
+        let mut name = repo
+            .path()
+            .file_name()
+            .map(|name| name.to_owned())
+            .unwrap_or_default();
+
+        name.push(CACHE_SUFFIX);
+        Self::new(repo.path().with_file_name(name))
+    }
+
+    /// Path to the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Remove the cache file, if it exists
+    pub fn clear(&self) -> Result<(), Error> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the database from the cache if it has the given key
+    pub(crate) fn load(&self, key: &str) -> Option<AdvisoryDatabase> {
+        let data = fs::read(&self.path).ok()?;
+        let (&version, compressed) = data.strip_prefix(MAGIC)?.split_first()?;
+
+        if version != FORMAT_VERSION {
+            return None;
+        }
+
+        let mut encoded = vec![];
+        GzDecoder::new(compressed).read_to_end(&mut encoded).ok()?;
+        let contents: Contents<AdvisoryDatabase> = encoding::from_slice(&encoded).ok()?;
+
+        if contents.key != key {
+            return None;
+        }
+
+        Some(contents.database)
+    }
+
+    /// Save the given database to the cache with the given key
+    pub(crate) fn save(&self, key: &str, db: &AdvisoryDatabase) -> Result<(), Error> {
+        let contents = Contents {
+            key: key.to_owned(),
+            database: db,
+        };
+
+        let encoded = encoding::to_vec(&contents)
+            .map_err(|e| err!(ErrorKind::Io, "couldn't write cache: {}", e))?;
+
+        // Write to a temporary file first so a partially written cache is
+        // never read
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = self.path.with_file_name(tmp_name);
+
+        let mut file = BufWriter::new(File::create(&tmp_path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&[FORMAT_VERSION])?;
+
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(&encoded)?;
+        encoder.finish()?.flush()?;
+
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Key for advisories loaded from the given commit of the advisory DB
+pub(crate) fn commit_key(commit_id: &str) -> String {
+    format!("rustsec-{}:{}", env!("CARGO_PKG_VERSION"), commit_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cache, FORMAT_VERSION, MAGIC};
+    use advisory::{Advisory, Collection};
+    use db::AdvisoryDatabase;
+    use std::{env, fs, process};
+
+    #[test]
+    fn round_trip_test() {
+        let mut advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2019-0001"
+package = "std"
+date = "2019-06-01"
+informational = "unsound"
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+affected_os = ["linux"]
+patched_versions = [">= 1.36.0"]
+unknown = "value"
+title = "Title"
+description = "Description"
+
+[advisory.affected]
+functions = { "std::f" = ["< 1.36.0"] }
+"#,
+        )
+        .unwrap();
+        advisory.collection = Collection::Rust;
+
+        let db = AdvisoryDatabase::from_advisories(vec![advisory.clone()]).unwrap();
+        let cache = Cache::new(env::temp_dir().join(format!(
+            "rustsec-cache-round-trip-test-{}.cache",
+            process::id()
+        )));
+        cache.save("key", &db).unwrap();
+
+        let loaded = cache.load("key").unwrap();
+        assert_eq!(loaded.iter().collect::<Vec<_>>(), vec![&advisory]);
+        assert!(cache.load("other key").is_none());
+
+        // Caches in other versions of the format are ignored
+        let mut data = fs::read(cache.path()).unwrap();
+        data[MAGIC.len()] = FORMAT_VERSION + 1;
+        fs::write(cache.path(), data).unwrap();
+        assert!(cache.load("key").is_none());
+
+        cache.clear().unwrap();
+        assert!(cache.load("key").is_none());
+    }
+}
@@ -0,0 +1,357 @@
+//! Export of advisories as [CVE JSON 5] records, for submission to the CVE
+//! program by a CNA (CVE Numbering Authority)
+//!
+//! [CVE JSON 5]: https://github.com/CVEProject/cve-schema
+
+use serde_json::{Map, Value};
+
+use advisory::{ranges, Advisory};
+use cvss::{Cvss, Severity};
+use error::{Error, ErrorKind};
+
+/// Version of the CVE record format records are exported in
+pub const DATA_VERSION: &str = "5.1";
+
+/// URL of the package registry crates are published to
+pub const CRATES_IO_URL: &str = "https://crates.io";
+
+/// CVE record in the CVE JSON 5 format
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    /// Kind of data (always `CVE_RECORD`)
+    pub data_type: String,
+
+    /// Version of the CVE record format (e.g. `5.1`)
+    pub data_version: String,
+
+    /// Metadata about the CVE ID
+    pub cve_metadata: Metadata,
+
+    /// Information about the vulnerability from various organizations
+    pub containers: Containers,
+}
+
+/// Metadata about the CVE ID of a record
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    /// The CVE ID (e.g. `CVE-2019-1000001`)
+    pub cve_id: String,
+
+    /// UUID of the organization that assigned the CVE ID
+    pub assigner_org_id: String,
+
+    /// State of the record (`PUBLISHED` for those exported from advisories)
+    pub state: String,
+
+    /// When the record was published (RFC 3339 timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_published: Option<String>,
+}
+
+/// Containers of information about a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Containers {
+    /// Information from the CNA which assigned the CVE ID
+    pub cna: Cna,
+}
+
+/// Information about a vulnerability from the CNA which assigned its CVE ID
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cna {
+    /// The CNA providing this information
+    pub provider_metadata: ProviderMetadata,
+
+    /// Short title of the vulnerability
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Descriptions of the vulnerability
+    pub descriptions: Vec<Description>,
+
+    /// Products affected by the vulnerability
+    pub affected: Vec<Product>,
+
+    /// Kinds of weakness which lead to the vulnerability (i.e. CWE IDs)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub problem_types: Vec<ProblemType>,
+
+    /// Links to more information about the vulnerability
+    pub references: Vec<Reference>,
+
+    /// Severity metrics (e.g. CVSS scores)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metrics: Vec<Value>,
+}
+
+/// Organization providing information about a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMetadata {
+    /// UUID of the organization
+    pub org_id: String,
+}
+
+/// Description of a vulnerability in a given language
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Description {
+    /// Language of the description (e.g. `en`)
+    pub lang: String,
+
+    /// The description itself
+    pub value: String,
+}
+
+/// Kind of weakness which leads to a vulnerability, in various languages
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProblemType {
+    /// Descriptions of the weakness
+    pub descriptions: Vec<ProblemTypeDescription>,
+}
+
+/// Description of a kind of weakness in a given language
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemTypeDescription {
+    /// Language of the description (e.g. `en`)
+    pub lang: String,
+
+    /// CWE ID of the weakness (e.g. `CWE-416`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwe_id: Option<String>,
+
+    /// The description itself
+    pub description: String,
+
+    /// Kind of description (`CWE` for CWE IDs)
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Product affected by a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    /// Name of the product
+    pub product: String,
+
+    /// URL of the package registry the product is published to
+    #[serde(rename = "collectionURL")]
+    pub collection_url: String,
+
+    /// Name of the package in its registry
+    pub package_name: String,
+
+    /// Status of versions not listed in `versions` (e.g. `unaffected`)
+    pub default_status: String,
+
+    /// Versions of the product and whether they're affected
+    pub versions: Vec<VersionRange>,
+}
+
+/// Range of versions and whether they're affected
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionRange {
+    /// First version in the range
+    pub version: String,
+
+    /// Status of the versions in the range (e.g. `affected`)
+    pub status: String,
+
+    /// Upper bound of the range (exclusive), or `*` if it's unbounded
+    pub less_than: String,
+
+    /// How versions are ordered (e.g. `semver`)
+    pub version_type: String,
+}
+
+/// Link to more information about a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Reference {
+    /// URL of the reference
+    pub url: String,
+}
+
+/// Convert an advisory into a CVE record (see `Advisory::to_cve_record`)
+pub(crate) fn from_advisory(advisory: &Advisory, org_id: &str) -> Result<Record, Error> {
+    // Rejecting a published CVE needs a record in a different format
+    if advisory.is_withdrawn() {
+        fail!(ErrorKind::BadParam, "{} has been withdrawn", advisory.id);
+    }
+
+    let cve_id = if advisory.id.is_cve() {
+        &advisory.id
+    } else {
+        advisory
+            .aliases
+            .iter()
+            .find(|alias| alias.is_cve())
+            .ok_or_else(|| err!(ErrorKind::BadParam, "{} has no CVE alias", advisory.id))?
+    };
+
+    let versions = &advisory.versions;
+    let versions = ranges::unmatched_ranges(versions.patched.iter().chain(&versions.unaffected))?
+        .into_iter()
+        .map(|range| VersionRange {
+            version: range.start.to_string(),
+            status: "affected".to_owned(),
+            less_than: range
+                .end
+                .map(|end| end.to_string())
+                .unwrap_or_else(|| "*".to_owned()),
+            version_type: "semver".to_owned(),
+        })
+        .collect();
+
+    let description = if advisory.description.trim().is_empty() {
+        advisory.title.trim()
+    } else {
+        advisory.description.trim()
+    };
+
+    let mut references = vec![];
+    references.extend(advisory.id.url());
+    references.extend(advisory.url.as_ref().map(|url| url.to_string()));
+    references.extend(advisory.references.iter().map(|r| r.url.to_string()));
+    references.extend(advisory.patches.iter().map(|p| p.url.to_string()));
+    references.extend(
+        advisory
+            .aliases
+            .iter()
+            .filter(|a| *a != cve_id)
+            .filter_map(|a| a.url()),
+    );
+    references.push(format!(
+        "{}/crates/{}",
+        CRATES_IO_URL,
+        advisory.package.as_str()
+    ));
+
+    Ok(Record {
+        data_type: "CVE_RECORD".to_owned(),
+        data_version: DATA_VERSION.to_owned(),
+        cve_metadata: Metadata {
+            cve_id: cve_id.as_str().to_owned(),
+            assigner_org_id: org_id.to_owned(),
+            state: "PUBLISHED".to_owned(),
+            date_published: Some(format!("{}T12:00:00Z", advisory.date.as_str())),
+        },
+        containers: Containers {
+            cna: Cna {
+                provider_metadata: ProviderMetadata {
+                    org_id: org_id.to_owned(),
+                },
+                title: Some(advisory.title.trim().to_owned()),
+                descriptions: vec![Description {
+                    lang: "en".to_owned(),
+                    value: description.to_owned(),
+                }],
+                affected: vec![Product {
+                    product: advisory.package.as_str().to_owned(),
+                    collection_url: CRATES_IO_URL.to_owned(),
+                    package_name: advisory.package.as_str().to_owned(),
+                    default_status: "unaffected".to_owned(),
+                    versions,
+                }],
+                problem_types: advisory
+                    .cwe
+                    .iter()
+                    .map(|cwe| ProblemType {
+                        descriptions: vec![ProblemTypeDescription {
+                            lang: "en".to_owned(),
+                            cwe_id: Some(cwe.to_string()),
+                            description: cwe.to_string(),
+                            kind: "CWE".to_owned(),
+                        }],
+                    })
+                    .collect(),
+                references: references
+                    .into_iter()
+                    .map(|url| Reference { url })
+                    .collect(),
+                metrics: advisory.cvss.iter().map(cvss_metric).collect(),
+            },
+        },
+    })
+}
+
+/// Describe a CVSS vector as a CVE metric (e.g. `{"cvssV3_1": {...}}`)
+fn cvss_metric(cvss: &Cvss) -> Value {
+    let vector = cvss.to_string();
+
+    // e.g. `3.1` for a `CVSS:3.1/...` vector
+    let version = vector["CVSS:".len()..]
+        .split('/')
+        .next()
+        .unwrap()
+        .to_owned();
+    let base_score = cvss.base_score();
+    let severity = Severity::from_score(base_score).as_str().to_uppercase();
+
+    let mut metric = Map::new();
+    metric.insert("version".to_owned(), Value::String(version.clone()));
+    metric.insert("vectorString".to_owned(), Value::String(vector));
+    metric.insert("baseScore".to_owned(), base_score.into());
+    metric.insert("baseSeverity".to_owned(), Value::String(severity));
+
+    let mut value = Map::new();
+    value.insert(
+        format!("cvssV{}", version.replace('.', "_")),
+        Value::Object(metric),
+    );
+    Value::Object(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use advisory::Advisory;
+    use serde_json;
+
+    #[test]
+    fn from_advisory_test() {
+        let mut advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2018-0001"
+package = "mycrate"
+date = "2018-06-01"
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+aliases = ["CVE-2018-1000001"]
+cwe = ["CWE-416"]
+patched_versions = [">= 1.2.0"]
+title = "Title"
+description = "Description"
+"#,
+        )
+        .unwrap();
+
+        let org_id = "00000000-0000-4000-8000-000000000000";
+        let record = advisory.to_cve_record(org_id).unwrap();
+        assert_eq!(record.cve_metadata.cve_id, "CVE-2018-1000001");
+        assert_eq!(record.cve_metadata.state, "PUBLISHED");
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json["containers"]["cna"]["affected"][0]["versions"][0],
+            serde_json::json!({
+                "version": "0.0.0-0",
+                "status": "affected",
+                "lessThan": "1.2.0",
+                "versionType": "semver",
+            })
+        );
+        assert_eq!(
+            json["containers"]["cna"]["problemTypes"][0]["descriptions"][0]["cweId"],
+            "CWE-416"
+        );
+        assert_eq!(
+            json["containers"]["cna"]["metrics"][0]["cvssV3_1"]["baseSeverity"],
+            "CRITICAL"
+        );
+
+        advisory.aliases.clear();
+        assert!(advisory.to_cve_record(org_id).is_err());
+    }
+}
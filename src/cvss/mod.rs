@@ -0,0 +1,198 @@
+//! Common Vulnerability Scoring System (CVSS) vectors, as used to describe
+//! the severity of the vulnerabilities in advisories
+//!
+//! <https://www.first.org/cvss/>
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use error::{Error, ErrorKind};
+
+/// Define an enum for a CVSS metric, i.e. the values it may have in vectors
+macro_rules! metric {
+    (
+        $(#[$attr:meta])*
+        $name:ident($abbr:expr) {
+            $($(#[$variant_attr:meta])* $variant:ident => $value:expr,)+
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+        pub enum $name {
+            $($(#[$variant_attr])* $variant,)+
+        }
+
+        impl $name {
+            /// Abbreviated name of this metric in vectors
+            pub const NAME: &'static str = $abbr;
+
+            /// Get the abbreviated value of this metric in vectors
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)+
+                }
+            }
+
+            /// Parse the abbreviated value of this metric in a vector
+            pub(crate) fn parse(value: &str) -> Result<Self, Error> {
+                match value {
+                    $($value => Ok($name::$variant),)+
+                    _ => Err(err!(
+                        ErrorKind::Parse,
+                        "invalid CVSS {} metric value: {}",
+                        $abbr,
+                        value
+                    )),
+                }
+            }
+        }
+    };
+}
+
+mod severity;
+pub mod v3;
+pub mod v4;
+
+pub use self::severity::Severity;
+
+/// CVSS vector of any supported version
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Cvss {
+    /// CVSS v3.0 or v3.1 vector
+    V3(v3::Vector),
+
+    /// CVSS v4.0 vector
+    V4(v4::Vector),
+}
+
+impl Cvss {
+    /// Compute the base score (from 0.0 to 10.0) of this vector
+    pub fn base_score(&self) -> f64 {
+        match *self {
+            Cvss::V3(ref vector) => vector.base_score(),
+            Cvss::V4(ref vector) => vector.base.score(),
+        }
+    }
+
+    /// Compute the score (from 0.0 to 10.0) of this vector, adjusted by any
+    /// temporal, threat, or environmental metrics it has
+    pub fn score(&self) -> f64 {
+        match *self {
+            Cvss::V3(ref vector) => vector.score(),
+            Cvss::V4(ref vector) => vector.score(),
+        }
+    }
+
+    /// Get the qualitative severity rating of this vector's score
+    pub fn severity(&self) -> Severity {
+        Severity::from_score(self.score())
+    }
+}
+
+impl FromStr for Cvss {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Error> {
+        if vector.starts_with("CVSS:3.") {
+            Ok(Cvss::V3(vector.parse()?))
+        } else if vector.starts_with("CVSS:4.") {
+            Ok(Cvss::V4(vector.parse()?))
+        } else {
+            Err(err!(
+                ErrorKind::Parse,
+                "unsupported CVSS vector: {}",
+                vector
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Cvss {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Cvss::V3(ref vector) => vector.fmt(f),
+            Cvss::V4(ref vector) => vector.fmt(f),
+        }
+    }
+}
+
+impl Serialize for Cvss {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cvss {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+/// Metrics of a CVSS vector (e.g. `AV:N`), as `(abbreviated name, value)` pairs
+pub(crate) type Metrics<'a> = Vec<(&'a str, &'a str)>;
+
+/// Split a CVSS vector into its version prefix (e.g. `CVSS:3.1`) and its
+/// metrics, rejecting metrics given more than once
+pub(crate) fn split_vector(vector: &str) -> Result<(&str, Metrics<'_>), Error> {
+    let mut parts = vector.split('/');
+    let prefix = parts.next().unwrap_or_default();
+    let mut metrics: Metrics = vec![];
+
+    for part in parts {
+        let mut name_and_value = part.splitn(2, ':');
+
+        let (name, value) = match (name_and_value.next(), name_and_value.next()) {
+            (Some(name), Some(value)) => (name, value),
+            _ => {
+                fail!(ErrorKind::Parse, "invalid CVSS metric: {}", part);
+            }
+        };
+
+        if metrics.iter().any(|&(existing, _)| existing == name) {
+            fail!(ErrorKind::Parse, "duplicate CVSS metric: {}", name);
+        }
+
+        metrics.push((name, value));
+    }
+
+    Ok((prefix, metrics))
+}
+
+/// Find the value of the metric with the given abbreviated name
+pub(crate) fn get_metric<'a>(metrics: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    metrics
+        .iter()
+        .find(|&&(metric, _)| metric == name)
+        .map(|&(_, value)| value)
+}
+
+/// Parse an optional metric, which is `None` if omitted or not defined (`X`)
+pub(crate) fn optional_metric<T, F>(
+    metrics: &[(&str, &str)],
+    name: &str,
+    parse: F,
+) -> Result<Option<T>, Error>
+where
+    F: Fn(&str) -> Result<T, Error>,
+{
+    match get_metric(metrics, name) {
+        None | Some("X") => Ok(None),
+        Some(value) => parse(value).map(Some),
+    }
+}
+
+/// Ensure a vector only contains metrics from the given groups
+pub(crate) fn ensure_known_metrics(
+    metrics: &[(&str, &str)],
+    groups: &[&[&str]],
+) -> Result<(), Error> {
+    for &(name, _) in metrics {
+        if !groups.iter().any(|group| group.contains(&name)) {
+            fail!(ErrorKind::Parse, "unknown CVSS metric: {}", name);
+        }
+    }
+
+    Ok(())
+}
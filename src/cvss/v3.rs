@@ -0,0 +1,700 @@
+//! CVSS v3.0 and v3.1 vectors
+//!
+//! <https://www.first.org/cvss/v3.1/specification-document>
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::{ensure_known_metrics, get_metric, optional_metric, split_vector};
+use error::{Error, ErrorKind};
+
+metric! {
+    /// Attack Vector (AV): how the vulnerability may be exploited
+    AttackVector("AV") {
+        /// Network (N)
+        Network => "N",
+        /// Adjacent (A)
+        Adjacent => "A",
+        /// Local (L)
+        Local => "L",
+        /// Physical (P)
+        Physical => "P",
+    }
+}
+
+metric! {
+    /// Attack Complexity (AC): conditions beyond the attacker's control which
+    /// must exist to exploit the vulnerability
+    AttackComplexity("AC") {
+        /// Low (L)
+        Low => "L",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// Privileges Required (PR): privileges the attacker must have
+    PrivilegesRequired("PR") {
+        /// None (N)
+        None => "N",
+        /// Low (L)
+        Low => "L",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// User Interaction (UI): whether a user other than the attacker must participate
+    UserInteraction("UI") {
+        /// None (N)
+        None => "N",
+        /// Required (R)
+        Required => "R",
+    }
+}
+
+metric! {
+    /// Scope (S): whether the vulnerability impacts components beyond the vulnerable one
+    Scope("S") {
+        /// Unchanged (U)
+        Unchanged => "U",
+        /// Changed (C)
+        Changed => "C",
+    }
+}
+
+metric! {
+    /// Impact of the vulnerability on Confidentiality (C), Integrity (I), or Availability (A)
+    Impact("C/I/A") {
+        /// None (N)
+        None => "N",
+        /// Low (L)
+        Low => "L",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// Exploit Code Maturity (E): likelihood of the vulnerability being attacked
+    ExploitCodeMaturity("E") {
+        /// Unproven (U)
+        Unproven => "U",
+        /// Proof-of-Concept (P)
+        ProofOfConcept => "P",
+        /// Functional (F)
+        Functional => "F",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// Remediation Level (RL): whether a fix or workaround is available
+    RemediationLevel("RL") {
+        /// Official Fix (O)
+        OfficialFix => "O",
+        /// Temporary Fix (T)
+        TemporaryFix => "T",
+        /// Workaround (W)
+        Workaround => "W",
+        /// Unavailable (U)
+        Unavailable => "U",
+    }
+}
+
+metric! {
+    /// Report Confidence (RC): confidence in the existence of the vulnerability
+    ReportConfidence("RC") {
+        /// Unknown (U)
+        Unknown => "U",
+        /// Reasonable (R)
+        Reasonable => "R",
+        /// Confirmed (C)
+        Confirmed => "C",
+    }
+}
+
+metric! {
+    /// Importance of the Confidentiality (CR), Integrity (IR), or Availability
+    /// (AR) of the affected component to an organization
+    Requirement("CR/IR/AR") {
+        /// Low (L)
+        Low => "L",
+        /// Medium (M)
+        Medium => "M",
+        /// High (H)
+        High => "H",
+    }
+}
+
+/// Abbreviated names of the base metrics, in the order they appear in vectors
+const BASE_METRICS: &[&str] = &["AV", "AC", "PR", "UI", "S", "C", "I", "A"];
+
+/// Abbreviated names of the temporal and environmental metrics, in the order
+/// they appear in vectors
+const OPTIONAL_METRICS: &[&str] = &[
+    "E", "RL", "RC", "CR", "IR", "AR", "MAV", "MAC", "MPR", "MUI", "MS", "MC", "MI", "MA",
+];
+
+/// Base metrics of a CVSS v3.0 or v3.1 vector, e.g.
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Base {
+    /// Minor version of CVSS v3 (i.e. 0 or 1)
+    pub minor_version: u8,
+
+    /// Attack Vector (AV)
+    pub attack_vector: AttackVector,
+
+    /// Attack Complexity (AC)
+    pub attack_complexity: AttackComplexity,
+
+    /// Privileges Required (PR)
+    pub privileges_required: PrivilegesRequired,
+
+    /// User Interaction (UI)
+    pub user_interaction: UserInteraction,
+
+    /// Scope (S)
+    pub scope: Scope,
+
+    /// Confidentiality Impact (C)
+    pub confidentiality: Impact,
+
+    /// Integrity Impact (I)
+    pub integrity: Impact,
+
+    /// Availability Impact (A)
+    pub availability: Impact,
+}
+
+impl Base {
+    /// Compute the base score (from 0.0 to 10.0) of this vector
+    pub fn score(&self) -> f64 {
+        let impact = self.impact();
+
+        if impact <= 0.0 {
+            return 0.0;
+        }
+
+        self.round_up(self.scope_adjusted(impact + self.exploitability()))
+    }
+
+    /// Compute the exploitability sub-score of this vector
+    pub fn exploitability(&self) -> f64 {
+        let attack_vector = match self.attack_vector {
+            AttackVector::Network => 0.85,
+            AttackVector::Adjacent => 0.62,
+            AttackVector::Local => 0.55,
+            AttackVector::Physical => 0.2,
+        };
+
+        let attack_complexity = match self.attack_complexity {
+            AttackComplexity::Low => 0.77,
+            AttackComplexity::High => 0.44,
+        };
+
+        let privileges_required = match (self.privileges_required, self.scope) {
+            (PrivilegesRequired::None, _) => 0.85,
+            (PrivilegesRequired::Low, Scope::Unchanged) => 0.62,
+            (PrivilegesRequired::Low, Scope::Changed) => 0.68,
+            (PrivilegesRequired::High, Scope::Unchanged) => 0.27,
+            (PrivilegesRequired::High, Scope::Changed) => 0.5,
+        };
+
+        let user_interaction = match self.user_interaction {
+            UserInteraction::None => 0.85,
+            UserInteraction::Required => 0.62,
+        };
+
+        8.22 * attack_vector * attack_complexity * privileges_required * user_interaction
+    }
+
+    /// Compute the impact sub-score of this vector
+    pub fn impact(&self) -> f64 {
+        let impact_sub_score = self.impact_sub_score([1.0; 3]);
+
+        match self.scope {
+            Scope::Unchanged => 6.42 * impact_sub_score,
+            Scope::Changed => {
+                7.52 * (impact_sub_score - 0.029) - 3.25 * (impact_sub_score - 0.02).powi(15)
+            }
+        }
+    }
+
+    /// Combine the impacts on confidentiality, integrity, and availability,
+    /// weighted by the given requirements
+    fn impact_sub_score(&self, requirements: [f64; 3]) -> f64 {
+        1.0 - (1.0 - requirements[0] * impact_weight(self.confidentiality))
+            * (1.0 - requirements[1] * impact_weight(self.integrity))
+            * (1.0 - requirements[2] * impact_weight(self.availability))
+    }
+
+    /// Adjust the sum of the impact and exploitability sub-scores for scope
+    fn scope_adjusted(&self, value: f64) -> f64 {
+        match self.scope {
+            Scope::Unchanged => value.min(10.0),
+            Scope::Changed => (1.08 * value).min(10.0),
+        }
+    }
+
+    /// Round up to one decimal place as specified by this vector's version
+    fn round_up(&self, value: f64) -> f64 {
+        if self.minor_version == 0 {
+            return (value * 10.0).ceil() / 10.0;
+        }
+
+        // CVSS v3.1 rounds via integers to avoid floating point errors
+        let int_input = (value * 100_000.0).round() as u64;
+
+        if int_input.is_multiple_of(10_000) {
+            int_input as f64 / 100_000.0
+        } else {
+            ((int_input / 10_000) + 1) as f64 / 10.0
+        }
+    }
+
+    /// Parse the base metrics of a vector which has already been split
+    fn from_metrics(prefix: &str, metrics: &[(&str, &str)]) -> Result<Self, Error> {
+        let minor_version = match prefix {
+            "CVSS:3.0" => 0,
+            "CVSS:3.1" => 1,
+            _ => {
+                fail!(ErrorKind::Parse, "unsupported CVSS version: {}", prefix);
+            }
+        };
+
+        let get = |name: &str| {
+            get_metric(metrics, name)
+                .ok_or_else(|| err!(ErrorKind::Parse, "missing CVSS {} metric", name))
+        };
+
+        Ok(Base {
+            minor_version,
+            attack_vector: AttackVector::parse(get("AV")?)?,
+            attack_complexity: AttackComplexity::parse(get("AC")?)?,
+            privileges_required: PrivilegesRequired::parse(get("PR")?)?,
+            user_interaction: UserInteraction::parse(get("UI")?)?,
+            scope: Scope::parse(get("S")?)?,
+            confidentiality: Impact::parse(get("C")?)?,
+            integrity: Impact::parse(get("I")?)?,
+            availability: Impact::parse(get("A")?)?,
+        })
+    }
+}
+
+/// Weight of the given impact on confidentiality, integrity, or availability
+fn impact_weight(impact: Impact) -> f64 {
+    match impact {
+        Impact::None => 0.0,
+        Impact::Low => 0.22,
+        Impact::High => 0.56,
+    }
+}
+
+impl FromStr for Base {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Error> {
+        let (prefix, metrics) = split_vector(vector)?;
+        ensure_known_metrics(&metrics, &[BASE_METRICS])?;
+        Base::from_metrics(prefix, &metrics)
+    }
+}
+
+impl fmt::Display for Base {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CVSS:3.{}/AV:{}/AC:{}/PR:{}/UI:{}/S:{}/C:{}/I:{}/A:{}",
+            self.minor_version,
+            self.attack_vector.as_str(),
+            self.attack_complexity.as_str(),
+            self.privileges_required.as_str(),
+            self.user_interaction.as_str(),
+            self.scope.as_str(),
+            self.confidentiality.as_str(),
+            self.integrity.as_str(),
+            self.availability.as_str()
+        )
+    }
+}
+
+impl Serialize for Base {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+/// Temporal metrics of a CVSS v3 vector, each of which is `None` if not
+/// defined (i.e. omitted or `X`)
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Temporal {
+    /// Exploit Code Maturity (E)
+    pub exploit_code_maturity: Option<ExploitCodeMaturity>,
+
+    /// Remediation Level (RL)
+    pub remediation_level: Option<RemediationLevel>,
+
+    /// Report Confidence (RC)
+    pub report_confidence: Option<ReportConfidence>,
+}
+
+impl Temporal {
+    /// Are none of the temporal metrics defined?
+    pub fn is_empty(&self) -> bool {
+        *self == Temporal::default()
+    }
+
+    /// Multiplier applied to a score by these temporal metrics
+    fn multiplier(&self) -> f64 {
+        let exploit_code_maturity = match self.exploit_code_maturity {
+            None | Some(ExploitCodeMaturity::High) => 1.0,
+            Some(ExploitCodeMaturity::Functional) => 0.97,
+            Some(ExploitCodeMaturity::ProofOfConcept) => 0.94,
+            Some(ExploitCodeMaturity::Unproven) => 0.91,
+        };
+
+        let remediation_level = match self.remediation_level {
+            None | Some(RemediationLevel::Unavailable) => 1.0,
+            Some(RemediationLevel::Workaround) => 0.97,
+            Some(RemediationLevel::TemporaryFix) => 0.96,
+            Some(RemediationLevel::OfficialFix) => 0.95,
+        };
+
+        let report_confidence = match self.report_confidence {
+            None | Some(ReportConfidence::Confirmed) => 1.0,
+            Some(ReportConfidence::Reasonable) => 0.96,
+            Some(ReportConfidence::Unknown) => 0.92,
+        };
+
+        exploit_code_maturity * remediation_level * report_confidence
+    }
+}
+
+/// Environmental metrics of a CVSS v3 vector, each of which is `None` if not
+/// defined (i.e. omitted or `X`)
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Environmental {
+    /// Confidentiality Requirement (CR)
+    pub confidentiality_requirement: Option<Requirement>,
+
+    /// Integrity Requirement (IR)
+    pub integrity_requirement: Option<Requirement>,
+
+    /// Availability Requirement (AR)
+    pub availability_requirement: Option<Requirement>,
+
+    /// Modified Attack Vector (MAV)
+    pub modified_attack_vector: Option<AttackVector>,
+
+    /// Modified Attack Complexity (MAC)
+    pub modified_attack_complexity: Option<AttackComplexity>,
+
+    /// Modified Privileges Required (MPR)
+    pub modified_privileges_required: Option<PrivilegesRequired>,
+
+    /// Modified User Interaction (MUI)
+    pub modified_user_interaction: Option<UserInteraction>,
+
+    /// Modified Scope (MS)
+    pub modified_scope: Option<Scope>,
+
+    /// Modified Confidentiality Impact (MC)
+    pub modified_confidentiality: Option<Impact>,
+
+    /// Modified Integrity Impact (MI)
+    pub modified_integrity: Option<Impact>,
+
+    /// Modified Availability Impact (MA)
+    pub modified_availability: Option<Impact>,
+}
+
+impl Environmental {
+    /// Are none of the environmental metrics defined?
+    pub fn is_empty(&self) -> bool {
+        *self == Environmental::default()
+    }
+
+    /// Apply the modified base metrics to the given base metrics
+    fn modify(&self, base: &Base) -> Base {
+        Base {
+            minor_version: base.minor_version,
+            attack_vector: self.modified_attack_vector.unwrap_or(base.attack_vector),
+            attack_complexity: self
+                .modified_attack_complexity
+                .unwrap_or(base.attack_complexity),
+            privileges_required: self
+                .modified_privileges_required
+                .unwrap_or(base.privileges_required),
+            user_interaction: self
+                .modified_user_interaction
+                .unwrap_or(base.user_interaction),
+            scope: self.modified_scope.unwrap_or(base.scope),
+            confidentiality: self
+                .modified_confidentiality
+                .unwrap_or(base.confidentiality),
+            integrity: self.modified_integrity.unwrap_or(base.integrity),
+            availability: self.modified_availability.unwrap_or(base.availability),
+        }
+    }
+
+    /// Weights of the confidentiality, integrity, and availability requirements
+    fn requirement_weights(&self) -> [f64; 3] {
+        let weight = |requirement| match requirement {
+            Some(Requirement::Low) => 0.5,
+            None | Some(Requirement::Medium) => 1.0,
+            Some(Requirement::High) => 1.5,
+        };
+
+        [
+            weight(self.confidentiality_requirement),
+            weight(self.integrity_requirement),
+            weight(self.availability_requirement),
+        ]
+    }
+}
+
+/// CVSS v3.0 or v3.1 vector, including any temporal and environmental
+/// metrics, e.g. `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/E:P/RL:O`
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Vector {
+    /// Base metrics
+    pub base: Base,
+
+    /// Temporal metrics
+    pub temporal: Temporal,
+
+    /// Environmental metrics
+    pub environmental: Environmental,
+}
+
+impl Vector {
+    /// Compute the base score of this vector
+    pub fn base_score(&self) -> f64 {
+        self.base.score()
+    }
+
+    /// Compute the temporal score of this vector, i.e. its base score
+    /// adjusted by its temporal metrics
+    pub fn temporal_score(&self) -> f64 {
+        self.base
+            .round_up(self.base.score() * self.temporal.multiplier())
+    }
+
+    /// Compute the environmental score of this vector, i.e. its score after
+    /// applying its environmental (and temporal) metrics
+    pub fn environmental_score(&self) -> f64 {
+        let modified = self.environmental.modify(&self.base);
+        let modified_impact_sub_score = modified
+            .impact_sub_score(self.environmental.requirement_weights())
+            .min(0.915);
+
+        let modified_impact = match (modified.scope, modified.minor_version) {
+            (Scope::Unchanged, _) => 6.42 * modified_impact_sub_score,
+            (Scope::Changed, 0) => {
+                7.52 * (modified_impact_sub_score - 0.029)
+                    - 3.25 * (modified_impact_sub_score - 0.02).powi(15)
+            }
+            (Scope::Changed, _) => {
+                7.52 * (modified_impact_sub_score - 0.029)
+                    - 3.25 * (modified_impact_sub_score * 0.9731 - 0.02).powi(13)
+            }
+        };
+
+        if modified_impact <= 0.0 {
+            return 0.0;
+        }
+
+        let score =
+            modified.round_up(modified.scope_adjusted(modified_impact + modified.exploitability()));
+
+        modified.round_up(score * self.temporal.multiplier())
+    }
+
+    /// Compute the most specific score of this vector: its environmental
+    /// score if it has environmental metrics, otherwise its temporal score if
+    /// it has temporal metrics, otherwise its base score
+    pub fn score(&self) -> f64 {
+        if !self.environmental.is_empty() {
+            self.environmental_score()
+        } else if !self.temporal.is_empty() {
+            self.temporal_score()
+        } else {
+            self.base_score()
+        }
+    }
+}
+
+impl From<Base> for Vector {
+    fn from(base: Base) -> Self {
+        Vector {
+            base,
+            temporal: Temporal::default(),
+            environmental: Environmental::default(),
+        }
+    }
+}
+
+impl FromStr for Vector {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Error> {
+        let (prefix, metrics) = split_vector(vector)?;
+        ensure_known_metrics(&metrics, &[BASE_METRICS, OPTIONAL_METRICS])?;
+
+        let temporal = Temporal {
+            exploit_code_maturity: optional_metric(&metrics, "E", ExploitCodeMaturity::parse)?,
+            remediation_level: optional_metric(&metrics, "RL", RemediationLevel::parse)?,
+            report_confidence: optional_metric(&metrics, "RC", ReportConfidence::parse)?,
+        };
+
+        let environmental = Environmental {
+            confidentiality_requirement: optional_metric(&metrics, "CR", Requirement::parse)?,
+            integrity_requirement: optional_metric(&metrics, "IR", Requirement::parse)?,
+            availability_requirement: optional_metric(&metrics, "AR", Requirement::parse)?,
+            modified_attack_vector: optional_metric(&metrics, "MAV", AttackVector::parse)?,
+            modified_attack_complexity: optional_metric(&metrics, "MAC", AttackComplexity::parse)?,
+            modified_privileges_required: optional_metric(
+                &metrics,
+                "MPR",
+                PrivilegesRequired::parse,
+            )?,
+            modified_user_interaction: optional_metric(&metrics, "MUI", UserInteraction::parse)?,
+            modified_scope: optional_metric(&metrics, "MS", Scope::parse)?,
+            modified_confidentiality: optional_metric(&metrics, "MC", Impact::parse)?,
+            modified_integrity: optional_metric(&metrics, "MI", Impact::parse)?,
+            modified_availability: optional_metric(&metrics, "MA", Impact::parse)?,
+        };
+
+        Ok(Vector {
+            base: Base::from_metrics(prefix, &metrics)?,
+            temporal,
+            environmental,
+        })
+    }
+}
+
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.base)?;
+
+        let temporal = &self.temporal;
+        let env = &self.environmental;
+
+        for &(name, value) in &[
+            ("E", temporal.exploit_code_maturity.map(|m| m.as_str())),
+            ("RL", temporal.remediation_level.map(|m| m.as_str())),
+            ("RC", temporal.report_confidence.map(|m| m.as_str())),
+            ("CR", env.confidentiality_requirement.map(|m| m.as_str())),
+            ("IR", env.integrity_requirement.map(|m| m.as_str())),
+            ("AR", env.availability_requirement.map(|m| m.as_str())),
+            ("MAV", env.modified_attack_vector.map(|m| m.as_str())),
+            ("MAC", env.modified_attack_complexity.map(|m| m.as_str())),
+            ("MPR", env.modified_privileges_required.map(|m| m.as_str())),
+            ("MUI", env.modified_user_interaction.map(|m| m.as_str())),
+            ("MS", env.modified_scope.map(|m| m.as_str())),
+            ("MC", env.modified_confidentiality.map(|m| m.as_str())),
+            ("MI", env.modified_integrity.map(|m| m.as_str())),
+            ("MA", env.modified_availability.map(|m| m.as_str())),
+        ] {
+            if let Some(value) = value {
+                write!(f, "/{}:{}", name, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Vector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Base, Vector};
+
+    #[test]
+    fn score_test() {
+        for &(vector, score) in &[
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H", 10.0),
+            ("CVSS:3.1/AV:L/AC:H/PR:L/UI:R/S:U/C:L/I:N/A:N", 2.2),
+            ("CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:C/C:L/I:L/A:N", 6.4),
+            ("CVSS:3.0/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:N", 5.9),
+            ("CVSS:3.1/AV:P/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N", 0.0),
+        ] {
+            let base = vector.parse::<Base>().unwrap();
+            assert_eq!(base.score(), score, "{}", vector);
+            assert_eq!(base.to_string(), vector);
+        }
+
+        assert!("CVSS:2.0/AV:N/AC:L/Au:N/C:P/I:P/A:P"
+            .parse::<Base>()
+            .is_err());
+        assert!("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/E:P"
+            .parse::<Base>()
+            .is_err());
+        assert!("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"
+            .parse::<Base>()
+            .is_err());
+        assert!("CVSS:3.1/AV:N/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+            .parse::<Base>()
+            .is_err());
+    }
+
+    #[test]
+    fn vector_score_test() {
+        for &(vector, base, temporal, environmental) in &[
+            (
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/E:P/RL:O/RC:C",
+                9.8,
+                8.8,
+                8.8,
+            ),
+            (
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/CR:L/IR:L/AR:L",
+                9.8,
+                9.8,
+                8.0,
+            ),
+        ] {
+            let parsed = vector.parse::<Vector>().unwrap();
+            assert_eq!(parsed.base_score(), base, "{}", vector);
+            assert_eq!(parsed.temporal_score(), temporal, "{}", vector);
+            assert_eq!(parsed.environmental_score(), environmental, "{}", vector);
+            assert_eq!(parsed.to_string(), vector);
+        }
+
+        let undefined = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/E:X/MAV:X"
+            .parse::<Vector>()
+            .unwrap();
+        assert!(undefined.temporal.is_empty() && undefined.environmental.is_empty());
+        assert_eq!(
+            undefined.to_string(),
+            "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+        );
+    }
+}
@@ -0,0 +1,818 @@
+//! CVSS v4.0 vectors
+//!
+//! <https://www.first.org/cvss/v4.0/specification-document>
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::{ensure_known_metrics, get_metric, optional_metric, split_vector};
+use error::{Error, ErrorKind};
+
+mod lookup;
+
+metric! {
+    /// Attack Vector (AV): how the vulnerability may be exploited
+    AttackVector("AV") {
+        /// Network (N)
+        Network => "N",
+        /// Adjacent (A)
+        Adjacent => "A",
+        /// Local (L)
+        Local => "L",
+        /// Physical (P)
+        Physical => "P",
+    }
+}
+
+metric! {
+    /// Attack Complexity (AC): measures the attacker must take to evade
+    /// security-enhancing conditions
+    AttackComplexity("AC") {
+        /// Low (L)
+        Low => "L",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// Attack Requirements (AT): deployment and execution conditions of the
+    /// vulnerable system which enable the attack
+    AttackRequirements("AT") {
+        /// None (N)
+        None => "N",
+        /// Present (P)
+        Present => "P",
+    }
+}
+
+metric! {
+    /// Privileges Required (PR): privileges the attacker must have
+    PrivilegesRequired("PR") {
+        /// None (N)
+        None => "N",
+        /// Low (L)
+        Low => "L",
+        /// High (H)
+        High => "H",
+    }
+}
+
+metric! {
+    /// User Interaction (UI): whether a user other than the attacker must participate
+    UserInteraction("UI") {
+        /// None (N)
+        None => "N",
+        /// Passive (P)
+        Passive => "P",
+        /// Active (A)
+        Active => "A",
+    }
+}
+
+metric! {
+    /// Impact on the Confidentiality (VC/SC), Integrity (VI/SI), or Availability
+    /// (VA/SA) of the vulnerable system or subsequent systems
+    Impact("VC/VI/VA/SC/SI/SA") {
+        /// High (H)
+        High => "H",
+        /// Low (L)
+        Low => "L",
+        /// None (N)
+        None => "N",
+    }
+}
+
+metric! {
+    /// Modified Subsequent System Integrity (MSI) or Availability (MSA)
+    /// Impact, which may additionally reflect a risk to human safety
+    ModifiedSubsequentImpact("MSI/MSA") {
+        /// Safety (S)
+        Safety => "S",
+        /// High (H)
+        High => "H",
+        /// Low (L)
+        Low => "L",
+        /// Negligible (N)
+        Negligible => "N",
+    }
+}
+
+metric! {
+    /// Exploit Maturity (E): likelihood of the vulnerability being attacked
+    ExploitMaturity("E") {
+        /// Attacked (A)
+        Attacked => "A",
+        /// POC (P)
+        ProofOfConcept => "P",
+        /// Unreported (U)
+        Unreported => "U",
+    }
+}
+
+metric! {
+    /// Importance of the Confidentiality (CR), Integrity (IR), or Availability
+    /// (AR) of the vulnerable system to an organization
+    Requirement("CR/IR/AR") {
+        /// High (H)
+        High => "H",
+        /// Medium (M)
+        Medium => "M",
+        /// Low (L)
+        Low => "L",
+    }
+}
+
+/// Abbreviated names of the base metrics, in the order they appear in vectors
+const BASE_METRICS: &[&str] = &[
+    "AV", "AC", "AT", "PR", "UI", "VC", "VI", "VA", "SC", "SI", "SA",
+];
+
+/// Abbreviated names of the threat and environmental metrics, in the order
+/// they appear in vectors
+const OPTIONAL_METRICS: &[&str] = &[
+    "E", "CR", "IR", "AR", "MAV", "MAC", "MAT", "MPR", "MUI", "MVC", "MVI", "MVA", "MSC", "MSI",
+    "MSA",
+];
+
+/// Base metrics of a CVSS v4.0 vector, e.g.
+/// `CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N`
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Base {
+    /// Attack Vector (AV)
+    pub attack_vector: AttackVector,
+
+    /// Attack Complexity (AC)
+    pub attack_complexity: AttackComplexity,
+
+    /// Attack Requirements (AT)
+    pub attack_requirements: AttackRequirements,
+
+    /// Privileges Required (PR)
+    pub privileges_required: PrivilegesRequired,
+
+    /// User Interaction (UI)
+    pub user_interaction: UserInteraction,
+
+    /// Vulnerable System Confidentiality Impact (VC)
+    pub vulnerable_confidentiality: Impact,
+
+    /// Vulnerable System Integrity Impact (VI)
+    pub vulnerable_integrity: Impact,
+
+    /// Vulnerable System Availability Impact (VA)
+    pub vulnerable_availability: Impact,
+
+    /// Subsequent System Confidentiality Impact (SC)
+    pub subsequent_confidentiality: Impact,
+
+    /// Subsequent System Integrity Impact (SI)
+    pub subsequent_integrity: Impact,
+
+    /// Subsequent System Availability Impact (SA)
+    pub subsequent_availability: Impact,
+}
+
+impl Base {
+    /// Compute the base score (CVSS-B, from 0.0 to 10.0) of this vector
+    pub fn score(&self) -> f64 {
+        Vector::from(*self).score()
+    }
+
+    /// Parse the base metrics of a vector which has already been split
+    fn from_metrics(prefix: &str, metrics: &[(&str, &str)]) -> Result<Self, Error> {
+        if prefix != "CVSS:4.0" {
+            fail!(ErrorKind::Parse, "unsupported CVSS version: {}", prefix);
+        }
+
+        let get = |name: &str| {
+            get_metric(metrics, name)
+                .ok_or_else(|| err!(ErrorKind::Parse, "missing CVSS {} metric", name))
+        };
+
+        Ok(Base {
+            attack_vector: AttackVector::parse(get("AV")?)?,
+            attack_complexity: AttackComplexity::parse(get("AC")?)?,
+            attack_requirements: AttackRequirements::parse(get("AT")?)?,
+            privileges_required: PrivilegesRequired::parse(get("PR")?)?,
+            user_interaction: UserInteraction::parse(get("UI")?)?,
+            vulnerable_confidentiality: Impact::parse(get("VC")?)?,
+            vulnerable_integrity: Impact::parse(get("VI")?)?,
+            vulnerable_availability: Impact::parse(get("VA")?)?,
+            subsequent_confidentiality: Impact::parse(get("SC")?)?,
+            subsequent_integrity: Impact::parse(get("SI")?)?,
+            subsequent_availability: Impact::parse(get("SA")?)?,
+        })
+    }
+}
+
+impl FromStr for Base {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Error> {
+        let (prefix, metrics) = split_vector(vector)?;
+        ensure_known_metrics(&metrics, &[BASE_METRICS])?;
+        Base::from_metrics(prefix, &metrics)
+    }
+}
+
+impl fmt::Display for Base {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CVSS:4.0/AV:{}/AC:{}/AT:{}/PR:{}/UI:{}/VC:{}/VI:{}/VA:{}/SC:{}/SI:{}/SA:{}",
+            self.attack_vector.as_str(),
+            self.attack_complexity.as_str(),
+            self.attack_requirements.as_str(),
+            self.privileges_required.as_str(),
+            self.user_interaction.as_str(),
+            self.vulnerable_confidentiality.as_str(),
+            self.vulnerable_integrity.as_str(),
+            self.vulnerable_availability.as_str(),
+            self.subsequent_confidentiality.as_str(),
+            self.subsequent_integrity.as_str(),
+            self.subsequent_availability.as_str()
+        )
+    }
+}
+
+impl Serialize for Base {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+/// Threat metrics of a CVSS v4.0 vector, which are `None` if not defined
+/// (i.e. omitted or `X`)
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Threat {
+    /// Exploit Maturity (E)
+    pub exploit_maturity: Option<ExploitMaturity>,
+}
+
+impl Threat {
+    /// Are none of the threat metrics defined?
+    pub fn is_empty(&self) -> bool {
+        *self == Threat::default()
+    }
+}
+
+/// Environmental metrics of a CVSS v4.0 vector, each of which is `None` if
+/// not defined (i.e. omitted or `X`)
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Environmental {
+    /// Confidentiality Requirement (CR)
+    pub confidentiality_requirement: Option<Requirement>,
+
+    /// Integrity Requirement (IR)
+    pub integrity_requirement: Option<Requirement>,
+
+    /// Availability Requirement (AR)
+    pub availability_requirement: Option<Requirement>,
+
+    /// Modified Attack Vector (MAV)
+    pub modified_attack_vector: Option<AttackVector>,
+
+    /// Modified Attack Complexity (MAC)
+    pub modified_attack_complexity: Option<AttackComplexity>,
+
+    /// Modified Attack Requirements (MAT)
+    pub modified_attack_requirements: Option<AttackRequirements>,
+
+    /// Modified Privileges Required (MPR)
+    pub modified_privileges_required: Option<PrivilegesRequired>,
+
+    /// Modified User Interaction (MUI)
+    pub modified_user_interaction: Option<UserInteraction>,
+
+    /// Modified Vulnerable System Confidentiality Impact (MVC)
+    pub modified_vulnerable_confidentiality: Option<Impact>,
+
+    /// Modified Vulnerable System Integrity Impact (MVI)
+    pub modified_vulnerable_integrity: Option<Impact>,
+
+    /// Modified Vulnerable System Availability Impact (MVA)
+    pub modified_vulnerable_availability: Option<Impact>,
+
+    /// Modified Subsequent System Confidentiality Impact (MSC)
+    pub modified_subsequent_confidentiality: Option<Impact>,
+
+    /// Modified Subsequent System Integrity Impact (MSI)
+    pub modified_subsequent_integrity: Option<ModifiedSubsequentImpact>,
+
+    /// Modified Subsequent System Availability Impact (MSA)
+    pub modified_subsequent_availability: Option<ModifiedSubsequentImpact>,
+}
+
+impl Environmental {
+    /// Are none of the environmental metrics defined?
+    pub fn is_empty(&self) -> bool {
+        *self == Environmental::default()
+    }
+}
+
+/// CVSS v4.0 vector, including any threat and environmental metrics, e.g.
+/// `CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/E:P`
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Vector {
+    /// Base metrics
+    pub base: Base,
+
+    /// Threat metrics
+    pub threat: Threat,
+
+    /// Environmental metrics
+    pub environmental: Environmental,
+}
+
+impl Vector {
+    /// Compute the score of this vector, taking all of its metrics into
+    /// account (i.e. CVSS-B, CVSS-BT, CVSS-BE, or CVSS-BTE)
+    pub fn score(&self) -> f64 {
+        Scoring::from(self).score()
+    }
+}
+
+impl From<Base> for Vector {
+    fn from(base: Base) -> Self {
+        Vector {
+            base,
+            threat: Threat::default(),
+            environmental: Environmental::default(),
+        }
+    }
+}
+
+impl FromStr for Vector {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Error> {
+        let (prefix, metrics) = split_vector(vector)?;
+        ensure_known_metrics(&metrics, &[BASE_METRICS, OPTIONAL_METRICS])?;
+
+        let threat = Threat {
+            exploit_maturity: optional_metric(&metrics, "E", ExploitMaturity::parse)?,
+        };
+
+        let environmental = Environmental {
+            confidentiality_requirement: optional_metric(&metrics, "CR", Requirement::parse)?,
+            integrity_requirement: optional_metric(&metrics, "IR", Requirement::parse)?,
+            availability_requirement: optional_metric(&metrics, "AR", Requirement::parse)?,
+            modified_attack_vector: optional_metric(&metrics, "MAV", AttackVector::parse)?,
+            modified_attack_complexity: optional_metric(&metrics, "MAC", AttackComplexity::parse)?,
+            modified_attack_requirements: optional_metric(
+                &metrics,
+                "MAT",
+                AttackRequirements::parse,
+            )?,
+            modified_privileges_required: optional_metric(
+                &metrics,
+                "MPR",
+                PrivilegesRequired::parse,
+            )?,
+            modified_user_interaction: optional_metric(&metrics, "MUI", UserInteraction::parse)?,
+            modified_vulnerable_confidentiality: optional_metric(&metrics, "MVC", Impact::parse)?,
+            modified_vulnerable_integrity: optional_metric(&metrics, "MVI", Impact::parse)?,
+            modified_vulnerable_availability: optional_metric(&metrics, "MVA", Impact::parse)?,
+            modified_subsequent_confidentiality: optional_metric(&metrics, "MSC", Impact::parse)?,
+            modified_subsequent_integrity: optional_metric(
+                &metrics,
+                "MSI",
+                ModifiedSubsequentImpact::parse,
+            )?,
+            modified_subsequent_availability: optional_metric(
+                &metrics,
+                "MSA",
+                ModifiedSubsequentImpact::parse,
+            )?,
+        };
+
+        Ok(Vector {
+            base: Base::from_metrics(prefix, &metrics)?,
+            threat,
+            environmental,
+        })
+    }
+}
+
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.base)?;
+
+        let env = &self.environmental;
+
+        for &(name, value) in &[
+            ("E", self.threat.exploit_maturity.map(|m| m.as_str())),
+            ("CR", env.confidentiality_requirement.map(|m| m.as_str())),
+            ("IR", env.integrity_requirement.map(|m| m.as_str())),
+            ("AR", env.availability_requirement.map(|m| m.as_str())),
+            ("MAV", env.modified_attack_vector.map(|m| m.as_str())),
+            ("MAC", env.modified_attack_complexity.map(|m| m.as_str())),
+            ("MAT", env.modified_attack_requirements.map(|m| m.as_str())),
+            ("MPR", env.modified_privileges_required.map(|m| m.as_str())),
+            ("MUI", env.modified_user_interaction.map(|m| m.as_str())),
+            (
+                "MVC",
+                env.modified_vulnerable_confidentiality.map(|m| m.as_str()),
+            ),
+            ("MVI", env.modified_vulnerable_integrity.map(|m| m.as_str())),
+            (
+                "MVA",
+                env.modified_vulnerable_availability.map(|m| m.as_str()),
+            ),
+            (
+                "MSC",
+                env.modified_subsequent_confidentiality.map(|m| m.as_str()),
+            ),
+            ("MSI", env.modified_subsequent_integrity.map(|m| m.as_str())),
+            (
+                "MSA",
+                env.modified_subsequent_availability.map(|m| m.as_str()),
+            ),
+        ] {
+            if let Some(value) = value {
+                write!(f, "/{}:{}", name, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Vector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+/// Abbreviated names of the metrics used to compute scores
+const SCORED_METRICS: [&str; 15] = [
+    "AV", "PR", "UI", "AC", "AT", "VC", "VI", "VA", "SC", "SI", "SA", "E", "CR", "IR", "AR",
+];
+
+/// Effective values of the metrics used to compute a score (in the order of
+/// `SCORED_METRICS`), following FIRST's reference implementation
+#[derive(Copy, Clone, Debug)]
+struct Scoring([&'static str; 15]);
+
+impl From<&Vector> for Scoring {
+    fn from(vector: &Vector) -> Self {
+        let base = &vector.base;
+        let environmental = &vector.environmental;
+
+        // Undefined threat and environmental metrics take their worst case
+        // values, i.e. the vulnerability is being attacked (E:A) and the
+        // vulnerable system's security is highly important (CR:H/IR:H/AR:H)
+        let requirement =
+            |requirement: Option<Requirement>| requirement.map(|r| r.as_str()).unwrap_or("H");
+
+        Scoring([
+            environmental
+                .modified_attack_vector
+                .unwrap_or(base.attack_vector)
+                .as_str(),
+            environmental
+                .modified_privileges_required
+                .unwrap_or(base.privileges_required)
+                .as_str(),
+            environmental
+                .modified_user_interaction
+                .unwrap_or(base.user_interaction)
+                .as_str(),
+            environmental
+                .modified_attack_complexity
+                .unwrap_or(base.attack_complexity)
+                .as_str(),
+            environmental
+                .modified_attack_requirements
+                .unwrap_or(base.attack_requirements)
+                .as_str(),
+            environmental
+                .modified_vulnerable_confidentiality
+                .unwrap_or(base.vulnerable_confidentiality)
+                .as_str(),
+            environmental
+                .modified_vulnerable_integrity
+                .unwrap_or(base.vulnerable_integrity)
+                .as_str(),
+            environmental
+                .modified_vulnerable_availability
+                .unwrap_or(base.vulnerable_availability)
+                .as_str(),
+            environmental
+                .modified_subsequent_confidentiality
+                .unwrap_or(base.subsequent_confidentiality)
+                .as_str(),
+            environmental
+                .modified_subsequent_integrity
+                .map(|m| m.as_str())
+                .unwrap_or_else(|| base.subsequent_integrity.as_str()),
+            environmental
+                .modified_subsequent_availability
+                .map(|m| m.as_str())
+                .unwrap_or_else(|| base.subsequent_availability.as_str()),
+            vector
+                .threat
+                .exploit_maturity
+                .map(|m| m.as_str())
+                .unwrap_or("A"),
+            requirement(environmental.confidentiality_requirement),
+            requirement(environmental.integrity_requirement),
+            requirement(environmental.availability_requirement),
+        ])
+    }
+}
+
+impl Scoring {
+    /// Combine partial vectors from the tables of highest severity vectors
+    fn from_max_vectors(vectors: &[&'static str]) -> Self {
+        let mut scoring = Scoring([""; 15]);
+
+        for metrics in vectors {
+            for metric in metrics.split('/') {
+                let (name, value) = metric.split_at(metric.find(':').unwrap());
+                let index = SCORED_METRICS.iter().position(|&m| m == name).unwrap();
+                scoring.0[index] = &value[1..];
+            }
+        }
+
+        scoring
+    }
+
+    /// Get the value of the metric with the given abbreviated name
+    fn get(&self, name: &str) -> &'static str {
+        self.0[SCORED_METRICS.iter().position(|&m| m == name).unwrap()]
+    }
+
+    /// Get the severity level of the metric with the given abbreviated name,
+    /// i.e. its distance from the metric's most severe value
+    fn level(&self, name: &str) -> f64 {
+        match (name, self.get(name)) {
+            ("AV", "N") | ("PR", "N") | ("UI", "N") | ("AC", "L") | ("AT", "N") => 0.0,
+            ("AV", "A") | ("PR", "L") | ("UI", "P") | ("AC", "H") | ("AT", "P") => 0.1,
+            ("AV", "L") | ("PR", "H") | ("UI", "A") => 0.2,
+            ("AV", "P") => 0.3,
+            ("VC", "H") | ("VI", "H") | ("VA", "H") => 0.0,
+            ("VC", "L") | ("VI", "L") | ("VA", "L") => 0.1,
+            ("VC", "N") | ("VI", "N") | ("VA", "N") => 0.2,
+            ("SI", "S") | ("SA", "S") => 0.0,
+            ("SC", "H") | ("SI", "H") | ("SA", "H") => 0.1,
+            ("SC", "L") | ("SI", "L") | ("SA", "L") => 0.2,
+            ("SC", "N") | ("SI", "N") | ("SA", "N") => 0.3,
+            ("E", "A") | ("CR", "H") | ("IR", "H") | ("AR", "H") => 0.0,
+            ("E", "P") | ("CR", "M") | ("IR", "M") | ("AR", "M") => 0.1,
+            ("E", "U") | ("CR", "L") | ("IR", "L") | ("AR", "L") => 0.2,
+            _ => 0.0,
+        }
+    }
+
+    /// Is the metric with the given abbreviated name set to the given value?
+    fn is(&self, name: &str, value: &str) -> bool {
+        self.get(name) == value
+    }
+
+    /// Compute the levels of the equivalence sets EQ1 to EQ6, which identify
+    /// the MacroVector this vector belongs to
+    fn equivalence_levels(&self) -> [u8; 6] {
+        let eq1 = if self.is("AV", "N") && self.is("PR", "N") && self.is("UI", "N") {
+            0
+        } else if (self.is("AV", "N") || self.is("PR", "N") || self.is("UI", "N"))
+            && !self.is("AV", "P")
+        {
+            1
+        } else {
+            2
+        };
+
+        let eq2 = if self.is("AC", "L") && self.is("AT", "N") {
+            0
+        } else {
+            1
+        };
+
+        let eq3 = if self.is("VC", "H") && self.is("VI", "H") {
+            0
+        } else if self.is("VC", "H") || self.is("VI", "H") || self.is("VA", "H") {
+            1
+        } else {
+            2
+        };
+
+        let eq4 = if self.is("SI", "S") || self.is("SA", "S") {
+            0
+        } else if self.is("SC", "H") || self.is("SI", "H") || self.is("SA", "H") {
+            1
+        } else {
+            2
+        };
+
+        let eq5 = match self.get("E") {
+            "A" => 0,
+            "P" => 1,
+            _ => 2,
+        };
+
+        let eq6 = if (self.is("CR", "H") && self.is("VC", "H"))
+            || (self.is("IR", "H") && self.is("VI", "H"))
+            || (self.is("AR", "H") && self.is("VA", "H"))
+        {
+            0
+        } else {
+            1
+        };
+
+        [eq1, eq2, eq3, eq4, eq5, eq6]
+    }
+
+    /// Compute the score: that of the vector's MacroVector, less the mean of
+    /// its (proportional) severity distances from the MacroVector's highest
+    /// severity vector towards the next lower MacroVectors
+    fn score(&self) -> f64 {
+        if ["VC", "VI", "VA", "SC", "SI", "SA"]
+            .iter()
+            .all(|name| self.is(name, "N"))
+        {
+            return 0.0;
+        }
+
+        let eqs = self.equivalence_levels();
+        let value = lookup::macro_vector_score(eqs).expect("MacroVector should exist");
+
+        let lower = |index: usize| {
+            let mut lower_eqs = eqs;
+            lower_eqs[index] += 1;
+            lookup::macro_vector_score(lower_eqs)
+        };
+
+        // EQ3 and EQ6 are scored together
+        let lower_eq3_eq6 = match (eqs[2], eqs[5]) {
+            (0, 0) => {
+                let (left, right) = (lower(5), lower(2));
+                if left > right {
+                    left
+                } else {
+                    right
+                }
+            }
+            (1, 0) => lower(5),
+            _ => lower(2),
+        };
+
+        // Find the first highest severity vector in the MacroVector from which
+        // this vector is no more severe in any metric
+        let mut distances = [0.0; 15];
+
+        'search: for eq1 in lookup::max_vectors_eq1(eqs[0]) {
+            for eq2 in lookup::max_vectors_eq2(eqs[1]) {
+                for eq3_eq6 in lookup::max_vectors_eq3_eq6(eqs[2], eqs[5]) {
+                    for eq4 in lookup::max_vectors_eq4(eqs[3]) {
+                        for eq5 in lookup::max_vectors_eq5(eqs[4]) {
+                            let max = Scoring::from_max_vectors(&[eq1, eq2, eq3_eq6, eq4, eq5]);
+
+                            let mut is_max = true;
+
+                            for (i, name) in SCORED_METRICS.iter().enumerate() {
+                                distances[i] = self.level(name) - max.level(name);
+                                is_max = is_max && distances[i] >= 0.0;
+                            }
+
+                            if is_max {
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let distance = |names: &[&str]| -> f64 {
+            names
+                .iter()
+                .map(|name| distances[SCORED_METRICS.iter().position(|m| m == name).unwrap()])
+                .sum()
+        };
+
+        let max_severity = lookup::max_severity(eqs[0], eqs[1], eqs[2], eqs[5], eqs[3]);
+
+        let eq_distances = [
+            (lower(0), distance(&["AV", "PR", "UI"]), max_severity[0]),
+            (lower(1), distance(&["AC", "AT"]), max_severity[1]),
+            (
+                lower_eq3_eq6,
+                distance(&["VC", "VI", "VA", "CR", "IR", "AR"]),
+                max_severity[2],
+            ),
+            (lower(3), distance(&["SC", "SI", "SA"]), max_severity[3]),
+            // The severity distance within EQ5 is always 0
+            (lower(4), 0.0, 1.0),
+        ];
+
+        let mut existing_lower = 0;
+        let mut normalized_distances = 0.0;
+
+        for &(lower_score, severity_distance, max_severity) in &eq_distances {
+            if let Some(lower_score) = lower_score {
+                existing_lower += 1;
+                normalized_distances += (value - lower_score) * severity_distance / max_severity;
+            }
+        }
+
+        let mean_distance = if existing_lower == 0 {
+            0.0
+        } else {
+            normalized_distances / f64::from(existing_lower)
+        };
+
+        round(value - mean_distance)
+    }
+}
+
+/// Round a score to one decimal place (as FIRST's reference implementation does)
+fn round(score: f64) -> f64 {
+    let score = score.clamp(0.0, 10.0);
+    ((score + 0.000_01) * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Base, Vector};
+
+    #[test]
+    fn score_test() {
+        for &(vector, score) in &[
+            (
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N",
+                9.3,
+            ),
+            (
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:H/SI:H/SA:H",
+                10.0,
+            ),
+            (
+                "CVSS:4.0/AV:L/AC:L/AT:N/PR:L/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N",
+                8.5,
+            ),
+            (
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:N/VI:N/VA:N/SC:N/SI:N/SA:N",
+                0.0,
+            ),
+        ] {
+            let base = vector.parse::<Base>().unwrap();
+            assert_eq!(base.score(), score, "{}", vector);
+            assert_eq!(base.to_string(), vector);
+        }
+
+        assert!("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+            .parse::<Base>()
+            .is_err());
+        assert!("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H"
+            .parse::<Base>()
+            .is_err());
+    }
+
+    #[test]
+    fn vector_score_test() {
+        for &(vector, score) in &[
+            (
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/E:U",
+                8.1,
+            ),
+            (
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/CR:L/IR:L/AR:L",
+                8.9,
+            ),
+            (
+                "CVSS:4.0/AV:L/AC:L/AT:N/PR:L/UI:N/VC:N/VI:N/VA:N/SC:N/SI:N/SA:N/MSI:S",
+                7.7,
+            ),
+        ] {
+            let parsed = vector.parse::<Vector>().unwrap();
+            assert_eq!(parsed.score(), score, "{}", vector);
+            assert_eq!(parsed.to_string(), vector);
+        }
+
+        assert!(
+            "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/E:X"
+                .parse::<Vector>()
+                .unwrap()
+                .threat
+                .is_empty()
+        );
+    }
+}
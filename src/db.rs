@@ -1,22 +1,314 @@
 //! Database containing `RustSec` security advisories
 
+#[cfg(feature = "chrono")]
+use chrono::NaiveTime;
+#[cfg(feature = "json")]
+use platforms::target::{Arch, OS};
 use semver::Version;
-use std::collections::{btree_map, BTreeMap};
-use std::ffi::{OsStr, OsString};
-use toml;
+#[cfg(feature = "json")]
+use semver::VersionReq;
+#[cfg(feature = "osv")]
+use osv;
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(any(feature = "json", feature = "osv"))]
+use serde_json;
+#[cfg(feature = "json")]
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+#[cfg(feature = "osv")]
+use std::fs;
+use std::hash::Hash;
+#[cfg(feature = "json")]
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use advisory::{self, Advisory, AdvisoryId, AdvisoryWrapper};
+use advisory::{
+    self, Advisory, AdvisoryId, Collection, Date, Informational, License, Lint, LintLevel,
+};
+#[cfg(feature = "json")]
+use advisory::{Category, Cwe, Keyword, Url};
+#[cfg(feature = "archive")]
+use archive::Archive;
+#[cfg(feature = "snapshot")]
+use archive::Snapshot;
+#[cfg(feature = "cache")]
+use cache::{self, Cache};
+#[cfg(feature = "json")]
+use cvss::{Cvss, Severity};
+use diff::Diff;
 use error::{Error, ErrorKind};
 use lockfile::Lockfile;
 use package::PackageName;
-use repository::Repository;
+use query::{self, Query};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use repository::{self, RepoFile, Repository, VendoredDirectory};
+use stats::Statistics;
 use vulnerability::Vulnerabilities;
+use warning::Warning;
 
-/// A collection of security advisories, indexed both by ID and crate
+/// Source of advisories to load into an `AdvisoryDatabase`
+pub enum Source<'a> {
+    /// Git repository (see `AdvisoryDatabase::from_repository`)
+    Repository(&'a Repository),
+
+    /// Unpacked archive (see `AdvisoryDatabase::from_archive`)
+    #[cfg(feature = "archive")]
+    Archive(&'a Archive),
+
+    /// Local directory (see `AdvisoryDatabase::open`)
+    Directory(&'a Path),
+
+    /// Vendored copy without Git metadata (see `AdvisoryDatabase::from_vendored`)
+    Vendored(&'a VendoredDirectory),
+
+    /// Signed snapshot (see `AdvisoryDatabase::from_snapshot`)
+    #[cfg(feature = "snapshot")]
+    Snapshot(&'a Snapshot),
+
+    /// Directory of OSV entries (see `AdvisoryDatabase::open_osv`)
+    #[cfg(feature = "osv")]
+    Osv(&'a Path),
+}
+
+/// What to do when merging databases which contain advisories with the same ID
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Fail with an error
+    Error,
+
+    /// Keep the advisory which was loaded first
+    KeepFirst,
+
+    /// Replace it with the advisory which was loaded last
+    KeepLast,
+
+    /// Keep whichever advisory was issued most recently (or the one which
+    /// was loaded first, if they were issued on the same date)
+    KeepNewest,
+}
+
+/// How to handle advisory files which can't be loaded (e.g. because they're
+/// malformed) when loading a database
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ParseMode {
+    /// Fail with an error (e.g. for checking the advisory DB in CI)
+    #[default]
+    Strict,
+
+    /// Skip the file, recording a `ParseWarning` about it (see
+    /// `AdvisoryDatabase::parse_warnings`)
+    Lenient,
+}
+
+/// Options controlling how advisory files are loaded into a database
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LoadOptions {
+    /// How to handle advisory files which can't be loaded
+    pub mode: ParseMode,
+
+    /// Reject advisories with URLs (or references) which aren't `https://`
+    pub https_only: bool,
+
+    /// Don't keep the descriptions of advisories in memory (leaving their
+    /// `description` fields empty), reading them from the advisory files
+    /// when they're needed instead (see `AdvisoryDatabase::description`)
+    pub lazy_descriptions: bool,
+}
+
+/// An advisory file which was skipped when loading a database in
+/// `ParseMode::Lenient`
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// Path to the advisory file
+    pub path: PathBuf,
+
+    /// Why the file couldn't be loaded
+    pub error: Error,
+
+    /// Problems with the file's name or location (e.g. it isn't named after
+    /// the advisory in it), if that's why it couldn't be loaded
+    pub lints: Vec<Lint>,
+}
+
+/// Options controlling which advisories are matched against packages
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchOptions {
+    /// Also match advisories which have been withdrawn (e.g. for research)
+    pub include_withdrawn: bool,
+
+    /// Also match informational advisories (e.g. about unmaintained crates),
+    /// which are otherwise only reported as warnings
+    pub include_informational: bool,
+
+    /// Collection of packages to match advisories from (crates.io crates by
+    /// default, or e.g. `Collection::Rust` for advisories about `std`)
+    pub collection: Collection,
+}
+
+/// A collection of security advisories, indexed by ID, alias, and crate.
+///
+/// Databases are serialized as a map of the advisories in each collection
+/// (e.g. `{ "crates": [...], "rust": [...] }`), since advisories themselves
+/// don't say which collection they're in. Parse warnings aren't serialized,
+/// and neither are descriptions which haven't been loaded yet (see
+/// `LoadOptions::lazy_descriptions`).
 #[derive(Debug)]
 pub struct AdvisoryDatabase {
     advisories: BTreeMap<AdvisoryId, Advisory>,
-    crates: BTreeMap<PackageName, Vec<AdvisoryId>>,
+    aliases: HashMap<AdvisoryId, Vec<AdvisoryId>>,
+    crates: HashMap<PackageName, Vec<AdvisoryId>>,
+    description_paths: HashMap<AdvisoryId, PathBuf>,
+    parse_warnings: Vec<ParseWarning>,
+}
+
+impl Serialize for AdvisoryDatabase {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut collections = BTreeMap::new();
+
+        for advisory in self.iter() {
+            collections
+                .entry(advisory.collection)
+                .or_insert_with(Vec::new)
+                .push(advisory);
+        }
+
+        collections.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AdvisoryDatabase {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let collections = BTreeMap::<Collection, Vec<Advisory>>::deserialize(deserializer)?;
+
+        let advisories = collections
+            .into_iter()
+            .flat_map(|(collection, advisories)| {
+                advisories.into_iter().map(move |advisory| Advisory {
+                    collection,
+                    ..advisory
+                })
+            });
+
+        Self::from_advisories(advisories).map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+/// Version of the schema of documents written by `AdvisoryDatabase::export_json`,
+/// which will be incremented if it ever changes incompatibly
+#[cfg(feature = "json")]
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Document written by `AdvisoryDatabase::export_json`
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    /// Version of the schema of this document
+    schema_version: u32,
+
+    /// Every advisory in the database, in order of their IDs
+    advisories: Vec<JsonAdvisory<'a>>,
+}
+
+/// Advisory in a document written by `AdvisoryDatabase::export_json`. This is
+/// its own schema (rather than the serialized `Advisory`) so that it only
+/// changes along with `JSON_SCHEMA_VERSION`, and every field is always present
+/// with a plain JSON type (e.g. strings rather than structured IDs).
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct JsonAdvisory<'a> {
+    id: &'a str,
+    package: &'a str,
+    collection: &'static str,
+    date: &'a str,
+    withdrawn: Option<&'a str>,
+    informational: Option<&'a str>,
+    title: &'a str,
+    description: Cow<'a, str>,
+    url: Option<&'a str>,
+    aliases: Vec<&'a str>,
+    related: Vec<&'a str>,
+    references: Vec<JsonLink<'a>>,
+    patches: Vec<JsonLink<'a>>,
+    categories: Vec<&'a str>,
+    cwe: Vec<String>,
+    keywords: Vec<&'a str>,
+    cvss: Option<String>,
+    severity: Option<&'static str>,
+    license: &'static str,
+    patched_versions: Vec<String>,
+    unaffected_versions: Vec<String>,
+    affected_arch: Vec<&'static str>,
+    affected_os: Vec<&'static str>,
+    affected_functions: BTreeMap<&'a str, Vec<String>>,
+}
+
+/// Reference or patch in a document written by `AdvisoryDatabase::export_json`
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct JsonLink<'a> {
+    kind: &'a str,
+    url: &'a str,
+}
+
+#[cfg(feature = "json")]
+impl<'a> JsonAdvisory<'a> {
+    /// Describe the given advisory, which has the given description (since
+    /// it may have been loaded separately)
+    fn new(advisory: &'a Advisory, description: Cow<'a, str>) -> Self {
+        let to_strings = |reqs: &[VersionReq]| reqs.iter().map(|req| req.to_string()).collect();
+
+        JsonAdvisory {
+            id: advisory.id.as_str(),
+            package: advisory.package.as_str(),
+            collection: advisory.collection.as_str(),
+            date: advisory.date.as_str(),
+            withdrawn: advisory.withdrawn.as_ref().map(Date::as_str),
+            informational: advisory.informational.as_ref().map(Informational::as_str),
+            title: &advisory.title,
+            description,
+            url: advisory.url.as_ref().map(Url::as_str),
+            aliases: advisory.aliases.iter().map(AdvisoryId::as_str).collect(),
+            related: advisory.related.iter().map(AdvisoryId::as_str).collect(),
+            references: advisory
+                .references
+                .iter()
+                .map(|reference| JsonLink {
+                    kind: reference.kind.as_str(),
+                    url: reference.url.as_str(),
+                })
+                .collect(),
+            patches: advisory
+                .patches
+                .iter()
+                .map(|patch| JsonLink {
+                    kind: patch.kind.as_str(),
+                    url: patch.url.as_str(),
+                })
+                .collect(),
+            categories: advisory.categories.iter().map(Category::as_str).collect(),
+            cwe: advisory.cwe.iter().map(Cwe::to_string).collect(),
+            keywords: advisory.keywords.iter().map(Keyword::as_str).collect(),
+            cvss: advisory.cvss.as_ref().map(Cvss::to_string),
+            severity: advisory.severity().map(Severity::as_str),
+            license: advisory.license().as_str(),
+            patched_versions: to_strings(&advisory.versions.patched),
+            unaffected_versions: to_strings(&advisory.versions.unaffected),
+            affected_arch: advisory
+                .affected_arch()
+                .into_iter()
+                .map(Arch::as_str)
+                .collect(),
+            affected_os: advisory.affected_os().into_iter().map(OS::as_str).collect(),
+            affected_functions: advisory
+                .affected
+                .iter()
+                .flat_map(|affected| affected.functions.iter())
+                .map(|(path, reqs)| (path.as_str(), to_strings(reqs)))
+                .collect(),
+        }
+    }
 }
 
 impl AdvisoryDatabase {
@@ -27,105 +319,1110 @@ impl AdvisoryDatabase {
         Self::from_repository(&repo)
     }
 
+    /// Fetch the default advisory database as a tarball from GitHub (without using Git)
+    #[cfg(feature = "archive")]
+    pub fn fetch_archive() -> Result<Self, Error> {
+        let archive = Archive::fetch_default_archive()?;
+        Self::from_archive(&archive)
+    }
+
+    /// Load an advisory database from a local directory with the same layout
+    /// as the `advisory-db` repository (e.g. a vendored copy, or one generated
+    /// by a custom pipeline), without using Git. Only `.toml` and `.md` files
+    /// in the package directories are loaded.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with(path, &LoadOptions::default())
+    }
+
+    /// Load an advisory database from a local directory, handling advisory
+    /// files which can't be loaded according to the given options
+    pub fn open_with<P: AsRef<Path>>(path: P, options: &LoadOptions) -> Result<Self, Error> {
+        Self::from_advisory_files(repository::Iter::new(path.as_ref())?, options)
+    }
+
     /// Create a new `AdvisoryDatabase` from the given `Repository`
     pub fn from_repository(repo: &Repository) -> Result<Self, Error> {
-        let mut advisories = BTreeMap::new();
-        let mut crates = BTreeMap::new();
-
-        for advisory_file in repo.crate_advisories()? {
-            let AdvisoryWrapper { advisory } = toml::from_str(&advisory_file.read_to_string()?)?;
-
-            if !advisory.id.is_rustsec() {
-                fail!(
-                    ErrorKind::Parse,
-                    "expected a RUSTSEC advisory ID: {}",
-                    advisory.id
-                );
+        Self::from_repository_with(repo, &LoadOptions::default())
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given `Repository`, handling
+    /// advisory files which can't be loaded according to the given options
+    pub fn from_repository_with(repo: &Repository, options: &LoadOptions) -> Result<Self, Error> {
+        Self::from_advisory_files(repo.crate_advisories()?, options)
+    }
+
+    /// Create a new `AdvisoryDatabase` from the advisories in the given commit
+    /// of the `Repository` (e.g. to see what was known at the time of an
+    /// earlier audit), without checking it out
+    pub fn from_repository_at(repo: &Repository, commit_id: &str) -> Result<Self, Error> {
+        let mut advisories = vec![];
+
+        for (path, contents) in repo.crate_advisories_at(commit_id)? {
+            let advisory = load_advisory(&path, &contents, &LoadOptions::default())
+                .map_err(|warning| warning.error)?;
+
+            advisories.push(advisory);
+        }
+
+        Self::from_advisories(advisories)
+    }
+
+    /// Create a new `AdvisoryDatabase` as of the end of the given date (UTC),
+    /// from the last commit to the `Repository` made by then, with only the
+    /// advisories issued by then (see `AdvisoryDatabase::as_of`)
+    #[cfg(feature = "chrono")]
+    pub fn from_repository_as_of(repo: &Repository, date: &Date) -> Result<Self, Error> {
+        let end = date
+            .to_naive_date()
+            .succ_opt()
+            .map(|next_day| next_day.and_time(NaiveTime::MIN).and_utc());
+
+        for commit in repo.commits()? {
+            let commit = commit?;
+
+            if end.map(|end| commit.time < end).unwrap_or(true) {
+                return Ok(Self::from_repository_at(repo, &commit.commit_id)?.as_of(date));
             }
+        }
+
+        fail!(
+            ErrorKind::BadParam,
+            "no commits to the repository as of {}",
+            date
+        );
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given `Repository`, loading it
+    /// from the given cache if it was saved from the repository's latest
+    /// commit, and otherwise parsing the advisory files and saving them to it
+    #[cfg(feature = "cache")]
+    pub fn from_repository_cached(repo: &Repository, cache: &Cache) -> Result<Self, Error> {
+        let key = cache::commit_key(&repo.latest_commit()?.commit_id);
 
-            let advisory_path = advisory_file.path().to_owned();
-            let expected_filename = OsString::from(format!("{}.toml", advisory.id));
-
-            // Ensure advisory has the correct filename
-            if advisory_path.file_name().unwrap() != expected_filename {
-                fail!(
-                    ErrorKind::Repo,
-                    "expected {} to be named {:?}",
-                    advisory_file.path().display(),
-                    expected_filename
-                );
+        if let Some(db) = cache.load(&key) {
+            return Ok(db);
+        }
+
+        let db = Self::from_repository(repo)?;
+
+        // Failing to save the cache (e.g. because its directory is read-only)
+        // only means the advisory files will be parsed again next time
+        let _ = cache.save(&key, &db);
+
+        Ok(db)
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given unpacked `Archive`
+    #[cfg(feature = "archive")]
+    pub fn from_archive(archive: &Archive) -> Result<Self, Error> {
+        Self::from_archive_with(archive, &LoadOptions::default())
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given unpacked `Archive`,
+    /// handling advisory files which can't be loaded according to the given options
+    #[cfg(feature = "archive")]
+    pub fn from_archive_with(archive: &Archive, options: &LoadOptions) -> Result<Self, Error> {
+        Self::from_advisory_files(archive.crate_advisories()?, options)
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given `VendoredDirectory`
+    pub fn from_vendored(vendored: &VendoredDirectory) -> Result<Self, Error> {
+        Self::from_vendored_with(vendored, &LoadOptions::default())
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given `VendoredDirectory`,
+    /// handling advisory files which can't be loaded according to the given options
+    pub fn from_vendored_with(
+        vendored: &VendoredDirectory,
+        options: &LoadOptions,
+    ) -> Result<Self, Error> {
+        Self::from_advisory_files(vendored.crate_advisories()?, options)
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given (verified) `Snapshot`
+    #[cfg(feature = "snapshot")]
+    pub fn from_snapshot(snapshot: &Snapshot) -> Result<Self, Error> {
+        Self::from_advisories(snapshot.advisories().iter().cloned())
+    }
+
+    /// Load advisories from a directory of OSV entries (i.e. `.json` files),
+    /// e.g. from an OSV-only private database. Entries which don't affect a
+    /// crates.io package are skipped.
+    #[cfg(feature = "osv")]
+    pub fn open_osv<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut advisories = vec![];
+
+        for dir_entry in fs::read_dir(path)? {
+            let path = dir_entry?.path();
+
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+
+            let entry: osv::Entry = serde_json::from_slice(&fs::read(&path)?)
+                .map_err(|e| err!(ErrorKind::Parse, "invalid OSV entry {}: {}", path.display(), e))?;
+
+            let affects_crate = entry
+                .affected
+                .iter()
+                .any(|affected| affected.package.ecosystem == osv::CRATES_IO_ECOSYSTEM);
+
+            if affects_crate {
+                advisories.push(Advisory::from_osv(&entry)?);
+            }
+        }
+
+        Ok(Self::index(advisories))
+    }
+
+    /// Load and merge advisories from several sources (e.g. the RustSec
+    /// advisory DB along with a private one), in the given order
+    pub fn load_all(sources: &[Source], on_duplicate: DuplicatePolicy) -> Result<Self, Error> {
+        let mut db = Self::index(vec![]);
+
+        for source in sources {
+            let other = match *source {
+                Source::Repository(repo) => Self::from_repository(repo)?,
+                #[cfg(feature = "archive")]
+                Source::Archive(archive) => Self::from_archive(archive)?,
+                Source::Directory(path) => Self::open(path)?,
+                Source::Vendored(vendored) => Self::from_vendored(vendored)?,
+                #[cfg(feature = "snapshot")]
+                Source::Snapshot(snapshot) => Self::from_snapshot(snapshot)?,
+                #[cfg(feature = "osv")]
+                Source::Osv(path) => Self::open_osv(path)?,
+            };
+
+            db.merge(other, on_duplicate)?;
+        }
+
+        Ok(db)
+    }
+
+    /// Merge the advisories from another database into this one (e.g. a
+    /// private database into the public one), resolving advisories with the
+    /// same ID according to the given policy
+    pub fn merge(&mut self, other: Self, on_duplicate: DuplicatePolicy) -> Result<(), Error> {
+        // Check for duplicates up front, so this database is left unchanged
+        if on_duplicate == DuplicatePolicy::Error {
+            if let Some(id) = other
+                .advisories
+                .keys()
+                .find(|id| self.advisories.contains_key(id))
+            {
+                fail!(ErrorKind::Repo, "duplicate advisory ID: {}", id);
             }
+        }
+
+        let mut description_paths = other.description_paths;
+
+        for (id, advisory) in other.advisories {
+            if let Some(existing) = self.advisories.get(&id) {
+                match on_duplicate {
+                    DuplicatePolicy::Error => unreachable!("duplicates were checked for above"),
+                    DuplicatePolicy::KeepFirst => continue,
+                    DuplicatePolicy::KeepNewest if advisory.date <= existing.date => continue,
+                    DuplicatePolicy::KeepLast | DuplicatePolicy::KeepNewest => {
+                        unindex(&mut self.crates, &existing.package, &id);
 
-            // Ensure advisory is in the correct directory
-            let advisory_parent_dir = advisory_path.parent().unwrap().file_name().unwrap();
-
-            if advisory_parent_dir != OsStr::new(advisory.package.as_str()) {
-                fail!(
-                    ErrorKind::Repo,
-                    "expected {} to be in {} directory (instead of \"{:?}\")",
-                    advisory.id,
-                    advisory.package,
-                    advisory_parent_dir
-                );
+                        for alias in &existing.aliases {
+                            unindex(&mut self.aliases, alias, &id);
+                        }
+                    }
+                }
             }
 
+            match description_paths.remove(&id) {
+                Some(path) => self.description_paths.insert(id, path),
+                None => self.description_paths.remove(&id),
+            };
+
+            self.insert(advisory);
+        }
+
+        self.parse_warnings.extend(other.parse_warnings);
+        Ok(())
+    }
+
+    /// Load advisories from the given advisory files
+    fn from_advisory_files(
+        advisory_files: repository::Iter,
+        options: &LoadOptions,
+    ) -> Result<Self, Error> {
+        let mut advisories = vec![];
+        let mut description_paths = HashMap::new();
+        let mut parse_warnings = vec![];
+
+        let load = |advisory_file: &RepoFile| {
+            let result = load_advisory_file(advisory_file, options);
+            (advisory_file.path().to_owned(), result)
+        };
+
+        // Files are parsed in parallel if possible, but the results are
+        // collected in the same order either way
+        #[cfg(feature = "rayon")]
+        let results = advisory_files
+            .collect::<Vec<_>>()
+            .par_iter()
+            .map(load)
+            .collect::<Vec<_>>();
+
+        #[cfg(not(feature = "rayon"))]
+        let results = advisory_files.map(|advisory_file| load(&advisory_file));
+
+        for (path, result) in results {
+            match result {
+                Ok(advisory) => {
+                    if options.lazy_descriptions {
+                        description_paths.insert(advisory.id.clone(), path);
+                    }
+
+                    advisories.push(advisory);
+                }
+                Err(warning) => match options.mode {
+                    ParseMode::Strict => return Err(warning.error),
+                    ParseMode::Lenient => parse_warnings.push(warning),
+                },
+            }
+        }
+
+        let mut db = Self::from_advisories(advisories)?;
+        db.description_paths = description_paths;
+        db.parse_warnings = parse_warnings;
+        Ok(db)
+    }
+
+    /// Create a new `AdvisoryDatabase` from the given advisories, e.g. ones
+    /// built in code with `AdvisoryBuilder::fixture` for testing matching
+    /// logic without fixture files. The advisories must have RUSTSEC IDs,
+    /// and any with the placeholder ID are left out.
+    pub fn from_advisories<I: IntoIterator<Item = Advisory>>(
+        advisory_iter: I,
+    ) -> Result<Self, Error> {
+        let mut checked = vec![];
+
+        for advisory in advisory_iter {
+            check_advisory(&advisory)?;
+
             // Ensure placeholder advisories load and parse correctly, but
             // don't actually insert them into the advisory database
             if advisory.id.is_placeholder() {
                 continue;
             }
 
-            let mut crate_advisories = match crates.entry(advisory.package.clone()) {
-                btree_map::Entry::Vacant(entry) => entry.insert(vec![]),
-                btree_map::Entry::Occupied(entry) => entry.into_mut(),
-            };
+            checked.push(advisory);
+        }
+
+        Ok(Self::index(checked))
+    }
 
-            crate_advisories.push(advisory.id.clone());
-            advisories.insert(advisory.id.clone(), advisory.clone());
+    /// Index the given advisories by ID, alias, and crate
+    fn index<I: IntoIterator<Item = Advisory>>(advisory_iter: I) -> Self {
+        let mut db = Self {
+            advisories: BTreeMap::new(),
+            aliases: HashMap::new(),
+            crates: HashMap::new(),
+            description_paths: HashMap::new(),
+            parse_warnings: vec![],
+        };
+
+        for advisory in advisory_iter {
+            db.insert(advisory);
         }
 
-        Ok(Self { advisories, crates })
+        db
     }
 
-    /// Look up an advisory by an advisory ID (e.g. "RUSTSEC-YYYY-XXXX")
+    /// Add an advisory to the database and its indexes, replacing any with
+    /// the same ID (which must already have been removed from the indexes)
+    fn insert(&mut self, advisory: Advisory) {
+        for alias in &advisory.aliases {
+            self.aliases
+                .entry(alias.clone())
+                .or_default()
+                .push(advisory.id.clone());
+        }
+
+        self.crates
+            .entry(advisory.package.clone())
+            .or_default()
+            .push(advisory.id.clone());
+
+        self.advisories.insert(advisory.id.clone(), advisory);
+    }
+
+    /// Get the advisory files which were skipped when loading this database
+    /// in `ParseMode::Lenient`, and why
+    pub fn parse_warnings(&self) -> &[ParseWarning] {
+        &self.parse_warnings
+    }
+
+    /// Get the next unassigned RUSTSEC ID for the given year (e.g.
+    /// `RUSTSEC-2019-0004` if the last one assigned was `RUSTSEC-2019-0003`)
+    pub fn assign_next_id(&self, year: u32) -> Result<AdvisoryId, Error> {
+        let last_number = self
+            .advisories
+            .keys()
+            .filter(|id| id.is_rustsec() && id.year() == Some(year))
+            .filter_map(|id| id.number())
+            .max()
+            .unwrap_or(0);
+
+        AdvisoryId::new(format!("RUSTSEC-{}-{:04}", year, last_number + 1))
+    }
+
+    /// Look up an advisory by its ID (e.g. "RUSTSEC-YYYY-XXXX") or, failing
+    /// that, by one of its aliases (e.g. a CVE or GHSA ID). If several
+    /// advisories have the alias, the one with the lowest ID is returned.
     pub fn find<A: AsRef<AdvisoryId>>(&self, id: A) -> Option<&Advisory> {
-        self.advisories.get(id.as_ref())
+        let id = id.as_ref();
+
+        self.advisories
+            .get(id)
+            .or_else(|| self.find_by_alias(id).into_iter().next())
     }
 
-    /// Look up advisories relevant to a particular crate
+    /// Get the description of the advisory with the given ID, reading it from
+    /// the advisory's file if the database was loaded with
+    /// `LoadOptions::lazy_descriptions`
+    pub fn description<A: AsRef<AdvisoryId>>(&self, id: A) -> Result<String, Error> {
+        let advisory = self
+            .find(id.as_ref())
+            .ok_or_else(|| err!(ErrorKind::BadParam, "no such advisory: {}", id.as_ref()))?;
+
+        match self.description_paths.get(&advisory.id) {
+            Some(path) => {
+                let contents = RepoFile::new(path.as_path())?.read_to_string()?;
+                Ok(parse_advisory(path, &contents, true)?.description)
+            }
+            None => Ok(advisory.description.clone()),
+        }
+    }
+
+    /// Look up the advisories which have the given alias (e.g. a CVE ID), in
+    /// order of their IDs
+    pub fn find_by_alias<A: AsRef<AdvisoryId>>(&self, alias: A) -> Vec<&Advisory> {
+        let mut ids = self
+            .aliases
+            .get(alias.as_ref())
+            .map(|ids| ids.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        ids.sort();
+        ids.into_iter().map(|id| &self.advisories[id]).collect()
+    }
+
+    /// Look up advisories relevant to a particular crate (or toolchain package)
     pub fn find_by_crate<N: AsRef<PackageName>>(&self, crate_name: N) -> Vec<&Advisory> {
-        if let Some(ids) = self.crates.get(crate_name.as_ref()) {
-            ids.iter().map(|id| self.find(&id).unwrap()).collect()
+        self.find_by_package(crate_name)
+    }
+
+    /// Look up advisories relevant to a particular package, using an index
+    /// of the advisories about each package built when the database is loaded
+    pub fn find_by_package<N: AsRef<PackageName>>(&self, name: N) -> Vec<&Advisory> {
+        if let Some(ids) = self.crates.get(name.as_ref()) {
+            ids.iter().map(|id| &self.advisories[id]).collect()
         } else {
             vec![]
         }
     }
 
-    /// Find advisories that are unpatched and impact a given crate and version
+    /// Find the advisories matching the given query, in order of their IDs
+    pub fn query(&self, query: &Query) -> Vec<&Advisory> {
+        let candidates = match query.package_name() {
+            Some(name) => self.find_by_package(name),
+            None => self.advisories.values().collect(),
+        };
+
+        let mut matches = candidates
+            .into_iter()
+            .filter(|advisory| query.matches(advisory))
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| a.id.cmp(&b.id));
+        matches
+    }
+
+    /// Search the titles, descriptions, package names, and keywords of the
+    /// advisories which haven't been withdrawn for the terms in the given
+    /// phrase (e.g. "use after free in tokio"), returning the ones matching
+    /// any of them, best matches first. Descriptions which haven't been
+    /// loaded (see `LoadOptions::lazy_descriptions`) aren't searched.
+    pub fn search(&self, phrase: &str) -> Vec<&Advisory> {
+        let mut matches = self
+            .advisories
+            .values()
+            .filter(|advisory| !advisory.is_withdrawn())
+            .map(|advisory| (query::search_score(advisory, phrase), advisory))
+            .filter(|&(score, _)| score > 0)
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+        matches.into_iter().map(|(_, advisory)| advisory).collect()
+    }
+
+    /// Look up advisories about packages in a particular collection (e.g.
+    /// the Rust toolchain)
+    pub fn find_by_collection(&self, collection: Collection) -> Vec<&Advisory> {
+        self.advisories
+            .values()
+            .filter(|advisory| advisory.collection == collection)
+            .collect()
+    }
+
+    /// Find advisories that are unpatched and impact a given crate and
+    /// version, excluding withdrawn advisories
     pub fn advisories_for_crate<N: AsRef<PackageName>>(
         &self,
         crate_name: N,
         version: &Version,
+    ) -> Vec<&Advisory> {
+        self.advisories_for_crate_with(crate_name, version, &MatchOptions::default())
+    }
+
+    /// Find advisories that are unpatched and impact a given crate and
+    /// version, according to the given options
+    pub fn advisories_for_crate_with<N: AsRef<PackageName>>(
+        &self,
+        crate_name: N,
+        version: &Version,
+        options: &MatchOptions,
     ) -> Vec<&Advisory> {
         self.find_by_crate(crate_name)
             .iter()
-            .filter(|advisory| {
-                !advisory
-                    .patched_versions
-                    .iter()
-                    .any(|req| req.matches(version))
-            })
-            .map(|a| *a)
+            .filter(|advisory| advisory.collection == options.collection)
+            .filter(|advisory| options.include_withdrawn || !advisory.is_withdrawn())
+            .filter(|advisory| options.include_informational || !advisory.is_informational())
+            .filter(|advisory| advisory.versions.is_vulnerable(version))
+            .copied()
+            .collect()
+    }
+
+    /// Find informational advisories (e.g. about unmaintained crates) which
+    /// apply to a given crate and version, excluding withdrawn advisories
+    pub fn informational_for_crate<N: AsRef<PackageName>>(
+        &self,
+        crate_name: N,
+        version: &Version,
+    ) -> Vec<&Advisory> {
+        let options = MatchOptions {
+            include_informational: true,
+            ..MatchOptions::default()
+        };
+
+        self.advisories_for_crate_with(crate_name, version, &options)
+            .into_iter()
+            .filter(|advisory| advisory.is_informational())
             .collect()
     }
 
+    /// Return warnings from informational advisories for the given lockfile
+    pub fn warnings(&self, lockfile: &Lockfile) -> Vec<Warning> {
+        Warning::find(self, lockfile)
+    }
+
+    /// Return warnings from advisories about unmaintained crates in the given
+    /// lockfile, for tracking them separately from vulnerabilities and other
+    /// warnings
+    pub fn unmaintained(&self, lockfile: &Lockfile) -> Vec<Warning> {
+        Warning::find_kind(self, lockfile, &Informational::Unmaintained)
+    }
+
     /// Return a collection of vulnerabilities for the given lockfile
     pub fn vulnerabilities(&self, lockfile: &Lockfile) -> Vulnerabilities {
         Vulnerabilities::find(self, lockfile)
     }
 
+    /// Return a collection of vulnerabilities for the given lockfile,
+    /// according to the given options
+    pub fn vulnerabilities_with(
+        &self,
+        lockfile: &Lockfile,
+        options: &MatchOptions,
+    ) -> Vulnerabilities {
+        Vulnerabilities::find_with(self, lockfile, options)
+    }
+
+    /// Export every advisory in the database as an OSV entry, writing each to
+    /// `<id>.json` in the given directory (which must already exist)
+    #[cfg(feature = "osv")]
+    pub fn export_osv<P: AsRef<Path>>(&self, dir: P) -> Result<(), Error> {
+        for advisory in self.advisories.values() {
+            let json = serde_json::to_string_pretty(&advisory.to_osv()?)
+                .map_err(|e| err!(ErrorKind::Parse, "couldn't serialize {}: {}", advisory.id, e))?;
+
+            let path = dir.as_ref().join(format!("{}.json", advisory.id));
+            fs::write(path, json + "\n")?;
+        }
+
+        Ok(())
+    }
+
+    /// Export every advisory in the database (including withdrawn ones) to a
+    /// single JSON document, for consumers which can't use Git or parse TOML.
+    ///
+    /// The document is an object with a `schema_version` (see
+    /// `JSON_SCHEMA_VERSION`) and the `advisories`, in order of their IDs.
+    /// Each advisory has the same set of fields whatever it contains (e.g.
+    /// empty lists rather than missing ones), including which `collection`
+    /// it's in and its effective `severity` and `license`. Descriptions which
+    /// haven't been loaded yet (see `LoadOptions::lazy_descriptions`) are
+    /// loaded first.
+    #[cfg(feature = "json")]
+    pub fn export_json<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut advisories = vec![];
+
+        for advisory in self.advisories.values() {
+            let description = if self.description_paths.contains_key(&advisory.id) {
+                Cow::Owned(self.description(&advisory.id)?)
+            } else {
+                Cow::Borrowed(advisory.description.as_str())
+            };
+
+            advisories.push(JsonAdvisory::new(advisory, description));
+        }
+
+        let export = JsonExport {
+            schema_version: JSON_SCHEMA_VERSION,
+            advisories,
+        };
+
+        serde_json::to_writer_pretty(writer, &export)
+            .map_err(|e| err!(ErrorKind::Io, "couldn't export database as JSON: {}", e))
+    }
+
+    /// Compute the differences between this database and a newer one (e.g.
+    /// to find out which advisories have been added since it was fetched)
+    pub fn diff(&self, newer: &Self) -> Diff {
+        Diff::new(self, newer)
+    }
+
+    /// Get the license the database as a whole can be redistributed under:
+    /// CC-BY-4.0 if any advisory in it requires attribution, otherwise CC0
+    pub fn license(&self) -> License {
+        self.advisories()
+            .map(|advisory| advisory.license())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Compute aggregate statistics about the advisories in this database
+    /// (e.g. how many were issued each year)
+    pub fn statistics(&self) -> Statistics {
+        Statistics::new(self.iter())
+    }
+
+    /// Find likely duplicate advisories (e.g. for keeping the advisory DB
+    /// clean): ones with the same alias as another (e.g. a CVE ID), and ones
+    /// about the same crate with the same title and overlapping affected
+    /// versions. Lints are keyed by the later of the advisories' IDs.
+    pub fn lint_duplicates(&self) -> BTreeMap<AdvisoryId, Vec<Lint>> {
+        advisory::lint_duplicates(self.advisories())
+    }
+
     /// Iterate over all of the advisories in the database
     pub fn advisories(&self) -> advisory::Iter {
         advisory::Iter(self.advisories.iter())
     }
+
+    /// Iterate over all of the advisories in the database, in order of their
+    /// IDs (the same as `advisories`)
+    pub fn iter(&self) -> advisory::Iter<'_> {
+        self.advisories()
+    }
+
+    /// Get the database as it would have been on the given date, as far as
+    /// can be told from the advisories themselves: only the advisories issued
+    /// on or before the date are kept, and later withdrawals are undone (but
+    /// other later changes to the advisories aren't)
+    pub fn as_of(&self, date: &Date) -> Self {
+        let advisories = self
+            .iter()
+            .filter(|advisory| advisory.date <= *date)
+            .map(|advisory| {
+                let mut advisory = advisory.clone();
+
+                if advisory
+                    .withdrawn
+                    .as_ref()
+                    .map(|withdrawn| withdrawn > date)
+                    .unwrap_or(false)
+                {
+                    advisory.withdrawn = None;
+                }
+
+                advisory
+            });
+
+        let mut db = Self::index(advisories);
+
+        db.description_paths = self
+            .description_paths
+            .iter()
+            .filter(|&(id, _)| db.advisories.contains_key(id))
+            .map(|(id, path)| (id.clone(), path.clone()))
+            .collect();
+
+        db
+    }
+
+    /// Number of advisories in the database
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    /// Is the database empty?
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a AdvisoryDatabase {
+    type Item = &'a Advisory;
+    type IntoIter = advisory::Iter<'a>;
+
+    fn into_iter(self) -> advisory::Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for AdvisoryDatabase {
+    type Item = Advisory;
+    type IntoIter = advisory::IntoIter;
+
+    fn into_iter(self) -> advisory::IntoIter {
+        advisory::IntoIter(self.advisories.into_iter())
+    }
+}
+
+/// Remove the given advisory ID from the entry for `key` in an index,
+/// removing the entry altogether if that leaves it empty
+fn unindex<K: Eq + Hash>(index: &mut HashMap<K, Vec<AdvisoryId>>, key: &K, id: &AdvisoryId) {
+    let is_empty = match index.get_mut(key) {
+        Some(ids) => {
+            ids.retain(|existing_id| existing_id != id);
+            ids.is_empty()
+        }
+        None => false,
+    };
+
+    if is_empty {
+        index.remove(key);
+    }
+}
+
+/// Load an advisory file, ensuring it's named after the advisory and is in
+/// the directory of the affected package (and, if required, that its URLs
+/// are all `https://`)
+fn load_advisory_file(
+    advisory_file: &RepoFile,
+    options: &LoadOptions,
+) -> Result<Advisory, ParseWarning> {
+    let contents = advisory_file
+        .read_to_string()
+        .map_err(|error| ParseWarning {
+            path: advisory_file.path().to_owned(),
+            error,
+            lints: vec![],
+        })?;
+
+    load_advisory(advisory_file.path(), &contents, options)
+}
+
+/// Load an advisory from the contents of the advisory file at the given
+/// path, checking it the same way as `load_advisory_file`
+fn load_advisory(
+    path: &Path,
+    contents: &str,
+    options: &LoadOptions,
+) -> Result<Advisory, ParseWarning> {
+    let warning = |error, lints| ParseWarning {
+        path: path.to_owned(),
+        error,
+        lints,
+    };
+
+    let parsed = parse_advisory(path, contents, !options.lazy_descriptions);
+    let mut advisory = parsed.map_err(|e| warning(e, vec![]))?;
+    let lints = advisory.lint_path(path);
+
+    if !lints.is_empty() {
+        let problems = lints
+            .iter()
+            .map(|lint| lint.message.as_str())
+            .collect::<Vec<_>>();
+        let error = err!(
+            ErrorKind::Repo,
+            "{} is in the wrong place ({}): {}",
+            advisory.id,
+            path.display(),
+            problems.join("; ")
+        );
+
+        return Err(warning(error, lints));
+    }
+
+    // The advisory's collection is the directory its package is in, which
+    // `lint_path` has checked is one
+    advisory.collection = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str)
+        .and_then(|dir| dir.parse().ok())
+        .unwrap();
+
+    if options.https_only {
+        let lints = https_lints(&advisory);
+
+        if !lints.is_empty() {
+            let problems = lints
+                .iter()
+                .map(|lint| lint.message.as_str())
+                .collect::<Vec<_>>();
+            let error = err!(
+                ErrorKind::Parse,
+                "{} has non-https URLs: {}",
+                advisory.id,
+                problems.join("; ")
+            );
+
+            return Err(warning(error, lints));
+        }
+    }
+
+    check_advisory(&advisory).map_err(|e| warning(e, vec![]))?;
+    Ok(advisory)
+}
+
+/// Parse an advisory file's contents according to its extension, optionally
+/// leaving out its description
+fn parse_advisory(path: &Path, contents: &str, with_description: bool) -> Result<Advisory, Error> {
+    // V3 advisories are Markdown, whereas legacy ones are TOML
+    if path.extension() == Some(OsStr::new("md")) {
+        if with_description {
+            Advisory::from_markdown(contents)
+        } else {
+            Advisory::from_markdown_metadata(contents)
+        }
+    } else {
+        let mut advisory = Advisory::from_toml(contents)?;
+
+        if !with_description {
+            advisory.description = String::new();
+        }
+
+        Ok(advisory)
+    }
+}
+
+/// Find any of an advisory's URLs which aren't `https://`
+fn https_lints(advisory: &Advisory) -> Vec<Lint> {
+    let urls = advisory
+        .url
+        .iter()
+        .map(|url| ("url", url))
+        .chain(advisory.references.iter().map(|r| ("references", &r.url)));
+
+    urls.filter(|&(_, url)| !url.is_https())
+        .map(|(field, url)| Lint {
+            level: LintLevel::Error,
+            field,
+            message: format!("expected an https:// URL: {}", url),
+        })
+        .collect()
+}
+
+/// Ensure an advisory can be added to the database, i.e. it has a RUSTSEC ID
+fn check_advisory(advisory: &Advisory) -> Result<(), Error> {
+    if !advisory.id.is_rustsec() {
+        fail!(
+            ErrorKind::Parse,
+            "expected a RUSTSEC advisory ID: {}",
+            advisory.id
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdvisoryDatabase, DuplicatePolicy, LoadOptions, ParseMode};
+    use advisory::{Advisory, AdvisoryBuilder, AdvisoryId, Date};
+    use package::PackageName;
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+    };
+
+    fn advisory(id: &str, package: &str, date: &str, alias: &str) -> Advisory {
+        AdvisoryBuilder::fixture(id, package)
+            .and_then(|b| b.date(date))
+            .and_then(|b| b.alias(alias))
+            .and_then(|b| b.patched_version(">= 1.0.0"))
+            .and_then(|b| b.build())
+            .unwrap()
+    }
+
+    /// Database containing `RUSTSEC-2019-0002` about `a`
+    fn first() -> AdvisoryDatabase {
+        AdvisoryDatabase::from_advisories(vec![advisory(
+            "RUSTSEC-2019-0002",
+            "a",
+            "2019-01-01",
+            "CVE-2019-0001",
+        )])
+        .unwrap()
+    }
+
+    /// Database containing a new `RUSTSEC-2019-0001`, and a newer (and
+    /// conflicting) `RUSTSEC-2019-0002` about `b`
+    fn second() -> AdvisoryDatabase {
+        AdvisoryDatabase::from_advisories(vec![
+            advisory("RUSTSEC-2019-0001", "c", "2019-01-01", "CVE-2019-0003"),
+            advisory("RUSTSEC-2019-0002", "b", "2019-02-01", "CVE-2019-0002"),
+        ])
+        .unwrap()
+    }
+
+    fn id(string: &str) -> AdvisoryId {
+        AdvisoryId::new(string).unwrap()
+    }
+
+    /// Create an (empty) directory laid out like the advisory DB with the
+    /// given name, and get the path to it
+    fn advisory_dir(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("crates")).unwrap();
+        path
+    }
+
+    /// Write an advisory's file into the advisory DB directory at `path`
+    fn write_advisory(path: &Path, advisory: &Advisory) {
+        let package_path = path.join("crates").join(advisory.package.as_str());
+        fs::create_dir_all(&package_path).unwrap();
+        fs::write(
+            package_path.join(format!("{}.md", advisory.id)),
+            advisory.to_markdown_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn merge_error_test() {
+        let mut db = first();
+        assert!(db.merge(second(), DuplicatePolicy::Error).is_err());
+
+        assert_eq!(db.len(), 1);
+        assert!(db.find(id("RUSTSEC-2019-0001")).is_none());
+        assert!(db.find_by_package(PackageName::from("c")).is_empty());
+        assert!(db.find_by_alias(id("CVE-2019-0003")).is_empty());
+        assert_eq!(db.find_by_package(PackageName::from("a")).len(), 1);
+    }
+
+    #[test]
+    fn merge_keep_first_test() {
+        let mut db = first();
+        db.merge(second(), DuplicatePolicy::KeepFirst).unwrap();
+
+        assert_eq!(db.len(), 2);
+        assert_eq!(
+            db.find(id("RUSTSEC-2019-0002")).unwrap().package.as_str(),
+            "a"
+        );
+        assert!(db.find_by_package(PackageName::from("b")).is_empty());
+        assert_eq!(db.find_by_alias(id("CVE-2019-0001")).len(), 1);
+    }
+
+    #[test]
+    fn merge_keep_last_test() {
+        let mut db = first();
+        db.merge(second(), DuplicatePolicy::KeepLast).unwrap();
+
+        assert_eq!(db.len(), 2);
+        assert_eq!(
+            db.find(id("RUSTSEC-2019-0002")).unwrap().package.as_str(),
+            "b"
+        );
+
+        // The replaced advisory is no longer indexed
+        assert!(db.find_by_package(PackageName::from("a")).is_empty());
+        assert!(db.find_by_alias(id("CVE-2019-0001")).is_empty());
+        assert!(!db.crates.contains_key(&PackageName::from("a")));
+        assert!(!db.aliases.contains_key(&id("CVE-2019-0001")));
+        assert_eq!(db.find_by_alias(id("CVE-2019-0002")).len(), 1);
+    }
+
+    #[test]
+    fn merge_keep_newest_test() {
+        let mut db = first();
+        db.merge(second(), DuplicatePolicy::KeepNewest).unwrap();
+        assert_eq!(
+            db.find(id("RUSTSEC-2019-0002")).unwrap().package.as_str(),
+            "b"
+        );
+        assert!(!db.crates.contains_key(&PackageName::from("a")));
+
+        // The older advisory doesn't replace the newer one
+        let mut db = second();
+        db.merge(first(), DuplicatePolicy::KeepNewest).unwrap();
+        assert_eq!(
+            db.find(id("RUSTSEC-2019-0002")).unwrap().package.as_str(),
+            "b"
+        );
+        assert!(db.find_by_package(PackageName::from("a")).is_empty());
+    }
+
+    #[test]
+    fn parse_mode_test() {
+        let path = advisory_dir("rustsec-db-parse-mode-test");
+        write_advisory(
+            &path,
+            &advisory("RUSTSEC-2019-0001", "a", "2019-01-01", "CVE-2019-0001"),
+        );
+
+        let malformed = path.join("crates").join("a").join("RUSTSEC-2019-0002.md");
+        fs::write(&malformed, "not an advisory").unwrap();
+
+        assert!(AdvisoryDatabase::open(&path).is_err());
+
+        let options = LoadOptions {
+            mode: ParseMode::Lenient,
+            ..LoadOptions::default()
+        };
+        let db = AdvisoryDatabase::open_with(&path, &options).unwrap();
+
+        assert_eq!(db.len(), 1);
+        assert!(db.find(id("RUSTSEC-2019-0001")).is_some());
+        assert_eq!(db.parse_warnings().len(), 1);
+        assert_eq!(db.parse_warnings()[0].path, malformed);
+    }
+
+    #[test]
+    fn as_of_test() {
+        let mut withdrawn = advisory("RUSTSEC-2019-0001", "a", "2019-01-01", "CVE-2019-0001");
+        withdrawn.withdrawn = Some(Date::new("2019-06-01").unwrap());
+
+        let db = AdvisoryDatabase::from_advisories(vec![
+            withdrawn,
+            advisory("RUSTSEC-2019-0002", "b", "2019-03-01", "CVE-2019-0002"),
+            advisory("RUSTSEC-2019-0003", "c", "2019-07-01", "CVE-2019-0003"),
+        ])
+        .unwrap();
+
+        // Advisories issued after the date are excluded, while ones modified
+        // (here, withdrawn) after it are kept as they were before then
+        let as_of = db.as_of(&Date::new("2019-03-01").unwrap());
+        assert_eq!(as_of.len(), 2);
+        assert!(!as_of.find(id("RUSTSEC-2019-0001")).unwrap().is_withdrawn());
+        assert!(as_of.find(id("RUSTSEC-2019-0002")).is_some());
+        assert!(as_of.find(id("RUSTSEC-2019-0003")).is_none());
+        assert!(as_of.find_by_package(PackageName::from("c")).is_empty());
+
+        let as_of = db.as_of(&Date::new("2019-06-01").unwrap());
+        assert_eq!(as_of.len(), 2);
+        assert!(as_of.find(id("RUSTSEC-2019-0001")).unwrap().is_withdrawn());
+
+        assert_eq!(db.as_of(&Date::new("2019-12-31").unwrap()).len(), 3);
+        assert!(db.as_of(&Date::new("2018-12-31").unwrap()).is_empty());
+    }
+
+    /// Export `db` as JSON and parse the exported document
+    #[cfg(feature = "json")]
+    fn export(db: &AdvisoryDatabase) -> serde_json::Value {
+        let mut json = vec![];
+        db.export_json(&mut json).unwrap();
+        serde_json::from_slice(&json).unwrap()
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn export_json_test() {
+        let path = advisory_dir("rustsec-db-export-json-test");
+        write_advisory(
+            &path,
+            &advisory("RUSTSEC-2019-0002", "b", "2019-02-01", "CVE-2019-0002"),
+        );
+        write_advisory(
+            &path,
+            &advisory("RUSTSEC-2019-0001", "a", "2019-01-01", "CVE-2019-0001"),
+        );
+
+        let options = LoadOptions {
+            lazy_descriptions: true,
+            ..LoadOptions::default()
+        };
+        let db = AdvisoryDatabase::open_with(&path, &options).unwrap();
+        assert!(db
+            .find(id("RUSTSEC-2019-0001"))
+            .unwrap()
+            .description
+            .is_empty());
+
+        let json = export(&db);
+        assert_eq!(json["schema_version"], super::JSON_SCHEMA_VERSION);
+
+        let advisories = json["advisories"].as_array().unwrap();
+        assert_eq!(advisories.len(), 2);
+        assert_eq!(advisories[0]["id"], "RUSTSEC-2019-0001");
+        assert_eq!(advisories[1]["id"], "RUSTSEC-2019-0002");
+
+        let exported = &advisories[0];
+        let mut keys = exported.as_object().unwrap().keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "affected_arch",
+                "affected_functions",
+                "affected_os",
+                "aliases",
+                "categories",
+                "collection",
+                "cvss",
+                "cwe",
+                "date",
+                "description",
+                "id",
+                "informational",
+                "keywords",
+                "license",
+                "package",
+                "patched_versions",
+                "patches",
+                "references",
+                "related",
+                "severity",
+                "title",
+                "unaffected_versions",
+                "url",
+                "withdrawn",
+            ]
+        );
+
+        // Descriptions which weren't loaded up front are read when exporting
+        assert_eq!(exported["description"], "Test advisory.");
+        assert_eq!(exported["package"], "a");
+        assert_eq!(exported["collection"], "crates");
+        assert_eq!(exported["date"], "2019-01-01");
+        assert_eq!(exported["aliases"], serde_json::json!(["CVE-2019-0001"]));
+        assert_eq!(
+            exported["patched_versions"],
+            serde_json::json!([">= 1.0.0"])
+        );
+        assert!(exported["withdrawn"].is_null());
+        assert!(exported["cvss"].is_null());
+
+        // Fields this version of the crate doesn't know about aren't exported
+        let mut unknown = advisory("RUSTSEC-2019-0001", "a", "2019-01-01", "CVE-2019-0001");
+        unknown
+            .unknown_fields
+            .insert("unknown".to_owned(), toml::Value::from("value"));
+        let json = export(&AdvisoryDatabase::from_advisories(vec![unknown]).unwrap());
+        let exported = json["advisories"][0].as_object().unwrap();
+        assert!(!exported.contains_key("unknown"));
+        assert_eq!(exported.len(), keys.len());
+    }
 }
@@ -0,0 +1,178 @@
+//! Differences between two versions of the advisory database (e.g. for
+//! generating release notes, or notifying about new advisories)
+
+use advisory::Advisory;
+use db::AdvisoryDatabase;
+
+/// Differences between an older and a newer `AdvisoryDatabase`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diff {
+    /// Advisories which are only in the newer database
+    pub added: Vec<Advisory>,
+
+    /// Advisories which are only in the older database
+    pub removed: Vec<Advisory>,
+
+    /// Advisories which are in both databases but have changed
+    pub modified: Vec<Modification>,
+
+    /// Advisories which have been withdrawn since the older database (these
+    /// are also included in `modified`, or `added` if they're new)
+    pub withdrawn: Vec<Advisory>,
+}
+
+impl Diff {
+    /// Compute the differences between an older and a newer database
+    pub fn new(older: &AdvisoryDatabase, newer: &AdvisoryDatabase) -> Self {
+        let mut diff = Diff::default();
+
+        for advisory in newer.advisories() {
+            match older.find(&advisory.id) {
+                Some(old) => {
+                    let fields = changed_fields(old, advisory);
+
+                    if fields.is_empty() {
+                        continue;
+                    }
+
+                    if !old.is_withdrawn() && advisory.is_withdrawn() {
+                        diff.withdrawn.push(advisory.clone());
+                    }
+
+                    diff.modified.push(Modification {
+                        old: old.clone(),
+                        new: advisory.clone(),
+                        fields,
+                    });
+                }
+                None => {
+                    if advisory.is_withdrawn() {
+                        diff.withdrawn.push(advisory.clone());
+                    }
+
+                    diff.added.push(advisory.clone());
+                }
+            }
+        }
+
+        for advisory in older.advisories() {
+            if newer.find(&advisory.id).is_none() {
+                diff.removed.push(advisory.clone());
+            }
+        }
+
+        diff
+    }
+
+    /// Are the databases the same?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// An advisory which has changed between two databases
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Modification {
+    /// The advisory in the older database
+    pub old: Advisory,
+
+    /// The advisory in the newer database
+    pub new: Advisory,
+
+    /// Names of the fields which have changed (e.g. `patched_versions`)
+    pub fields: Vec<&'static str>,
+}
+
+/// Find the names of the fields which differ between two advisories
+fn changed_fields(old: &Advisory, new: &Advisory) -> Vec<&'static str> {
+    let mut fields = vec![];
+
+    macro_rules! compare {
+        ($($name:expr => $($field:ident).+),+) => {
+            $(
+                if old.$($field).+ != new.$($field).+ {
+                    fields.push($name);
+                }
+            )+
+        };
+    }
+
+    compare! {
+        "package" => package,
+        "collection" => collection,
+        "date" => date,
+        "withdrawn" => withdrawn,
+        "informational" => informational,
+        "patched_versions" => versions.patched,
+        "unaffected_versions" => versions.unaffected,
+        "affected_arch" => affected_arch,
+        "affected_os" => affected_os,
+        "affected" => affected,
+        "aliases" => aliases,
+        "related" => related,
+        "references" => references,
+        "patches" => patches,
+        "categories" => categories,
+        "cwe" => cwe,
+        "keywords" => keywords,
+        "url" => url,
+        "cvss" => cvss,
+        "severity" => explicit_severity,
+        "license" => explicit_license,
+        "title" => title,
+        "description" => description,
+        "unknown_fields" => unknown_fields
+    }
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Diff;
+    use advisory::{Advisory, AdvisoryBuilder, Date};
+    use db::AdvisoryDatabase;
+
+    fn advisory(id: &str, title: &str) -> Advisory {
+        AdvisoryBuilder::fixture(id, "a")
+            .and_then(|b| b.title(title))
+            .and_then(|b| b.patched_version(">= 1.0.0"))
+            .and_then(|b| b.build())
+            .unwrap()
+    }
+
+    fn database(advisories: Vec<Advisory>) -> AdvisoryDatabase {
+        AdvisoryDatabase::from_advisories(advisories).unwrap()
+    }
+
+    #[test]
+    fn diff_test() {
+        let unchanged = advisory("RUSTSEC-2019-0001", "Unchanged");
+        let modified = advisory("RUSTSEC-2019-0002", "Old title");
+        let removed = advisory("RUSTSEC-2019-0003", "Removed");
+        let older = database(vec![unchanged.clone(), modified.clone(), removed.clone()]);
+
+        let mut withdrawn = unchanged.clone();
+        withdrawn.withdrawn = Some(Date::new("2019-06-01").unwrap());
+        let retitled = advisory("RUSTSEC-2019-0002", "New title");
+        let added = advisory("RUSTSEC-2019-0004", "Added");
+        let newer = database(vec![withdrawn.clone(), retitled.clone(), added.clone()]);
+
+        let diff = Diff::new(&older, &newer);
+        assert_eq!(diff.added, vec![added]);
+        assert_eq!(diff.removed, vec![removed]);
+        assert_eq!(diff.withdrawn, vec![withdrawn.clone()]);
+
+        let mut modifications = diff.modified.clone();
+        modifications.sort_by(|a, b| a.new.id.cmp(&b.new.id));
+        assert_eq!(modifications.len(), 2);
+        assert_eq!(modifications[0].new, withdrawn);
+        assert_eq!(modifications[0].fields, vec!["withdrawn"]);
+        assert_eq!(modifications[1].old, modified);
+        assert_eq!(modifications[1].new, retitled);
+        assert_eq!(modifications[1].fields, vec!["title"]);
+
+        assert!(!diff.is_empty());
+        assert!(Diff::new(&older, &older).is_empty());
+    }
+}
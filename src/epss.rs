@@ -0,0 +1,247 @@
+//! Client for Exploit Prediction Scoring System (EPSS) scores published by
+//! FIRST, which estimate how likely vulnerabilities are to be exploited
+//! (e.g. to triage the ones most likely to be exploited first, rather than
+//! going by CVSS severity alone)
+
+use serde_json;
+use std::{cmp::Ordering, collections::BTreeMap};
+use ureq;
+
+use advisory::{Advisory, AdvisoryId, Date};
+use error::{Error, ErrorKind};
+use vulnerability::{Vulnerabilities, Vulnerability};
+
+/// URL of the FIRST API endpoint serving EPSS scores
+pub const EPSS_API_URL: &str = "https://api.first.org/data/v1/epss";
+
+/// Maximum number of CVE IDs to request scores for at once (which is also the
+/// number of results the API returns per page by default)
+const CVES_PER_REQUEST: usize = 100;
+
+/// Client for fetching EPSS scores from the FIRST API
+pub struct Client {
+    /// Agent used to make requests
+    agent: ureq::Agent,
+
+    /// URL of the API endpoint
+    url: String,
+}
+
+impl Client {
+    /// Create a new client for the FIRST API
+    pub fn new() -> Self {
+        Self::with_url(EPSS_API_URL)
+    }
+
+    /// Create a new client for the API endpoint at the given URL (e.g. a mirror)
+    pub fn with_url<S: Into<String>>(url: S) -> Self {
+        // Honor the `HTTPS_PROXY`/`ALL_PROXY` environment variables like `Repository::fetch`
+        let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
+
+        Client {
+            agent,
+            url: url.into(),
+        }
+    }
+
+    /// Fetch the scores of the given advisories' CVE IDs (including aliases)
+    pub fn fetch_scores<'a, I>(&self, advisories: I) -> Result<Scores, Error>
+    where
+        I: IntoIterator<Item = &'a Advisory>,
+    {
+        let mut cve_ids = advisories
+            .into_iter()
+            .flat_map(cve_ids)
+            .map(|id| id.as_str())
+            .collect::<Vec<_>>();
+
+        cve_ids.sort();
+        cve_ids.dedup();
+
+        let mut scores = BTreeMap::new();
+
+        for chunk in cve_ids.chunks(CVES_PER_REQUEST) {
+            let response = self
+                .agent
+                .get(&self.url)
+                .query("cve", &chunk.join(","))
+                .call()?;
+
+            let page: Response = serde_json::from_reader(response.into_reader())
+                .map_err(|e| err!(ErrorKind::Parse, "invalid EPSS response: {}", e))?;
+
+            for entry in page.data {
+                let id = AdvisoryId::new(entry.cve.as_str())?;
+                scores.insert(id, entry.to_score()?);
+            }
+        }
+
+        Ok(Scores(scores))
+    }
+
+    /// Fetch the scores of the given vulnerabilities, returning them along
+    /// with their scores, most likely to be exploited first
+    pub fn score_vulnerabilities(
+        &self,
+        vulns: &Vulnerabilities,
+    ) -> Result<Vec<ScoredVulnerability>, Error> {
+        let scores = self.fetch_scores(vulns.iter().map(|vuln| &vuln.advisory))?;
+        Ok(scores.score_vulnerabilities(vulns))
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Estimate of how likely a vulnerability is to be exploited
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    /// Probability of the vulnerability being exploited in the next 30 days
+    pub probability: f64,
+
+    /// Proportion of vulnerabilities with the same or a lower probability
+    pub percentile: f64,
+
+    /// Date the score was calculated
+    pub date: Date,
+}
+
+/// EPSS scores of CVEs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scores(BTreeMap<AdvisoryId, Score>);
+
+impl Scores {
+    /// Get the score of the given CVE ID, if known
+    pub fn get(&self, cve_id: &AdvisoryId) -> Option<&Score> {
+        self.0.get(cve_id)
+    }
+
+    /// Get the highest score of the given advisory's CVE IDs (including
+    /// aliases), if any are known
+    pub fn for_advisory(&self, advisory: &Advisory) -> Option<&Score> {
+        cve_ids(advisory)
+            .filter_map(|id| self.get(id))
+            .max_by(|a, b| compare_scores(a, b))
+    }
+
+    /// Attach scores to the given vulnerabilities, returning them most likely
+    /// to be exploited first (with unscored vulnerabilities last)
+    pub fn score_vulnerabilities(&self, vulns: &Vulnerabilities) -> Vec<ScoredVulnerability> {
+        let mut scored = vulns
+            .iter()
+            .map(|vuln| ScoredVulnerability {
+                vulnerability: vuln.clone(),
+                score: self.for_advisory(&vuln.advisory).cloned(),
+            })
+            .collect::<Vec<_>>();
+
+        scored.sort_by(|a, b| match (&a.score, &b.score) {
+            (Some(a), Some(b)) => compare_scores(b, a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+
+        scored
+    }
+
+    /// Number of scores
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Are there no scores?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A vulnerability along with the EPSS score of its advisory, if known
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoredVulnerability {
+    /// The vulnerability
+    pub vulnerability: Vulnerability,
+
+    /// Score of the vulnerability's advisory (see `Scores::for_advisory`)
+    pub score: Option<Score>,
+}
+
+/// Response from the EPSS API
+#[derive(Deserialize)]
+struct Response {
+    data: Vec<Entry>,
+}
+
+/// Score of a CVE in responses from the EPSS API (whose numbers are strings)
+#[derive(Deserialize)]
+struct Entry {
+    cve: String,
+    epss: String,
+    percentile: String,
+    date: String,
+}
+
+impl Entry {
+    /// Parse the score in this entry
+    fn to_score(&self) -> Result<Score, Error> {
+        let parse = |number: &str| {
+            number
+                .parse::<f64>()
+                .ok()
+                .filter(|n| *n >= 0.0 && *n <= 1.0)
+                .ok_or_else(|| err!(ErrorKind::Parse, "invalid EPSS score for {}", self.cve))
+        };
+
+        Ok(Score {
+            probability: parse(&self.epss)?,
+            percentile: parse(&self.percentile)?,
+            date: Date::new(self.date.as_str())?,
+        })
+    }
+}
+
+/// Iterate over the CVE IDs of an advisory (including aliases)
+fn cve_ids(advisory: &Advisory) -> impl Iterator<Item = &AdvisoryId> {
+    Some(&advisory.id)
+        .into_iter()
+        .chain(advisory.aliases.iter())
+        .filter(|id| id.is_cve())
+}
+
+/// Compare scores by probability (which is never NaN)
+fn compare_scores(a: &Score, b: &Score) -> Ordering {
+    a.probability
+        .partial_cmp(&b.probability)
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Response};
+
+    #[test]
+    fn parse_response_test() {
+        let response: Response = ::serde_json::from_str(
+            r#"{"status":"OK","data":[{"cve":"CVE-2019-15542","epss":"0.002010000",
+                "percentile":"0.577860000","date":"2024-03-01"}]}"#,
+        )
+        .unwrap();
+
+        let score = response.data[0].to_score().unwrap();
+        assert_eq!(score.probability, 0.00201);
+        assert_eq!(score.percentile, 0.57786);
+        assert_eq!(score.date.as_str(), "2024-03-01");
+
+        let invalid = Entry {
+            cve: "CVE-2019-15542".to_owned(),
+            epss: "1.5".to_owned(),
+            percentile: "0.5".to_owned(),
+            date: "2024-03-01".to_owned(),
+        };
+
+        assert!(invalid.to_score().is_err());
+    }
+}
@@ -3,11 +3,16 @@
 #[cfg(feature = "chrono")]
 use chrono;
 use failure::{Backtrace, Context, Fail};
+#[cfg(feature = "git2")]
 use git2;
+#[cfg(feature = "gix")]
+use gix;
 use std::fmt::{self, Display};
 use std::io;
 use std::str::Utf8Error;
 use toml;
+#[cfg(feature = "archive")]
+use ureq;
 
 /// Error type
 #[derive(Debug)]
@@ -61,6 +66,11 @@ pub enum ErrorKind {
     #[fail(display = "I/O operation failed")]
     Io,
 
+    /// A network operation failed in a way which may succeed if retried
+    /// (e.g. the connection was reset)
+    #[fail(display = "network operation failed")]
+    Network,
+
     /// Couldn't parse response data
     #[fail(display = "couldn't parse data")]
     Parse,
@@ -68,6 +78,10 @@ pub enum ErrorKind {
     /// Git operation failed
     #[fail(display = "git operation failed")]
     Repo,
+
+    /// A network operation took longer than the configured timeout
+    #[fail(display = "operation timed out")]
+    Timeout,
 }
 
 /// Create a new error (of a given enum variant) with a formatted message
@@ -86,10 +100,10 @@ macro_rules! err {
 /// Create and return an error with a formatted message
 macro_rules! fail {
     ($kind:path, $msg:expr) => {
-        return Err(err!($kind, $msg).into());
+        return Err(err!($kind, $msg).into())
     };
     ($kind:path, $fmt:expr, $($arg:tt)+) => {
-        fail!($kind, &format!($fmt, $($arg)+));
+        fail!($kind, &format!($fmt, $($arg)+))
     };
 }
 
@@ -112,12 +126,20 @@ impl From<fmt::Error> for Error {
     }
 }
 
+#[cfg(feature = "git2")]
 impl From<git2::Error> for Error {
     fn from(other: git2::Error) -> Self {
         err!(ErrorKind::Repo, &other)
     }
 }
 
+#[cfg(feature = "gix")]
+impl From<gix::Error> for Error {
+    fn from(other: gix::Error) -> Self {
+        err!(ErrorKind::Repo, &other)
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(other: io::Error) -> Self {
         err!(ErrorKind::Io, &other)
@@ -129,3 +151,15 @@ impl From<toml::de::Error> for Error {
         err!(ErrorKind::Parse, &other)
     }
 }
+
+#[cfg(any(
+    feature = "archive",
+    feature = "epss",
+    feature = "ghsa",
+    feature = "yanked"
+))]
+impl From<ureq::Error> for Error {
+    fn from(other: ureq::Error) -> Self {
+        err!(ErrorKind::Io, &other)
+    }
+}
@@ -0,0 +1,532 @@
+//! Client for GitHub Security Advisories (GHSA) about Rust crates, which can
+//! be converted into advisories (e.g. to find ones missing from RustSec)
+
+use semver::{Version, VersionReq};
+use serde_json;
+use std::collections::{BTreeMap, HashSet};
+use ureq;
+
+use advisory::{
+    self, AdvisoryId, Collection, Cwe, Date, FunctionPath, License, Reference, Url, VersionSpec,
+};
+use cvss::Severity;
+use db::AdvisoryDatabase;
+use error::{Error, ErrorKind};
+use package::PackageName;
+
+/// URL of the GitHub REST API endpoint listing advisories about Rust crates
+pub const GITHUB_ADVISORIES_URL: &str =
+    "https://api.github.com/advisories?ecosystem=rust&per_page=100";
+
+/// GHSA ecosystem name of crates.io packages
+const RUST_ECOSYSTEM: &str = "rust";
+
+/// Client for fetching advisories from the GitHub REST API
+pub struct Client {
+    /// Agent used to make requests
+    agent: ureq::Agent,
+
+    /// Token to authenticate with (which gives a higher rate limit)
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a new client, authenticating with the given GitHub token if any
+    pub fn new(token: Option<String>) -> Self {
+        // Honor the `HTTPS_PROXY`/`ALL_PROXY` environment variables like `Repository::fetch`
+        let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
+        Client { agent, token }
+    }
+
+    /// Fetch all advisories about Rust crates
+    pub fn fetch_advisories(&self) -> Result<Vec<Advisory>, Error> {
+        self.fetch_advisories_from(GITHUB_ADVISORIES_URL)
+    }
+
+    /// Fetch all advisories from the given URL, following pagination links
+    pub fn fetch_advisories_from(&self, url: &str) -> Result<Vec<Advisory>, Error> {
+        let mut advisories = vec![];
+        let mut next_url = Some(url.to_owned());
+
+        while let Some(url) = next_url {
+            let mut request = self
+                .agent
+                .get(&url)
+                .set("Accept", "application/vnd.github+json")
+                .set("X-GitHub-Api-Version", "2022-11-28");
+
+            if let Some(ref token) = self.token {
+                request = request.set("Authorization", &format!("Bearer {}", token));
+            }
+
+            let response = request.call()?;
+            next_url = response.header("Link").and_then(next_link);
+
+            let page: Vec<Advisory> = serde_json::from_reader(response.into_reader())
+                .map_err(|e| err!(ErrorKind::Parse, "invalid GHSA response: {}", e))?;
+
+            advisories.extend(page);
+        }
+
+        Ok(advisories)
+    }
+}
+
+/// Find the URL of the next page in a `Link` header
+fn next_link(header: &str) -> Option<String> {
+    header
+        .split(',')
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| {
+            let start = link.find('<')? + 1;
+            let end = link.find('>')?;
+            link.get(start..end).map(|url| url.to_owned())
+        })
+}
+
+/// Advisory from the GitHub Advisory Database
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Advisory {
+    /// GHSA ID of the advisory (e.g. `GHSA-4mmc-49vf-jmcp`)
+    pub ghsa_id: String,
+
+    /// CVE ID of the vulnerability, if it has one
+    pub cve_id: Option<String>,
+
+    /// URL of the advisory on github.com
+    pub html_url: Option<String>,
+
+    /// One-line summary of the vulnerability
+    pub summary: String,
+
+    /// Detailed description of the vulnerability (in Markdown)
+    pub description: Option<String>,
+
+    /// Severity of the vulnerability (`low`, `moderate`, `high`, or `critical`)
+    pub severity: Option<String>,
+
+    /// Links to more information about the vulnerability
+    #[serde(default)]
+    pub references: Vec<String>,
+
+    /// When the advisory was published (RFC 3339 timestamp)
+    pub published_at: Option<String>,
+
+    /// When the advisory was withdrawn, if it has been (RFC 3339 timestamp)
+    pub withdrawn_at: Option<String>,
+
+    /// CVSS vectors describing the severity of the vulnerability
+    pub cvss_severities: Option<CvssSeverities>,
+
+    /// Weaknesses which lead to the vulnerability
+    #[serde(default)]
+    pub cwes: Vec<Weakness>,
+
+    /// Packages affected by the vulnerability, and which versions
+    #[serde(default)]
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+/// Weakness which leads to a vulnerability, from the CWE list
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Weakness {
+    /// CWE ID of the weakness (e.g. `CWE-79`)
+    pub cwe_id: String,
+
+    /// Name of the weakness
+    pub name: Option<String>,
+}
+
+/// CVSS vectors for each supported version of CVSS
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CvssSeverities {
+    /// CVSS v3 vector
+    pub cvss_v3: Option<CvssVector>,
+
+    /// CVSS v4 vector
+    pub cvss_v4: Option<CvssVector>,
+}
+
+/// CVSS vector of an advisory
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CvssVector {
+    /// The vector itself (e.g. `CVSS:3.1/AV:N/...`), if there is one
+    pub vector_string: Option<String>,
+}
+
+/// Package affected by an advisory, and which versions
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Vulnerability {
+    /// The affected package
+    pub package: Option<Package>,
+
+    /// Range of affected versions (e.g. `>= 1.0.0, < 1.2.3`)
+    pub vulnerable_version_range: Option<String>,
+
+    /// First version in which the vulnerability is fixed
+    pub first_patched_version: Option<String>,
+
+    /// Paths of the affected functions
+    #[serde(default)]
+    pub vulnerable_functions: Vec<String>,
+}
+
+/// Package in a particular ecosystem
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Package {
+    /// Ecosystem the package is in (i.e. `rust` for crates.io)
+    pub ecosystem: String,
+
+    /// Name of the package
+    pub name: String,
+}
+
+impl Advisory {
+    /// Convert this advisory into advisories about each of the crates it
+    /// affects (all with its GHSA ID). RUSTSEC IDs which it links to become
+    /// aliases, so it can be matched with the corresponding advisories.
+    pub fn to_advisories(&self) -> Result<Vec<advisory::Advisory>, Error> {
+        let mut crates: BTreeMap<&str, Vec<&Vulnerability>> = BTreeMap::new();
+
+        for vulnerability in &self.vulnerabilities {
+            if let Some(ref package) = vulnerability.package {
+                if package.ecosystem == RUST_ECOSYSTEM {
+                    crates.entry(&package.name).or_default().push(vulnerability);
+                }
+            }
+        }
+
+        let mut aliases = vec![];
+        aliases.extend(self.cve_id.iter().map(|id| AdvisoryId::new(id.as_str())));
+        aliases.extend(self.rustsec_ids().map(AdvisoryId::new));
+        let aliases = aliases.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        let published = self
+            .published_at
+            .as_ref()
+            .ok_or_else(|| err!(ErrorKind::Parse, "{} hasn't been published", self.ghsa_id))?;
+
+        let cvss = self
+            .cvss_severities
+            .iter()
+            .flat_map(|cvss| cvss.cvss_v3.iter().chain(cvss.cvss_v4.iter()))
+            .filter_map(|cvss| cvss.vector_string.as_ref())
+            .next()
+            .map(|vector| vector.parse())
+            .transpose()?;
+
+        let mut advisories = vec![];
+
+        for (name, vulnerabilities) in crates {
+            let (versions, affected) = versions(&vulnerabilities)?;
+
+            advisories.push(advisory::Advisory {
+                id: AdvisoryId::new(self.ghsa_id.as_str())?,
+                package: PackageName::from(name),
+                collection: Collection::Crates,
+                date: to_date(published)?,
+                withdrawn: self.withdrawn_at.as_ref().map(|d| to_date(d)).transpose()?,
+                informational: None,
+                versions,
+                affected_arch: None,
+                affected_os: None,
+                affected,
+                aliases: aliases.clone(),
+                related: vec![],
+                references: self
+                    .references
+                    .iter()
+                    .filter(|url| Some(*url) != self.html_url.as_ref())
+                    .filter_map(|url| Url::new(url.as_str()).ok())
+                    .map(Reference::new)
+                    .collect(),
+                patches: vec![],
+                categories: vec![],
+                cwe: self
+                    .cwes
+                    .iter()
+                    .filter_map(|weakness| weakness.cwe_id.parse::<Cwe>().ok())
+                    .collect(),
+                keywords: vec![],
+                url: self.html_url.as_ref().and_then(|url| Url::new(url.as_str()).ok()),
+                cvss,
+                explicit_severity: self.severity.as_ref().and_then(|s| to_severity(s)),
+                // Content from the GitHub Advisory Database requires attribution
+                explicit_license: Some(License::CcBy40),
+                title: self.summary.clone(),
+                description: self.description.clone().unwrap_or_default(),
+        unknown_fields: BTreeMap::new(),
+            });
+        }
+
+        Ok(advisories)
+    }
+
+    /// IDs of the RUSTSEC advisories this advisory links to
+    fn rustsec_ids<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        self.references.iter().filter_map(|url| {
+            let id = &url[url.find("RUSTSEC-")?..];
+            let len = id
+                .find(|c: char| c != '-' && !c.is_ascii_alphanumeric())
+                .unwrap_or(id.len());
+
+            Some(&id[..len])
+        })
+    }
+}
+
+/// Find the GHSA advisories about crates which aren't covered by any of the
+/// advisories in the database, i.e. neither their GHSA ID nor their CVE ID
+/// is an advisory ID or an alias in it, and they don't link to an advisory
+/// in it
+pub fn missing_from<'a>(db: &AdvisoryDatabase, advisories: &'a [Advisory]) -> Vec<&'a Advisory> {
+    let mut known = HashSet::new();
+
+    for advisory in db.advisories() {
+        known.insert(advisory.id.as_str());
+        known.extend(advisory.aliases.iter().map(|alias| alias.as_str()));
+    }
+
+    advisories
+        .iter()
+        .filter(|advisory| {
+            advisory
+                .vulnerabilities
+                .iter()
+                .filter_map(|vulnerability| vulnerability.package.as_ref())
+                .any(|package| package.ecosystem == RUST_ECOSYSTEM)
+        })
+        .filter(|advisory| {
+            let mut ids = vec![advisory.ghsa_id.as_str()];
+            ids.extend(advisory.cve_id.as_deref());
+            ids.extend(advisory.rustsec_ids());
+
+            !ids.iter().any(|id| known.contains(id))
+        })
+        .collect()
+}
+
+/// Lower or upper bound of a range of versions, and whether it's inclusive
+type Bound = (Version, bool);
+
+/// Compute the patched and unaffected versions (and affected functions)
+/// from the ranges of versions a crate is vulnerable in: versions before the
+/// first range are unaffected, and the rest of the versions outside of them
+/// are patched
+fn versions(
+    vulnerabilities: &[&Vulnerability],
+) -> Result<(VersionSpec, Option<advisory::Affected>), Error> {
+    let mut ranges = vec![];
+    let mut functions = BTreeMap::new();
+
+    for vulnerability in vulnerabilities {
+        let range = match vulnerability.vulnerable_version_range {
+            Some(ref range) => range.as_str(),
+            None => continue,
+        };
+
+        let (lower, mut upper) = parse_range(range)?;
+
+        if upper.is_none() {
+            if let Some(ref version) = vulnerability.first_patched_version {
+                upper = Some((to_version(version)?, false));
+            }
+        }
+
+        let req = parse_req(range)?;
+
+        for function in &vulnerability.vulnerable_functions {
+            if let Ok(path) = FunctionPath::new(function.as_str()) {
+                functions
+                    .entry(path)
+                    .or_insert_with(Vec::new)
+                    .push(req.clone());
+            }
+        }
+
+        ranges.push((lower, upper));
+    }
+
+    ranges.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut versions = VersionSpec::default();
+
+    if let Some(&(Some((ref lower, inclusive)), _)) = ranges.first() {
+        let op = if inclusive { "<" } else { "<=" };
+        versions
+            .unaffected
+            .push(parse_req(&format!("{} {}", op, lower))?);
+    }
+
+    for (i, (_, upper)) in ranges.iter().enumerate() {
+        let mut req = match *upper {
+            Some((ref version, inclusive)) => {
+                format!("{} {}", if inclusive { ">" } else { ">=" }, version)
+            }
+            None => continue,
+        };
+
+        if let Some(&(Some((ref lower, inclusive)), _)) = ranges.get(i + 1) {
+            req.push_str(&format!(
+                ", {} {}",
+                if inclusive { "<" } else { "<=" },
+                lower
+            ));
+        }
+
+        versions.patched.push(parse_req(&req)?);
+    }
+
+    let affected = if functions.is_empty() {
+        None
+    } else {
+        Some(advisory::Affected {
+            functions,
+            ..advisory::Affected::default()
+        })
+    };
+
+    Ok((versions, affected))
+}
+
+/// Parse a range of vulnerable versions (e.g. `>= 1.0.0, < 1.2.3` or `= 0.1.0`)
+/// into its lower and upper bounds
+fn parse_range(range: &str) -> Result<(Option<Bound>, Option<Bound>), Error> {
+    let mut lower = None;
+    let mut upper = None;
+
+    for predicate in range.split(',').map(|p| p.trim()) {
+        let (op, version) = [">=", "<=", ">", "<", "="]
+            .iter()
+            .find(|op| predicate.starts_with(**op))
+            .map(|op| (*op, to_version(predicate[op.len()..].trim())))
+            .ok_or_else(|| err!(ErrorKind::Parse, "unsupported version range: {}", range))?;
+
+        let version = version?;
+
+        match op {
+            ">=" => lower = Some((version, true)),
+            ">" => lower = Some((version, false)),
+            "<=" => upper = Some((version, true)),
+            "<" => upper = Some((version, false)),
+            _ => {
+                lower = Some((version.clone(), true));
+                upper = Some((version, true));
+            }
+        }
+    }
+
+    Ok((lower, upper))
+}
+
+/// Parse a possibly partial version (e.g. `1.2`), padding it with zeros
+fn to_version(version: &str) -> Result<Version, Error> {
+    let mut padded = version.to_owned();
+
+    while padded.split('-').next().unwrap().split('.').count() < 3 {
+        padded = match padded.find('-') {
+            Some(pos) => format!("{}.0{}", &padded[..pos], &padded[pos..]),
+            None => format!("{}.0", padded),
+        };
+    }
+
+    Version::parse(&padded)
+        .map_err(|e| err!(ErrorKind::Parse, "invalid version {}: {}", version, e))
+}
+
+/// Parse a version requirement
+fn parse_req(req: &str) -> Result<VersionReq, Error> {
+    VersionReq::parse(req).map_err(|e| {
+        err!(
+            ErrorKind::Parse,
+            "invalid version requirement {}: {}",
+            req,
+            e
+        )
+    })
+}
+
+/// Parse the date from an RFC 3339 timestamp
+fn to_date(timestamp: &str) -> Result<Date, Error> {
+    Date::new(timestamp.get(..10).unwrap_or(timestamp))
+}
+
+/// Convert a GHSA severity (where `moderate` means medium)
+fn to_severity(severity: &str) -> Option<Severity> {
+    match severity {
+        "moderate" => Some(Severity::Medium),
+        other => other.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_link, Advisory};
+    use cvss::Severity;
+    use serde_json;
+
+    #[test]
+    fn to_advisories_test() {
+        let advisory: Advisory = serde_json::from_str(
+            r#"{
+  "ghsa_id": "GHSA-4mmc-49vf-jmcp",
+  "cve_id": "CVE-2018-1000001",
+  "html_url": "https://github.com/advisories/GHSA-4mmc-49vf-jmcp",
+  "summary": "Title",
+  "description": "Description",
+  "severity": "moderate",
+  "references": ["https://rustsec.org/advisories/RUSTSEC-2018-0001.html"],
+  "published_at": "2018-06-01T10:00:00Z",
+  "withdrawn_at": null,
+  "cvss_severities": { "cvss_v3": { "vector_string": null }, "cvss_v4": null },
+  "cwes": [{ "cwe_id": "CWE-79", "name": "Cross-site Scripting" }],
+  "vulnerabilities": [
+    {
+      "package": { "ecosystem": "rust", "name": "mycrate" },
+      "vulnerable_version_range": ">= 0.2, < 0.3.1",
+      "first_patched_version": "0.3.1",
+      "vulnerable_functions": ["mycrate::f"]
+    },
+    {
+      "package": { "ecosystem": "rust", "name": "mycrate" },
+      "vulnerable_version_range": ">= 0.4.0",
+      "first_patched_version": "1.0.0",
+      "vulnerable_functions": []
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let advisories = advisory.to_advisories().unwrap();
+        assert_eq!(advisories.len(), 1);
+
+        let advisory = &advisories[0];
+        assert_eq!(advisory.id.as_str(), "GHSA-4mmc-49vf-jmcp");
+        assert_eq!(advisory.package.as_str(), "mycrate");
+        assert_eq!(advisory.date.as_str(), "2018-06-01");
+        assert_eq!(advisory.explicit_severity, Some(Severity::Medium));
+        assert_eq!(advisory.cwe[0].number(), 79);
+        assert_eq!(advisory.aliases[1].as_str(), "RUSTSEC-2018-0001");
+
+        let versions = &advisory.versions;
+        assert_eq!(versions.unaffected[0].to_string(), "< 0.2.0");
+        assert_eq!(versions.patched[0].to_string(), ">= 0.3.1, < 0.4.0");
+        assert_eq!(versions.patched[1].to_string(), ">= 1.0.0");
+
+        let functions = &advisory.affected.as_ref().unwrap().functions;
+        assert_eq!(functions.keys().next().unwrap().as_str(), "mycrate::f");
+    }
+
+    #[test]
+    fn next_link_test() {
+        let header = "<https://api.github.com/advisories?after=abc>; rel=\"next\", \
+                      <https://api.github.com/advisories?before=def>; rel=\"prev\"";
+
+        assert_eq!(
+            next_link(header).unwrap(),
+            "https://api.github.com/advisories?after=abc"
+        );
+        assert_eq!(next_link("<https://example.com/>; rel=\"prev\""), None);
+    }
+}
@@ -3,6 +3,33 @@
 //! This crate is primarily intended for use with the cargo-audit tool:
 //!
 //! <https://crates.io/crates/cargo-audit>
+//!
+//! Git operations use libgit2 by default. To use gitoxide (a pure Rust Git
+//! implementation) instead, disable default features and enable `gix`.
+//!
+//! libgit2 uses OpenSSL for TLS on most platforms, which can be difficult to
+//! cross-compile. The `rustls` feature (used with default features disabled)
+//! selects the `gix` backend, whose HTTPS transport, like that used to fetch
+//! archives, is built on rustls, so no C TLS library is needed at all.
+//!
+//! Enable the `tokio` feature for `Repository::fetch_async`, which fetches
+//! without blocking the async runtime, and the `tuf` feature for
+//! `Archive::fetch_tuf`, which verifies archives using TUF metadata. The
+//! `snapshot` feature enables loading signed JSON snapshots of the database
+//! (see `Snapshot`). The `html` feature enables rendering advisories as
+//! sanitized HTML (see `Advisory::to_html`), the `osv` feature enables
+//! exporting advisories in the OSV format (see the `osv` module), and the
+//! `cve` feature enables exporting them as CVE records (see the `cve` module).
+//! The `ghsa` feature enables fetching GitHub Security Advisories about Rust
+//! crates (see the `ghsa` module), and the `epss` feature enables fetching
+//! EPSS exploit-probability scores for vulnerabilities (see the `epss` module).
+//! The `rayon` feature parses advisory files in parallel when loading the
+//! database, and the `cache` feature caches the parsed database between runs
+//! (see `AdvisoryDatabase::from_repository_cached`). The `yanked` feature
+//! enables finding packages in a lockfile whose versions have been yanked
+//! from crates.io (see the `yanked` module), and the `json` feature enables
+//! exporting the whole database as a single JSON document (see
+//! `AdvisoryDatabase::export_json`).
 
 #![crate_name = "rustsec"]
 #![crate_type = "lib"]
@@ -14,33 +41,116 @@
     html_root_url = "https://docs.rs/rustsec/0.9.0"
 )]
 
+extern crate base64;
 #[cfg(feature = "chrono")]
 extern crate chrono;
+#[cfg(feature = "tuf")]
+extern crate ed25519_dalek;
+#[cfg(any(feature = "archive", feature = "cache"))]
+extern crate flate2;
 extern crate failure;
 #[macro_use]
 extern crate failure_derive;
+#[cfg(feature = "archive")]
+extern crate p256;
+#[cfg(feature = "git2")]
 extern crate git2;
+#[cfg(feature = "gix")]
+extern crate gix;
+#[cfg(feature = "tuf")]
+extern crate hex;
+extern crate pgp;
 extern crate platforms;
+#[cfg(feature = "html")]
+extern crate pulldown_cmark;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "git2")]
+extern crate rustls_pki_types;
 extern crate semver;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[cfg(any(
+    feature = "cve",
+    feature = "epss",
+    feature = "ghsa",
+    feature = "json",
+    feature = "osv",
+    feature = "snapshot",
+    feature = "tuf",
+    feature = "yanked"
+))]
+extern crate serde_json;
+#[cfg(feature = "tuf")]
+extern crate sha2;
+extern crate ssh_key;
+#[cfg(feature = "archive")]
+extern crate tar;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 extern crate toml;
+#[cfg(any(
+    feature = "archive",
+    feature = "epss",
+    feature = "ghsa",
+    feature = "yanked"
+))]
+extern crate ureq;
+#[cfg(feature = "git2")]
+extern crate webpki;
+
+#[cfg(not(any(feature = "git2", feature = "gix")))]
+compile_error!("either the `git2` or `gix` feature must be enabled");
+
+#[cfg(all(feature = "rustls", feature = "git2"))]
+compile_error!("the `rustls` feature requires disabling the `git2` feature (which uses OpenSSL)");
 
 #[macro_use]
 pub mod error;
 
 pub mod advisory;
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cve")]
+pub mod cve;
+pub mod cvss;
 pub mod db;
+pub mod diff;
+#[cfg(feature = "epss")]
+pub mod epss;
+#[cfg(feature = "ghsa")]
+pub mod ghsa;
 pub mod lockfile;
+#[cfg(feature = "osv")]
+pub mod osv;
 pub mod package;
+pub mod query;
 pub mod repository;
+pub mod stats;
 pub mod vulnerability;
+pub mod warning;
+pub mod watcher;
+#[cfg(feature = "yanked")]
+pub mod yanked;
 
 pub use advisory::*;
+#[cfg(feature = "archive")]
+pub use archive::*;
+#[cfg(feature = "cache")]
+pub use cache::Cache;
 pub use db::*;
+pub use diff::*;
 pub use error::*;
 pub use lockfile::*;
 pub use package::*;
+pub use query::*;
 pub use repository::*;
+pub use stats::*;
 pub use vulnerability::*;
+pub use warning::*;
+pub use watcher::*;
+#[cfg(feature = "yanked")]
+pub use yanked::*;
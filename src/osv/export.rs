@@ -0,0 +1,224 @@
+//! Conversion of advisories into OSV entries
+
+use serde_json::{Map, Value};
+
+use super::{
+    Affected, Entry, Event, Package, Range, Reference, Severity, CRATES_IO_ECOSYSTEM,
+    SCHEMA_VERSION,
+};
+use advisory::{ranges, Advisory, Date};
+use cvss::Cvss;
+use error::Error;
+
+/// Convert an advisory into an OSV entry (see `Advisory::to_osv`)
+pub(crate) fn from_advisory(advisory: &Advisory) -> Result<Entry, Error> {
+    let versions = &advisory.versions;
+    let unmatched = ranges::unmatched_ranges(versions.patched.iter().chain(&versions.unaffected))?;
+    let mut events = vec![];
+
+    for range in unmatched {
+        events.push(Event::Introduced(range.start.to_string()));
+
+        if let Some(end) = range.end {
+            events.push(Event::Fixed(end.to_string()));
+        }
+    }
+
+    let modified = advisory.modified();
+
+    let severity = advisory.cvss.iter().map(|cvss| Severity {
+        kind: match *cvss {
+            Cvss::V3(_) => "CVSS_V3",
+            Cvss::V4(_) => "CVSS_V4",
+        }
+        .to_owned(),
+        score: cvss.to_string(),
+    });
+
+    let affected = Affected {
+        package: Package {
+            ecosystem: CRATES_IO_ECOSYSTEM.to_owned(),
+            name: advisory.package.as_str().to_owned(),
+            purl: Some(format!("pkg:cargo/{}", advisory.package.as_str())),
+        },
+        ranges: vec![Range {
+            kind: "SEMVER".to_owned(),
+            events,
+        }],
+        versions: vec![],
+        ecosystem_specific: Some(ecosystem_specific(advisory)),
+        database_specific: Some(database_specific(advisory)),
+    };
+
+    Ok(Entry {
+        schema_version: Some(SCHEMA_VERSION.to_owned()),
+        id: advisory.id.as_str().to_owned(),
+        modified: timestamp(modified),
+        published: Some(timestamp(&advisory.date)),
+        withdrawn: advisory.withdrawn.as_ref().map(timestamp),
+        aliases: advisory.aliases.iter().map(|id| id.to_string()).collect(),
+        related: advisory.related.iter().map(|id| id.to_string()).collect(),
+        summary: Some(advisory.title.trim().to_owned()),
+        details: Some(advisory.description.trim().to_owned()),
+        severity: severity.collect(),
+        affected: vec![affected],
+        references: references(advisory),
+        database_specific: None,
+    })
+}
+
+/// Convert an advisory date into an RFC 3339 timestamp
+fn timestamp(date: &Date) -> String {
+    format!("{}T12:00:00Z", date.as_str())
+}
+
+/// Links to the advisory, the crate, and anything else the advisory refers to
+fn references(advisory: &Advisory) -> Vec<Reference> {
+    let reference = |kind: &str, url: String| Reference {
+        kind: kind.to_owned(),
+        url,
+    };
+
+    let mut references = vec![reference(
+        "PACKAGE",
+        format!("https://crates.io/crates/{}", advisory.package.as_str()),
+    )];
+
+    references.extend(advisory.id.url().map(|url| reference("ADVISORY", url)));
+    references.extend(
+        advisory
+            .url
+            .as_ref()
+            .map(|url| reference("WEB", url.to_string())),
+    );
+    references.extend(advisory.references.iter().map(|r| {
+        reference(&r.kind.as_str().to_uppercase(), r.url.to_string())
+    }));
+    references.extend(
+        advisory
+            .patches
+            .iter()
+            .map(|p| reference("FIX", p.url.to_string())),
+    );
+
+    references
+}
+
+/// What exactly is affected (i.e. the `affected` table of the advisory)
+fn ecosystem_specific(advisory: &Advisory) -> Value {
+    let functions = advisory
+        .affected
+        .iter()
+        .flat_map(|affected| affected.functions.keys())
+        .map(|path| path.as_str().to_owned())
+        .collect::<Vec<_>>();
+
+    let mut affects = Map::new();
+    affects.insert(
+        "arch".to_owned(),
+        strings(advisory.affected_arch().iter().map(|a| a.as_str())),
+    );
+    affects.insert(
+        "os".to_owned(),
+        strings(advisory.affected_os().iter().map(|os| os.as_str())),
+    );
+    affects.insert("functions".to_owned(), strings(functions.iter()));
+
+    let mut value = Map::new();
+    value.insert("affects".to_owned(), Value::Object(affects));
+    Value::Object(value)
+}
+
+/// Fields of the advisory which OSV has no equivalent of
+fn database_specific(advisory: &Advisory) -> Value {
+    let mut value = Map::new();
+    value.insert(
+        "categories".to_owned(),
+        strings(advisory.categories.iter().map(|c| c.as_str())),
+    );
+    value.insert(
+        "cwe_ids".to_owned(),
+        strings(advisory.cwe.iter().map(|cwe| cwe.to_string())),
+    );
+
+    let optional = |string: Option<String>| string.map(Value::String).unwrap_or(Value::Null);
+    value.insert(
+        "cvss".to_owned(),
+        optional(advisory.cvss.map(|cvss| cvss.to_string())),
+    );
+    value.insert(
+        "informational".to_owned(),
+        optional(advisory.informational.as_ref().map(|i| i.to_string())),
+    );
+    value.insert(
+        "severity".to_owned(),
+        optional(advisory.explicit_severity.map(|s| s.to_string())),
+    );
+    value.insert(
+        "license".to_owned(),
+        optional(advisory.explicit_license.map(|l| l.to_string())),
+    );
+
+    Value::Object(value)
+}
+
+/// Convert strings into a JSON array
+fn strings<I, S>(iter: I) -> Value
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    Value::Array(iter.map(|s| Value::String(s.as_ref().to_owned())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Event, Reference};
+    use advisory::Advisory;
+
+    #[test]
+    fn from_advisory_test() {
+        let advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2018-0001"
+package = "mycrate"
+date = "2018-06-01"
+url = "https://example.com/"
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+aliases = ["CVE-2018-1000001"]
+unaffected_versions = ["< 0.2.0"]
+patched_versions = ["^0.3.1", ">= 1.0.0"]
+title = "Title"
+description = "Description"
+"#,
+        )
+        .unwrap();
+
+        let entry = advisory.to_osv().unwrap();
+        assert_eq!(entry.id, "RUSTSEC-2018-0001");
+        assert_eq!(entry.published.as_ref().unwrap(), "2018-06-01T12:00:00Z");
+        assert_eq!(entry.aliases, ["CVE-2018-1000001"]);
+        assert_eq!(entry.severity[0].kind, "CVSS_V3");
+
+        let affected = &entry.affected[0];
+        assert_eq!(affected.package.name, "mycrate");
+        assert_eq!(affected.ranges[0].kind, "SEMVER");
+        assert_eq!(
+            affected.ranges[0].events,
+            [
+                Event::Introduced("0.2.0".to_owned()),
+                Event::Fixed("0.3.1".to_owned()),
+                Event::Introduced("0.4.0".to_owned()),
+                Event::Fixed("1.0.0".to_owned()),
+            ]
+        );
+
+        assert_eq!(
+            entry.references[1],
+            Reference {
+                kind: "ADVISORY".to_owned(),
+                url: "https://rustsec.org/advisories/RUSTSEC-2018-0001".to_owned(),
+            }
+        );
+    }
+}
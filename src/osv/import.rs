@@ -0,0 +1,487 @@
+//! Conversion of OSV entries into advisories
+
+use platforms::target::{Arch, OS};
+use semver::{Version, VersionReq};
+use serde_json::{self, Value};
+use std::collections::BTreeMap;
+
+use super::{Affected, Entry, Event, CRATES_IO_ECOSYSTEM};
+use advisory::ranges::min_version;
+use advisory::{
+    self, Advisory, AdvisoryId, Collection, Date, FunctionPath, Reference, Url, VersionSpec,
+};
+use error::{Error, ErrorKind};
+use package::PackageName;
+
+/// Upper bound of a range of affected versions
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum End {
+    /// Affected up to, but not including, the given version
+    Fixed(Version),
+
+    /// Affected up to and including the given version
+    LastAffected(Version),
+
+    /// Affected in all versions from the start of the range on
+    Unbounded,
+}
+
+impl End {
+    /// Key to order upper bounds by how many versions they include
+    fn key(&self) -> (bool, Option<&Version>, bool) {
+        match *self {
+            End::Fixed(ref version) => (false, Some(version), false),
+            End::LastAffected(ref version) => (false, Some(version), true),
+            End::Unbounded => (true, None, false),
+        }
+    }
+}
+
+/// Range of affected versions, from `start` (inclusive) up to `end`
+#[derive(Clone, Debug)]
+struct AffectedRange {
+    start: Version,
+    end: End,
+}
+
+impl AffectedRange {
+    /// Requirement matching the versions in this range
+    fn to_req(&self) -> Result<VersionReq, Error> {
+        let mut req = format!(">= {}", self.start);
+
+        match self.end {
+            End::Fixed(ref version) => req.push_str(&format!(", < {}", version)),
+            End::LastAffected(ref version) => req.push_str(&format!(", <= {}", version)),
+            End::Unbounded => (),
+        }
+
+        parse_req(&req)
+    }
+}
+
+/// Convert an OSV entry for a crates.io package into an advisory (see
+/// `Advisory::from_osv`)
+pub(crate) fn to_advisory(entry: &Entry) -> Result<Advisory, Error> {
+    let affected = crates_io_package(entry)?;
+    let ranges = affected_ranges(entry, affected)?;
+
+    let date = entry.published.as_ref().unwrap_or(&entry.modified);
+    let database_specific = |key: &str| {
+        affected
+            .database_specific
+            .as_ref()
+            .and_then(|value| value.get(key))
+            .or_else(|| {
+                entry
+                    .database_specific
+                    .as_ref()
+                    .and_then(|value| value.get(key))
+            })
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    };
+
+    // Strings in an array in the `database_specific` field of the package,
+    // or failing that the entry (e.g. GHSA entries give `cwe_ids` there)
+    let database_specific_strings = |key: &str| {
+        [&affected.database_specific, &entry.database_specific]
+            .iter()
+            .filter_map(|value| value.as_ref().and_then(|value| value.get(key)))
+            .filter_map(|value| value.as_array())
+            .next()
+            .map(|array| array.iter().filter_map(|item| item.as_str()).collect())
+            .unwrap_or_else(Vec::new)
+    };
+
+    let categories = database_specific_strings("categories")
+        .into_iter()
+        .filter_map(|category| category.parse().ok())
+        .collect();
+    let cwe = database_specific_strings("cwe_ids")
+        .into_iter()
+        .filter_map(|cwe| cwe.parse().ok())
+        .collect();
+
+    let cvss = match entry
+        .severity
+        .iter()
+        .find(|severity| severity.kind == "CVSS_V3" || severity.kind == "CVSS_V4")
+    {
+        Some(severity) => Some(severity.score.parse()?),
+        None => None,
+    };
+
+    Ok(Advisory {
+        id: AdvisoryId::new(entry.id.as_str())?,
+        package: PackageName::from(affected.package.name.as_str()),
+        collection: Collection::Crates,
+        date: to_date(date)?,
+        withdrawn: entry.withdrawn.as_ref().map(|d| to_date(d)).transpose()?,
+        informational: database_specific("informational").and_then(|kind| kind.parse().ok()),
+        versions: version_spec(&ranges)?,
+        affected_arch: None,
+        affected_os: None,
+        affected: affected_table(affected, &ranges)?,
+        aliases: to_ids(&entry.aliases)?,
+        related: to_ids(&entry.related)?,
+        references: references(entry),
+        patches: vec![],
+        categories,
+        cwe,
+        keywords: vec![],
+        url: url(entry),
+        cvss,
+        explicit_severity: database_specific("severity").and_then(|s| s.parse().ok()),
+        explicit_license: database_specific("license").and_then(|l| l.parse().ok()),
+        title: entry.summary.clone().unwrap_or_default(),
+        description: entry.details.clone().unwrap_or_default(),
+unknown_fields: BTreeMap::new(),
+    })
+}
+
+/// Find the crates.io package affected by an entry, of which there must be
+/// exactly one
+fn crates_io_package(entry: &Entry) -> Result<&Affected, Error> {
+    let mut packages = entry
+        .affected
+        .iter()
+        .filter(|affected| affected.package.ecosystem == CRATES_IO_ECOSYSTEM);
+
+    match (packages.next(), packages.next()) {
+        (Some(affected), None) => Ok(affected),
+        (None, _) => Err(err!(
+            ErrorKind::Parse,
+            "{} doesn't affect any crates.io packages",
+            entry.id
+        )),
+        (Some(_), Some(_)) => Err(err!(
+            ErrorKind::Parse,
+            "{} affects more than one crates.io package",
+            entry.id
+        )),
+    }
+}
+
+/// Compute the ranges of affected versions from the ranges in an entry,
+/// sorted and with overlapping ones merged
+fn affected_ranges(entry: &Entry, affected: &Affected) -> Result<Vec<AffectedRange>, Error> {
+    if affected.ranges.is_empty() && !affected.versions.is_empty() {
+        fail!(
+            ErrorKind::Parse,
+            "{} lists affected versions without ranges, which isn't supported",
+            entry.id
+        );
+    }
+
+    let mut ranges = vec![];
+
+    for range in &affected.ranges {
+        if range.kind != "SEMVER" && range.kind != "ECOSYSTEM" {
+            fail!(
+                ErrorKind::Parse,
+                "{} has an unsupported {} range",
+                entry.id,
+                range.kind
+            );
+        }
+
+        let mut start = None;
+
+        for event in &range.events {
+            match *event {
+                Event::Introduced(ref version) => start = Some(to_version(version)?),
+                Event::Fixed(ref version) => {
+                    if let Some(start) = start.take() {
+                        ranges.push(AffectedRange {
+                            start,
+                            end: End::Fixed(to_version(version)?),
+                        });
+                    }
+                }
+                Event::LastAffected(ref version) => {
+                    if let Some(start) = start.take() {
+                        ranges.push(AffectedRange {
+                            start,
+                            end: End::LastAffected(to_version(version)?),
+                        });
+                    }
+                }
+                Event::Limit(_) => (),
+            }
+        }
+
+        if let Some(start) = start {
+            ranges.push(AffectedRange {
+                start,
+                end: End::Unbounded,
+            });
+        }
+    }
+
+    ranges.sort_by(|a, b| a.start.cmp(&b.start));
+
+    let mut merged: Vec<AffectedRange> = vec![];
+
+    for range in ranges {
+        if let Some(last) = merged.last_mut() {
+            let overlaps = match last.end {
+                End::Fixed(ref end) => range.start <= *end,
+                End::LastAffected(ref end) => range.start <= *end,
+                End::Unbounded => true,
+            };
+
+            if overlaps {
+                if range.end.key() > last.end.key() {
+                    last.end = range.end;
+                }
+
+                continue;
+            }
+        }
+
+        merged.push(range);
+    }
+
+    Ok(merged)
+}
+
+/// Compute the patched and unaffected versions from the affected ranges:
+/// versions before the first range are unaffected, and the rest of the
+/// versions outside of them are patched
+fn version_spec(ranges: &[AffectedRange]) -> Result<VersionSpec, Error> {
+    let mut versions = VersionSpec::default();
+
+    // Versions which aren't in any range are patched or unaffected, except
+    // if there are no ranges, in which case all versions are affected
+    if let Some(first) = ranges.first() {
+        if first.start > Version::new(0, 0, 0) {
+            versions
+                .unaffected
+                .push(parse_req(&format!("< {}", first.start))?);
+        }
+    }
+
+    for (i, range) in ranges.iter().enumerate() {
+        let mut req = match range.end {
+            End::Fixed(ref version) => format!(">= {}", version),
+            End::LastAffected(ref version) => format!("> {}", version),
+            End::Unbounded => continue,
+        };
+
+        if let Some(next) = ranges.get(i + 1) {
+            req.push_str(&format!(", < {}", next.start));
+        }
+
+        versions.patched.push(parse_req(&req)?);
+    }
+
+    Ok(versions)
+}
+
+/// Build the `affected` table of the advisory from the `ecosystem_specific`
+/// information in an entry, in the format used when exporting to OSV
+fn affected_table(
+    affected: &Affected,
+    ranges: &[AffectedRange],
+) -> Result<Option<advisory::Affected>, Error> {
+    let affects = match affected
+        .ecosystem_specific
+        .as_ref()
+        .and_then(|value| value.get("affects"))
+    {
+        Some(affects) => affects,
+        None => return Ok(None),
+    };
+
+    let list = |key: &str| affects.get(key).cloned().unwrap_or(Value::Array(vec![]));
+    let parse_error = |e: serde_json::Error| err!(ErrorKind::Parse, "invalid affects: {}", e);
+
+    let arch: Vec<Arch> = serde_json::from_value(list("arch")).map_err(parse_error)?;
+    let os: Vec<OS> = serde_json::from_value(list("os")).map_err(parse_error)?;
+    let paths: Vec<FunctionPath> =
+        serde_json::from_value(list("functions")).map_err(parse_error)?;
+
+    // OSV doesn't record which versions each function is affected in, so
+    // assume they're affected in all of the affected versions
+    let reqs = ranges
+        .iter()
+        .map(|range| range.to_req())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let functions = paths
+        .into_iter()
+        .map(|path| (path, reqs.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let table = advisory::Affected {
+        arch,
+        os,
+        functions,
+        ..advisory::Affected::default()
+    };
+
+    Ok(if table.is_empty() { None } else { Some(table) })
+}
+
+/// Find the URL with more information about the vulnerability, i.e. the
+/// first reference other than the package and the entry itself
+fn url(entry: &Entry) -> Option<Url> {
+    let own_url = AdvisoryId::new(entry.id.as_str())
+        .ok()
+        .and_then(|id| id.url());
+
+    entry
+        .references
+        .iter()
+        .filter(|reference| reference.kind != "PACKAGE")
+        .filter(|reference| Some(&reference.url) != own_url.as_ref())
+        .filter_map(|reference| Url::new(reference.url.as_str()).ok())
+        .next()
+}
+
+/// Convert the references of an entry, other than those to the package, the
+/// entry itself, and the one which becomes the advisory's `url`
+fn references(entry: &Entry) -> Vec<Reference> {
+    let own_url = AdvisoryId::new(entry.id.as_str())
+        .ok()
+        .and_then(|id| id.url());
+    let url = url(entry);
+
+    entry
+        .references
+        .iter()
+        .filter(|reference| reference.kind != "PACKAGE")
+        .filter(|reference| Some(&reference.url) != own_url.as_ref())
+        .filter(|reference| url.as_ref().map(Url::as_str) != Some(&reference.url))
+        .filter_map(|reference| {
+            Some(Reference {
+                kind: reference.kind.to_lowercase().parse().unwrap(),
+                url: Url::new(reference.url.as_str()).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Parse the date from an RFC 3339 timestamp
+fn to_date(timestamp: &str) -> Result<Date, Error> {
+    Date::new(timestamp.get(..10).unwrap_or(timestamp))
+}
+
+/// Parse a version from an event, where `0` means all versions
+fn to_version(version: &str) -> Result<Version, Error> {
+    if version == "0" {
+        return Ok(min_version());
+    }
+
+    Version::parse(version)
+        .map_err(|e| err!(ErrorKind::Parse, "invalid version {}: {}", version, e))
+}
+
+/// Parse a version requirement
+fn parse_req(req: &str) -> Result<VersionReq, Error> {
+    VersionReq::parse(req).map_err(|e| {
+        err!(
+            ErrorKind::Parse,
+            "invalid version requirement {}: {}",
+            req,
+            e
+        )
+    })
+}
+
+/// Parse advisory IDs
+fn to_ids(ids: &[String]) -> Result<Vec<AdvisoryId>, Error> {
+    ids.iter().map(|id| AdvisoryId::new(id.as_str())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Entry;
+    use advisory::Advisory;
+    use serde_json;
+
+    #[test]
+    fn to_advisory_test() {
+        let entry: Entry = serde_json::from_str(
+            r#"{
+  "id": "ACME-2019-12",
+  "modified": "2019-03-04T10:00:00Z",
+  "published": "2019-03-01T09:00:00Z",
+  "aliases": ["GHSA-4mmc-49vf-jmcp"],
+  "summary": "Title",
+  "details": "Description",
+  "affected": [{
+    "package": { "ecosystem": "crates.io", "name": "mycrate" },
+    "ranges": [{
+      "type": "SEMVER",
+      "events": [
+        { "introduced": "0.2.0" },
+        { "fixed": "0.3.1" },
+        { "introduced": "0.4.0" },
+        { "last_affected": "1.0.0" }
+      ]
+    }]
+  }],
+  "references": [{ "type": "WEB", "url": "https://example.com/" }],
+  "database_specific": { "severity": "HIGH" }
+}"#,
+        )
+        .unwrap();
+
+        let advisory = Advisory::from_osv(&entry).unwrap();
+        assert_eq!(advisory.id.as_str(), "ACME-2019-12");
+        assert_eq!(advisory.package.as_str(), "mycrate");
+        assert_eq!(advisory.date.as_str(), "2019-03-01");
+        assert!(advisory.aliases[0].is_ghsa());
+        assert_eq!(advisory.url.as_ref().unwrap().as_str(), "https://example.com/");
+        assert_eq!(advisory.explicit_severity.unwrap().as_str(), "high");
+
+        let versions = &advisory.versions;
+        assert_eq!(versions.unaffected[0].to_string(), "< 0.2.0");
+        assert_eq!(versions.patched[0].to_string(), ">= 0.3.1, < 0.4.0");
+        assert_eq!(versions.patched[1].to_string(), "> 1.0.0");
+
+        for &(version, vulnerable) in &[
+            ("0.1.0", false),
+            ("0.3.0", true),
+            ("0.3.5", false),
+            ("1.0.0", true),
+            ("1.0.1", false),
+        ] {
+            assert_eq!(
+                versions.is_vulnerable(&version.parse().unwrap()),
+                vulnerable,
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2018-0001"
+package = "mycrate"
+date = "2018-06-01"
+informational = "unsound"
+url = "https://example.com/"
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+categories = ["memory-corruption"]
+patched_versions = [">= 1.0.0"]
+title = "Title"
+description = "Description"
+
+[advisory.affected]
+os = ["linux"]
+
+[advisory.affected.functions]
+"mycrate::f" = [">= 0.0.0-0, < 1.0.0"]
+"#,
+        )
+        .unwrap();
+
+        let imported = Advisory::from_osv(&advisory.to_osv().unwrap()).unwrap();
+        assert_eq!(imported, advisory);
+    }
+}
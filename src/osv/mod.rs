@@ -0,0 +1,161 @@
+//! Support for the [OSV] (Open Source Vulnerability) format, which is used
+//! to exchange advisories between vulnerability databases. Advisories can be
+//! exported as OSV entries (see `Advisory::to_osv`), and entries for crates.io
+//! packages imported as advisories (see `Advisory::from_osv`).
+//!
+//! [OSV]: https://ossf.github.io/osv-schema/
+
+mod export;
+mod import;
+
+use serde_json::Value;
+
+pub(crate) use self::export::from_advisory;
+pub(crate) use self::import::to_advisory;
+
+/// Version of the OSV schema entries are exported in
+pub const SCHEMA_VERSION: &str = "1.6.0";
+
+/// OSV ecosystem name of crates.io packages
+pub const CRATES_IO_ECOSYSTEM: &str = "crates.io";
+
+/// OSV entry describing a single vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    /// Version of the OSV schema this entry follows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+
+    /// ID of the vulnerability (e.g. `RUSTSEC-2019-0001`)
+    pub id: String,
+
+    /// When this entry was last modified (RFC 3339 timestamp)
+    pub modified: String,
+
+    /// When this entry was published (RFC 3339 timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+
+    /// When this entry was withdrawn, if it has been (RFC 3339 timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<String>,
+
+    /// IDs of the same vulnerability in other databases
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// IDs of closely related but distinct vulnerabilities
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<String>,
+
+    /// One-line summary of the vulnerability
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Detailed description of the vulnerability (in Markdown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    /// Severity scores of the vulnerability (e.g. CVSS vectors)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub severity: Vec<Severity>,
+
+    /// Packages affected by the vulnerability
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected: Vec<Affected>,
+
+    /// Links to more information about the vulnerability
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+
+    /// Extra information specific to the database this entry came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<Value>,
+}
+
+/// Severity score of a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Severity {
+    /// Kind of score (e.g. `CVSS_V3`)
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// The score itself (e.g. a CVSS vector)
+    pub score: String,
+}
+
+/// A package affected by a vulnerability, and which versions of it are
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Affected {
+    /// The affected package
+    pub package: Package,
+
+    /// Ranges of affected versions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ranges: Vec<Range>,
+
+    /// Individual affected versions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<String>,
+
+    /// Extra information specific to the package's ecosystem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecosystem_specific: Option<Value>,
+
+    /// Extra information specific to the database this entry came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<Value>,
+}
+
+/// Package in a particular ecosystem
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Package {
+    /// Ecosystem the package is in (e.g. `crates.io`)
+    pub ecosystem: String,
+
+    /// Name of the package
+    pub name: String,
+
+    /// Package URL of the package (e.g. `pkg:cargo/mycrate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+}
+
+/// Range of affected versions, as a sequence of events
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Range {
+    /// How the versions in events are ordered (e.g. `SEMVER`)
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// Events in which versions become affected or stop being affected
+    pub events: Vec<Event>,
+}
+
+/// Event in a range of affected versions
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    /// Version in which the vulnerability was introduced
+    Introduced(String),
+
+    /// Version in which the vulnerability was fixed
+    Fixed(String),
+
+    /// Last version affected by the vulnerability
+    LastAffected(String),
+
+    /// Upper limit of the range (exclusive)
+    Limit(String),
+}
+
+/// Link to more information about a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Reference {
+    /// Kind of reference (e.g. `ADVISORY`, `WEB`, or `PACKAGE`)
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// URL of the reference
+    pub url: String,
+}
@@ -17,6 +17,13 @@ pub struct Package {
 
     /// Dependencies of this crate
     pub dependencies: Option<Vec<String>>,
+
+    /// Cargo features enabled for this crate, if known (e.g. from `cargo
+    /// metadata` or an auditable binary, since `Cargo.lock` doesn't say).
+    /// Advisories which only apply with features which aren't enabled are
+    /// skipped when finding vulnerabilities.
+    #[serde(default)]
+    pub features: Option<Vec<String>>,
 }
 
 /// Name of a crate
@@ -0,0 +1,343 @@
+//! Queries for searching an `AdvisoryDatabase` for advisories matching
+//! several predicates at once (e.g. all high-severity advisories about a
+//! crate issued since a given date)
+
+use advisory::{Advisory, Category, Collection, Date, Informational};
+use cvss::Severity;
+use package::PackageName;
+
+/// A query matching advisories against all of the predicates which have been
+/// added to it (see `AdvisoryDatabase::query`). An empty query matches every
+/// advisory which hasn't been withdrawn.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Query {
+    package: Option<PackageName>,
+    collection: Option<Collection>,
+    informational: Option<Option<Informational>>,
+    severity: Option<Severity>,
+    after: Option<Date>,
+    before: Option<Date>,
+    modified_after: Option<Date>,
+    modified_before: Option<Date>,
+    keywords: Vec<String>,
+    categories: Vec<Category>,
+    include_withdrawn: bool,
+}
+
+impl Query {
+    /// Create a query matching every advisory which hasn't been withdrawn
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match advisories about the package with the given name
+    pub fn package<N: Into<PackageName>>(mut self, name: N) -> Self {
+        self.package = Some(name.into());
+        self
+    }
+
+    /// Only match advisories about packages in the given collection
+    pub fn collection(mut self, collection: Collection) -> Self {
+        self.collection = Some(collection);
+        self
+    }
+
+    /// Only match advisories about vulnerabilities (i.e. not informational ones)
+    pub fn vulnerabilities(mut self) -> Self {
+        self.informational = Some(None);
+        self
+    }
+
+    /// Only match informational advisories of the given kind (e.g. about
+    /// unmaintained crates)
+    pub fn informational(mut self, informational: Informational) -> Self {
+        self.informational = Some(Some(informational));
+        self
+    }
+
+    /// Only match advisories with at least the given severity (see
+    /// `Advisory::severity`), excluding ones with no known severity
+    pub fn severity_at_least(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    /// Only match advisories issued on or after the given date
+    pub fn after(mut self, date: Date) -> Self {
+        self.after = Some(date);
+        self
+    }
+
+    /// Only match advisories issued before the given date
+    pub fn before(mut self, date: Date) -> Self {
+        self.before = Some(date);
+        self
+    }
+
+    /// Only match advisories issued between the given dates (on or after
+    /// `start`, and before `end`)
+    pub fn published_between(self, start: Date, end: Date) -> Self {
+        self.after(start).before(end)
+    }
+
+    /// Only match advisories last modified (see `Advisory::modified`) on or
+    /// after the given date
+    pub fn modified_after(mut self, date: Date) -> Self {
+        self.modified_after = Some(date);
+        self
+    }
+
+    /// Only match advisories last modified before the given date
+    pub fn modified_before(mut self, date: Date) -> Self {
+        self.modified_before = Some(date);
+        self
+    }
+
+    /// Only match advisories last modified between the given dates (on or
+    /// after `start`, and before `end`), e.g. for a weekly digest
+    pub fn modified_between(self, start: Date, end: Date) -> Self {
+        self.modified_after(start).modified_before(end)
+    }
+
+    /// Only match advisories with the given keyword (if called more than
+    /// once, advisories must have all of the keywords)
+    pub fn keyword<S: Into<String>>(mut self, keyword: S) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    /// Only match advisories in the given category (if called more than
+    /// once, advisories must be in all of the categories)
+    pub fn category(mut self, category: Category) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    /// Also match advisories which have been withdrawn
+    pub fn include_withdrawn(mut self, include_withdrawn: bool) -> Self {
+        self.include_withdrawn = include_withdrawn;
+        self
+    }
+
+    /// Get the name of the package this query matches advisories about, if any
+    pub(crate) fn package_name(&self) -> Option<&PackageName> {
+        self.package.as_ref()
+    }
+
+    /// Does the given advisory match this query?
+    pub fn matches(&self, advisory: &Advisory) -> bool {
+        if !self.include_withdrawn && advisory.is_withdrawn() {
+            return false;
+        }
+
+        if let Some(ref package) = self.package {
+            if advisory.package != *package {
+                return false;
+            }
+        }
+
+        if let Some(collection) = self.collection {
+            if advisory.collection != collection {
+                return false;
+            }
+        }
+
+        if let Some(ref informational) = self.informational {
+            if advisory.informational != *informational {
+                return false;
+            }
+        }
+
+        if let Some(severity) = self.severity {
+            if advisory.severity().map(|s| s < severity).unwrap_or(true) {
+                return false;
+            }
+        }
+
+        if let Some(ref after) = self.after {
+            if advisory.date < *after {
+                return false;
+            }
+        }
+
+        if let Some(ref before) = self.before {
+            if advisory.date >= *before {
+                return false;
+            }
+        }
+
+        if let Some(ref after) = self.modified_after {
+            if advisory.modified() < after {
+                return false;
+            }
+        }
+
+        if let Some(ref before) = self.modified_before {
+            if advisory.modified() >= before {
+                return false;
+            }
+        }
+
+        if !self
+            .categories
+            .iter()
+            .all(|category| advisory.categories.contains(category))
+        {
+            return false;
+        }
+
+        self.keywords.iter().all(|keyword| {
+            advisory
+                .keywords
+                .iter()
+                .any(|k| k.as_str() == keyword.as_str())
+        })
+    }
+}
+
+/// Weight of search terms found in an advisory's title
+const TITLE_WEIGHT: usize = 3;
+
+/// Weight of search terms found in the name of an advisory's package
+const PACKAGE_WEIGHT: usize = 3;
+
+/// Weight of search terms found in an advisory's keywords
+const KEYWORD_WEIGHT: usize = 2;
+
+/// Weight of search terms found in an advisory's description
+const DESCRIPTION_WEIGHT: usize = 1;
+
+/// Bonus for advisories whose title contains the whole search phrase
+const PHRASE_WEIGHT: usize = 10;
+
+/// Split text into lowercase alphanumeric search terms
+fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Score how well an advisory matches the given search phrase (see
+/// `AdvisoryDatabase::search`), or zero if it doesn't match at all
+pub(crate) fn search_score(advisory: &Advisory, phrase: &str) -> usize {
+    let title = terms(&advisory.title);
+    let package = terms(advisory.package.as_str());
+    let description = terms(&advisory.description);
+    let keywords = advisory
+        .keywords
+        .iter()
+        .flat_map(|keyword| terms(keyword.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut score = 0;
+
+    for term in terms(phrase) {
+        let fields = [
+            (&title, TITLE_WEIGHT),
+            (&package, PACKAGE_WEIGHT),
+            (&keywords, KEYWORD_WEIGHT),
+            (&description, DESCRIPTION_WEIGHT),
+        ];
+
+        for &(field, weight) in &fields {
+            if field.contains(&term) {
+                score += weight;
+            }
+        }
+    }
+
+    let phrase = phrase.trim().to_lowercase();
+
+    if score > 0 && !phrase.is_empty() && advisory.title.to_lowercase().contains(&phrase) {
+        score += PHRASE_WEIGHT;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search_score, Query};
+    use advisory::{Advisory, Category, Date, Informational};
+    use cvss::Severity;
+
+    #[test]
+    fn matches_test() {
+        let advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2019-0001"
+package = "openssl"
+date = "2019-06-01"
+severity = "high"
+categories = ["crypto-failure"]
+keywords = ["ssl", "mitm"]
+patched_versions = [">= 1.0.0"]
+title = "Title"
+description = "Description"
+"#,
+        )
+        .unwrap();
+
+        let date = |string| Date::new(string).unwrap();
+
+        assert!(Query::new().matches(&advisory));
+        assert!(Query::new()
+            .package("openssl")
+            .severity_at_least(Severity::High)
+            .after(date("2019-06-01"))
+            .before(date("2019-07-01"))
+            .keyword("ssl")
+            .vulnerabilities()
+            .matches(&advisory));
+
+        assert!(!Query::new().package("a").matches(&advisory));
+        assert!(!Query::new()
+            .severity_at_least(Severity::Critical)
+            .matches(&advisory));
+        assert!(!Query::new().after(date("2019-06-02")).matches(&advisory));
+        assert!(!Query::new().before(date("2019-06-01")).matches(&advisory));
+        assert!(Query::new()
+            .published_between(date("2019-06-01"), date("2019-06-02"))
+            .modified_between(date("2019-06-01"), date("2019-06-02"))
+            .matches(&advisory));
+        assert!(!Query::new()
+            .modified_after(date("2019-06-02"))
+            .matches(&advisory));
+        assert!(!Query::new().keyword("xss").matches(&advisory));
+        assert!(Query::new()
+            .category(Category::CryptoFailure)
+            .published_between(date("2019-01-01"), date("2020-01-01"))
+            .matches(&advisory));
+        assert!(!Query::new()
+            .category(Category::MemoryCorruption)
+            .matches(&advisory));
+        assert!(!Query::new()
+            .informational(Informational::Unmaintained)
+            .matches(&advisory));
+    }
+
+    #[test]
+    fn search_score_test() {
+        let advisory = Advisory::from_toml(
+            r#"[advisory]
+id = "RUSTSEC-2019-0001"
+package = "tokio"
+date = "2019-06-01"
+keywords = ["memory-safety"]
+patched_versions = [">= 1.0.0"]
+title = "Use after free in Runtime"
+description = "A use-after-free can occur when dropping the runtime."
+"#,
+        )
+        .unwrap();
+
+        assert!(
+            search_score(&advisory, "use after free") > search_score(&advisory, "free after use")
+        );
+        assert!(search_score(&advisory, "tokio") > search_score(&advisory, "dropping"));
+        assert!(search_score(&advisory, "memory") > 0);
+        assert_eq!(search_score(&advisory, "xss"), 0);
+        assert_eq!(search_score(&advisory, ""), 0);
+    }
+}
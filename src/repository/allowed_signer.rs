@@ -0,0 +1,192 @@
+use ssh_key::{HashAlg, PublicKey, SshSig};
+
+use error::{Error, ErrorKind};
+
+/// Namespace Git makes SSH signatures in
+const GIT_NAMESPACE: &str = "git";
+
+/// SSH public key trusted to sign commits to the repository, as given by a
+/// line of an allowed signers file (see `ALLOWED SIGNERS` in `ssh-keygen(1)`)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowedSigner {
+    /// Principals (e.g. email addresses) the key belongs to
+    principals: Vec<String>,
+
+    /// Namespaces the key may sign in (if restricted)
+    namespaces: Option<Vec<String>>,
+
+    /// The key itself
+    public_key: PublicKey,
+}
+
+impl AllowedSigner {
+    /// Parse a line of an allowed signers file, i.e. `principals [options] key`.
+    /// The only supported option is `namespaces`.
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        let fields = split_outside_quotes(line.trim(), |c| c.is_ascii_whitespace());
+
+        if fields.len() < 3 {
+            fail!(ErrorKind::Parse, "invalid allowed signer: {}", line);
+        }
+
+        let principals = split_outside_quotes(&fields[0], |c| c == ',')
+            .iter()
+            .map(|principal| unquote(principal).to_owned())
+            .collect();
+
+        // Options are optional, so the second field is either them or the key type
+        let (options, key_fields) = if is_key_type(&fields[1]) {
+            (None, &fields[1..])
+        } else {
+            (Some(&fields[1]), &fields[2..])
+        };
+
+        let mut namespaces = None;
+
+        if let Some(options) = options {
+            for option in split_outside_quotes(options, |c| c == ',') {
+                let mut parts = option.splitn(2, '=');
+                let name = parts.next().unwrap_or_default();
+
+                match (name.to_ascii_lowercase().as_str(), parts.next()) {
+                    ("namespaces", Some(value)) => {
+                        namespaces = Some(unquote(value).split(',').map(str::to_owned).collect());
+                    }
+                    _ => {
+                        fail!(
+                            ErrorKind::Parse,
+                            "unsupported allowed signer option: {}",
+                            option
+                        );
+                    }
+                }
+            }
+        }
+
+        if key_fields.len() < 2 {
+            fail!(ErrorKind::Parse, "invalid allowed signer: {}", line);
+        }
+
+        let public_key = PublicKey::from_openssh(&format!("{} {}", key_fields[0], key_fields[1]))
+            .map_err(|e| err!(ErrorKind::Parse, "invalid allowed signer key: {}", e))?;
+
+        Ok(AllowedSigner {
+            principals,
+            namespaces,
+            public_key,
+        })
+    }
+
+    /// Parse the contents of an allowed signers file, skipping blank lines and comments
+    pub fn parse_file(contents: &str) -> Result<Vec<Self>, Error> {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// Get the principals this key belongs to
+    pub fn principals(&self) -> &[String] {
+        &self.principals
+    }
+
+    /// Get the fingerprint of this key (e.g. `SHA256:...`, as shown by `ssh-keygen -l`)
+    pub fn fingerprint(&self) -> String {
+        self.public_key.fingerprint(HashAlg::Sha256).to_string()
+    }
+
+    /// Is the given signature over `data` made (for Git) by this key?
+    pub(crate) fn verify(&self, signature: &SshSig, data: &[u8]) -> bool {
+        let namespace_allowed = self
+            .namespaces
+            .as_ref()
+            .map(|namespaces| namespaces.iter().any(|ns| ns == GIT_NAMESPACE))
+            .unwrap_or(true);
+
+        namespace_allowed
+            && self
+                .public_key
+                .verify(GIT_NAMESPACE, data, signature)
+                .is_ok()
+    }
+}
+
+/// Is the given allowed signers field an SSH key type (e.g. `ssh-ed25519`)?
+fn is_key_type(field: &str) -> bool {
+    ["ssh-", "ecdsa-", "sk-"]
+        .iter()
+        .any(|prefix| field.starts_with(prefix))
+}
+
+/// Split the given string at the characters matching `is_separator` which
+/// aren't within double quotes, skipping empty parts
+fn split_outside_quotes<F: Fn(char) -> bool>(string: &str, is_separator: F) -> Vec<String> {
+    let mut parts = vec![];
+    let mut part = String::new();
+    let mut quoted = false;
+
+    for c in string.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+
+        if !quoted && is_separator(c) {
+            if !part.is_empty() {
+                parts.push(part);
+                part = String::new();
+            }
+        } else {
+            part.push(c);
+        }
+    }
+
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    parts
+}
+
+/// Remove the double quotes surrounding the given string (if any)
+fn unquote(string: &str) -> &str {
+    if string.len() >= 2 && string.starts_with('"') && string.ends_with('"') {
+        &string[1..string.len() - 1]
+    } else {
+        string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AllowedSigner;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIG7uncG/Nh+cHbo6Uds6fe5sGeK6kjO+bAhRZhsCWjX9";
+
+    #[test]
+    fn parse_test() {
+        let signer = AllowedSigner::parse(&format!("a@example.com,b@example.com {}", KEY)).unwrap();
+        assert_eq!(signer.principals(), &["a@example.com", "b@example.com"]);
+        assert_eq!(signer.namespaces, None);
+        assert_eq!(
+            signer.fingerprint(),
+            "SHA256:WTppHQLw8xMWvZjvaKNad9/7qt8IliivPrQiG16gNBY"
+        );
+
+        let signer =
+            AllowedSigner::parse(&format!("a@example.com namespaces=\"git,file\" {} c", KEY))
+                .unwrap();
+        assert_eq!(
+            signer.namespaces,
+            Some(vec!["git".to_owned(), "file".to_owned()])
+        );
+
+        let contents = format!("# comment\n\na@example.com {}\n", KEY);
+        assert_eq!(AllowedSigner::parse_file(&contents).unwrap().len(), 1);
+
+        assert!(AllowedSigner::parse(&format!("a@example.com cert-authority {}", KEY)).is_err());
+        assert!(AllowedSigner::parse("a@example.com ssh-ed25519").is_err());
+    }
+}
@@ -5,8 +5,72 @@
 use git2;
 use std::env;
 
+use super::Credentials;
 use error::{Error, ErrorKind};
 
+/// Authenticate using the explicitly provided `Credentials`.
+///
+/// Unlike `with_authentication`, no attempt is made to discover credentials
+/// on the system: the provided credentials are tried exactly once.
+pub(crate) fn with_credentials<T, F>(credentials: &Credentials, mut f: F) -> Result<T, Error>
+where
+    F: FnMut(&mut git2::Credentials) -> Result<T, Error>,
+{
+    let mut attempted = false;
+
+    f(&mut |_url, _username, allowed| {
+        // libgit2 asks for a username separately when it's not in the URL
+        if allowed.contains(git2::CredentialType::USERNAME) {
+            return git2::Cred::username(credentials.username());
+        }
+
+        if attempted {
+            return Err(git2::Error::from_str(
+                "failed to authenticate with the provided credentials",
+            ));
+        }
+
+        attempted = true;
+
+        match *credentials {
+            Credentials::UserPass {
+                ref username,
+                ref password,
+            } if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) =>
+            {
+                git2::Cred::userpass_plaintext(username, password)
+            }
+            Credentials::SshKey {
+                ref username,
+                ref public_key,
+                ref private_key,
+                ref passphrase,
+            } if allowed.contains(git2::CredentialType::SSH_KEY) =>
+            {
+                git2::Cred::ssh_key(
+                    username,
+                    public_key.as_ref().map(|path| path.as_ref()),
+                    private_key,
+                    passphrase.as_ref().map(|s| s.as_ref()),
+                )
+            }
+            Credentials::SshAgent { ref username }
+                if allowed.contains(git2::CredentialType::SSH_KEY) =>
+            {
+                git2::Cred::ssh_key_from_agent(username)
+            }
+            Credentials::GitHubToken { ref token }
+                if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) =>
+            {
+                git2::Cred::userpass_plaintext(credentials.username(), token)
+            }
+            _ => Err(git2::Error::from_str(
+                "provided credentials are not supported by the remote",
+            )),
+        }
+    })
+}
+
 /// Prepare the authentication callbacks for cloning a git repository.
 ///
 /// The main purpose of this function is to construct the "authentication
@@ -0,0 +1,758 @@
+//! Backend which uses gitoxide (via the `gix` crate), a pure Rust Git implementation
+
+use gix;
+use std::path::Path;
+#[cfg(feature = "chrono")]
+use std::{
+    convert::TryInto,
+    error, fs, io,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "chrono")]
+use repository::{git_config::GitConfig, Credentials, FetchOptions, Progress, ProgressCallback, Tag};
+use repository::{Commit, Signature};
+
+use error::{Error, ErrorKind};
+
+/// Repository type for this backend
+pub(crate) type Repo = gix::Repository;
+
+/// Clone or fetch the repository at `url` into `path`, pointing HEAD at the
+/// fetched ref
+#[cfg(feature = "chrono")]
+pub(crate) fn fetch(url: &str, path: &Path, options: &FetchOptions) -> Result<(), Error> {
+    let interrupt = Arc::new(AtomicBool::new(false));
+
+    let shallow = if options.shallow {
+        gix::remote::fetch::Shallow::DepthAtRemote(NonZeroU32::new(1).unwrap())
+    } else {
+        gix::remote::fetch::Shallow::NoChange
+    };
+
+    let config_overrides = config_overrides(options)?;
+    let refs = options.refs();
+
+    let mut repo = if path.exists() {
+        gix::open(path)?
+    } else {
+        gix::init(path)?
+    };
+
+    let mut config = repo.config_snapshot_mut();
+    config.append_config(
+        config_overrides.iter().map(String::as_str),
+        gix::config::Source::Api,
+    )?;
+    config.commit()?;
+
+    // Reflog entries need a committer, which may not be configured
+    repo.committer_or_set_generic_fallback()?;
+
+    let refspec = refs.refspec();
+
+    // Fetch remote packfiles and update tips
+    let remote = repo
+        .remote_at(url)?
+        .with_refspecs(Some(refspec.as_str()), gix::remote::Direction::Fetch)?;
+
+    let mut connection = remote
+        .connect(gix::remote::Direction::Fetch)
+        .map_err(|e| transfer_error(e, &interrupt))?;
+
+    if let Some(ref credentials) = options.credentials {
+        connection.set_credentials(credentials_helper(credentials.clone()));
+    }
+
+    // Interrupt the transfer if it takes longer than the configured timeout
+    let _watchdog = options
+        .timeout
+        .map(|timeout| start_watchdog(timeout, interrupt.clone()));
+
+    connection
+        .prepare_fetch(gix::progress::Discard, Default::default())
+        .map_err(|e| transfer_error(e, &interrupt))?
+        .with_shallow(shallow)
+        .receive(
+            TransferProgress::new(options.progress.clone()),
+            &interrupt,
+        )
+        .map_err(|e| transfer_error(e, &interrupt))?;
+
+    // Get the current remote tip (as an updated local reference), or the
+    // target of the most recent matching tag
+    let remote_target = match options.signed_tags {
+        Some(ref pattern) => parse_commit_id(&Tag::latest(&repo, pattern)?.target_id)?,
+        None => {
+            repo.find_reference(refs.tracking.as_str())?
+                .peel_to_commit()?
+                .id
+        }
+    };
+
+    // Check out the pinned commit instead (if any), provided it's reachable
+    let remote_target = match options.commit {
+        Some(ref commit_id) => {
+            let pinned = parse_commit_id(commit_id)?;
+
+            if !is_ancestor(&repo, pinned, remote_target)? {
+                fail!(
+                    ErrorKind::Repo,
+                    "commit {} is not reachable from {}",
+                    commit_id,
+                    &refs.remote
+                );
+            }
+
+            pinned
+        }
+        None => remote_target,
+    };
+
+    // Point HEAD at the fetched commit (via the local branch, if any)
+    let head_target = match refs.branch {
+        Some(ref branch) => {
+            repo.reference(
+                branch.as_str(),
+                remote_target,
+                gix::refs::transaction::PreviousValue::Any,
+                format!(
+                    "rustsec: moving {} to {}: {}",
+                    branch, &refs.tracking, &remote_target
+                ),
+            )?;
+            gix::refs::Target::Symbolic(
+                branch
+                    .as_str()
+                    .try_into()
+                    .map_err(|e| err!(ErrorKind::BadParam, "invalid branch {}: {}", branch, e))?,
+            )
+        }
+        None => gix::refs::Target::Object(remote_target),
+    };
+
+    repo.edit_reference(gix::refs::transaction::RefEdit {
+        change: gix::refs::transaction::Change::Update {
+            log: gix::refs::transaction::LogChange {
+                message: format!("rustsec: checking out {}", &refs.remote).into(),
+                ..Default::default()
+            },
+            expected: gix::refs::transaction::PreviousValue::Any,
+            new: head_target,
+        },
+        name: "HEAD".try_into().unwrap(),
+        deref: false,
+    })?;
+
+    Ok(())
+}
+
+/// Is `ancestor` the same commit as, or an ancestor of, `descendant`?
+#[cfg(feature = "chrono")]
+fn is_ancestor(
+    repo: &Repo,
+    ancestor: gix::ObjectId,
+    descendant: gix::ObjectId,
+) -> Result<bool, Error> {
+    for info in repo.rev_walk(Some(descendant)).all()? {
+        if info?.id == ancestor {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Load the relevant settings from the user's global and system Git configuration
+#[cfg(feature = "chrono")]
+pub(crate) fn user_config() -> Result<GitConfig, Error> {
+    let config = gix::config::File::from_globals()
+        .map_err(|e| err!(ErrorKind::Repo, "couldn't load git config: {}", e))?;
+
+    let mut instead_of = vec![];
+
+    for section in config.sections_by_name("url").into_iter().flatten() {
+        if let Some(base) = section.header().subsection_name() {
+            for prefix in section.values("insteadOf") {
+                instead_of.push((prefix.to_string(), base.to_string()));
+            }
+        }
+    }
+
+    Ok(GitConfig {
+        instead_of,
+        proxy: config.string("http.proxy").map(|proxy| proxy.to_string()),
+    })
+}
+
+/// Get in-memory Git configuration overrides which apply the given `FetchOptions`
+#[cfg(feature = "chrono")]
+fn config_overrides(options: &FetchOptions) -> Result<Vec<String>, Error> {
+    let mut overrides = vec![];
+
+    // gitoxide's reqwest transport always uses the system's TLS configuration
+    if !options.tls.is_default() {
+        fail!(
+            ErrorKind::BadParam,
+            "custom TLS options are unsupported by the gix backend"
+        );
+    }
+
+    if options.max_disk_usage.is_some() {
+        fail!(
+            ErrorKind::BadParam,
+            "disk usage limits are unsupported by the gix backend"
+        );
+    }
+
+    if let Some(proxy_url) = options.proxy_url() {
+        overrides.push(format!("http.proxy={}", proxy_url));
+    }
+
+    if let Some(timeout) = options.connect_timeout {
+        overrides.push(format!(
+            "gitoxide.http.connectTimeout={}",
+            timeout.as_secs() * 1000 + u64::from(timeout.subsec_millis())
+        ));
+    }
+
+    // Send token-based credentials up front, since public repositories
+    // never ask for them
+    if let Some(header) = options
+        .credentials
+        .as_ref()
+        .and_then(Credentials::authorization_header)
+    {
+        overrides.push(format!("http.extraHeader={}", header));
+    }
+
+    // gitoxide uses the `ssh` program for SSH transports, so SSH credentials
+    // are passed to it on the command line. That command may be run through
+    // a shell, so each value interpolated into it is quoted.
+    match options.credentials {
+        Some(Credentials::SshKey {
+            ref username,
+            ref private_key,
+            ref passphrase,
+            ..
+        }) => {
+            if passphrase.is_some() {
+                fail!(
+                    ErrorKind::BadParam,
+                    "SSH key passphrases are unsupported by the gix backend"
+                );
+            }
+
+            overrides.push(format!(
+                "core.sshCommand=ssh -l {} -i {} -o IdentitiesOnly=yes",
+                shell_quote(username),
+                shell_quote(&private_key.to_string_lossy())
+            ));
+        }
+        Some(Credentials::SshAgent { ref username }) => {
+            overrides.push(format!("core.sshCommand=ssh -l {}", shell_quote(username)));
+        }
+        _ => (),
+    }
+
+    Ok(overrides)
+}
+
+/// Quote a value so that a POSIX shell passes it through as a single word
+#[cfg(feature = "chrono")]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Set `interrupt` once the given timeout elapses, unless the returned
+/// `Sender` is dropped first
+#[cfg(feature = "chrono")]
+fn start_watchdog(timeout: Duration, interrupt: Arc<AtomicBool>) -> mpsc::Sender<()> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+            interrupt.store(true, Ordering::Relaxed);
+        }
+    });
+
+    sender
+}
+
+/// Convert errors caused by the transfer timing out into `ErrorKind::Timeout`
+#[cfg(feature = "chrono")]
+fn transfer_error(error: gix::Error, interrupt: &AtomicBool) -> Error {
+    if interrupt.load(Ordering::Relaxed) || caused_by_io_error(&error, &[io::ErrorKind::TimedOut]) {
+        err!(ErrorKind::Timeout, "fetch timed out: {}", error)
+    } else if caused_by_io_error(&error, TRANSIENT_IO_ERRORS) {
+        err!(ErrorKind::Network, "fetch failed: {}", error)
+    } else {
+        error.into()
+    }
+}
+
+/// Kinds of I/O errors which may not recur if the operation is retried
+#[cfg(feature = "chrono")]
+const TRANSIENT_IO_ERRORS: &[io::ErrorKind] = &[
+    io::ErrorKind::BrokenPipe,
+    io::ErrorKind::ConnectionAborted,
+    io::ErrorKind::ConnectionRefused,
+    io::ErrorKind::ConnectionReset,
+    io::ErrorKind::Interrupted,
+    io::ErrorKind::NotConnected,
+    io::ErrorKind::UnexpectedEof,
+];
+
+/// Was the given error (or any of its sources) caused by an I/O error of one
+/// of the given kinds?
+#[cfg(feature = "chrono")]
+fn caused_by_io_error(error: &(dyn error::Error + 'static), kinds: &[io::ErrorKind]) -> bool {
+    let mut source = Some(error);
+
+    while let Some(error) = source {
+        if let Some(io_error) = error.downcast_ref::<io::Error>() {
+            if kinds.contains(&io_error.kind()) {
+                return true;
+            }
+        }
+
+        source = error.source();
+    }
+
+    false
+}
+
+/// Create a credentials helper which answers with the given username and
+/// password (or GitHub token)
+#[cfg(feature = "chrono")]
+fn credentials_helper(
+    credentials: Credentials,
+) -> impl FnMut(gix::credentials::helper::Action) -> gix::Result<Option<gix::credentials::protocol::Outcome>>
+{
+    move |action| match (action, &credentials) {
+        (
+            gix::credentials::helper::Action::Get(context),
+            Credentials::UserPass { username, password },
+        ) => Ok(Some(gix::credentials::protocol::Outcome {
+            identity: gix::sec::identity::Account {
+                username: username.clone(),
+                password: password.clone(),
+                oauth_refresh_token: None,
+            },
+            next: context.into(),
+        })),
+        (gix::credentials::helper::Action::Get(context), Credentials::GitHubToken { token }) => {
+            Ok(Some(gix::credentials::protocol::Outcome {
+                identity: gix::sec::identity::Account {
+                    username: credentials.username().to_owned(),
+                    password: token.clone(),
+                    oauth_refresh_token: None,
+                },
+                next: context.into(),
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// ID of gitoxide's progress for the number of objects received
+#[cfg(feature = "chrono")]
+const PROGRESS_ID_OBJECTS: gix::progress::Id = *b"IWIO";
+
+/// ID of gitoxide's progress for the number of bytes received
+#[cfg(feature = "chrono")]
+const PROGRESS_ID_BYTES: gix::progress::Id = *b"BWRB";
+
+/// Adapter which reports gitoxide's transfer progress to a `ProgressCallback`
+#[cfg(feature = "chrono")]
+struct TransferProgress {
+    /// Callback to report progress to
+    callback: Option<ProgressCallback>,
+
+    /// Overall transfer progress, shared between all of the nested progress
+    state: Arc<Mutex<Progress>>,
+
+    /// ID of the progress this instance tracks
+    id: gix::progress::Id,
+
+    /// Current step of the progress this instance tracks
+    step: gix::progress::StepShared,
+}
+
+#[cfg(feature = "chrono")]
+impl TransferProgress {
+    /// Create a new adapter, reporting to the given callback (if any)
+    fn new(callback: Option<ProgressCallback>) -> Self {
+        TransferProgress {
+            callback,
+            state: Arc::new(Mutex::new(Progress::Transfer {
+                received_objects: 0,
+                total_objects: 0,
+                received_bytes: 0,
+            })),
+            id: gix::progress::UNKNOWN,
+            step: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Update the overall progress and report it to the callback
+    fn update(&self, total: Option<usize>) {
+        let callback = match self.callback {
+            Some(ref callback) => callback,
+            None => return,
+        };
+
+        let step = self.step.load(Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap();
+
+        if let Progress::Transfer {
+            ref mut received_objects,
+            ref mut total_objects,
+            ref mut received_bytes,
+        } = *state
+        {
+            match self.id {
+                PROGRESS_ID_OBJECTS => {
+                    *received_objects = step;
+
+                    if let Some(total) = total {
+                        *total_objects = total;
+                    }
+                }
+                PROGRESS_ID_BYTES => *received_bytes = step,
+                _ => return,
+            }
+        }
+
+        callback.report(*state);
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl gix::progress::Count for TransferProgress {
+    fn set(&self, step: gix::progress::Step) {
+        self.step.store(step, Ordering::Relaxed);
+        self.update(None);
+    }
+
+    fn step(&self) -> gix::progress::Step {
+        self.step.load(Ordering::Relaxed)
+    }
+
+    fn inc_by(&self, step: gix::progress::Step) {
+        self.step.fetch_add(step, Ordering::Relaxed);
+        self.update(None);
+    }
+
+    fn counter(&self) -> gix::progress::StepShared {
+        self.step.clone()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl gix::progress::Progress for TransferProgress {
+    fn init(&mut self, max: Option<gix::progress::Step>, _unit: Option<gix::progress::Unit>) {
+        self.update(max);
+    }
+
+    fn set_name(&mut self, _name: String) {}
+
+    fn name(&self) -> Option<String> {
+        None
+    }
+
+    fn id(&self) -> gix::progress::Id {
+        self.id
+    }
+
+    fn message(&self, _level: gix::progress::MessageLevel, _message: String) {}
+}
+
+#[cfg(feature = "chrono")]
+impl gix::progress::NestedProgress for TransferProgress {
+    type SubProgress = Self;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self {
+        self.add_child_with_id(name, gix::progress::UNKNOWN)
+    }
+
+    fn add_child_with_id(&mut self, _name: impl Into<String>, id: gix::progress::Id) -> Self {
+        TransferProgress {
+            callback: self.callback.clone(),
+            state: self.state.clone(),
+            id,
+            step: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// Open the repository at the given path, ensuring it's in a clean state
+pub(crate) fn open(path: &Path) -> Result<Repo, Error> {
+    let repo = gix::open(path)?;
+
+    match repo.state() {
+        None => Ok(repo),
+        Some(state) => fail!(ErrorKind::Repo, "bad repository state: {:?}", state),
+    }
+}
+
+/// Get the path to the repository's Git directory (e.g. `.git`)
+pub(crate) fn git_dir(repo: &Repo) -> &Path {
+    repo.git_dir()
+}
+
+/// Garbage collect the repository (unsupported, as gitoxide can't yet write packfiles)
+pub(crate) fn gc(_repo: &Repo) -> Result<(), Error> {
+    Err(err!(
+        ErrorKind::BadParam,
+        "garbage collection is unsupported by the gix backend"
+    ))
+}
+
+/// Get information about the commit HEAD points to
+pub(crate) fn head_commit(repo: &Repo, path: &Path) -> Result<Commit, Error> {
+    let oid = repo
+        .head()?
+        .try_peel_to_id()?
+        .ok_or_else(|| err!(ErrorKind::Repo, "no ref target for: {}", path.display()))?;
+
+    commit_info(repo, oid.detach())
+}
+
+/// Get information about the commit with the given ID
+pub(crate) fn find_commit(repo: &Repo, commit_id: &str) -> Result<Commit, Error> {
+    commit_info(repo, parse_commit_id(commit_id)?)
+}
+
+/// Walk the IDs of HEAD and its ancestors, most recent first
+pub(crate) fn history(repo: &Repo) -> Result<History<'_>, Error> {
+    let walk = repo
+        .head_id()?
+        .ancestors()
+        .sorting(gix::revision::walk::Sorting::ByCommitTime(Default::default()))
+        .all()?;
+
+    Ok(History(walk))
+}
+
+/// Iterator over commit IDs, which walks the history as it goes
+pub(crate) struct History<'a>(gix::revision::Walk<'a>);
+
+impl<'a> Iterator for History<'a> {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Result<String, Error>> {
+        Some(
+            self.0
+                .next()?
+                .map(|info| info.id.to_string())
+                .map_err(Error::from),
+        )
+    }
+}
+
+/// Get the paths (relative to the root of the repository) and blob IDs of
+/// the files under the given directory in the tree of the given commit
+pub(crate) fn tree_files(
+    repo: &Repo,
+    commit_id: &str,
+    dir: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let tree_id = repo.find_commit(parse_commit_id(commit_id)?)?.tree_id()?;
+    let index = repo.index_from_tree(&tree_id)?;
+    let prefix = format!("{}/", dir);
+    let mut files = vec![];
+
+    for entry in index.entries() {
+        let entry_path = entry.path(&index).to_string();
+
+        if entry_path.starts_with(&prefix) {
+            files.push((entry_path, entry.id.to_string()));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Get the contents of the blob with the given ID
+pub(crate) fn read_blob(repo: &Repo, blob_id: &str) -> Result<Vec<u8>, Error> {
+    let oid = gix::ObjectId::from_hex(blob_id.as_bytes())
+        .map_err(|e| err!(ErrorKind::Repo, "invalid blob ID {}: {}", blob_id, e))?;
+
+    Ok(repo.find_blob(oid)?.detach().data)
+}
+
+/// Get information about all of the annotated tags in the repository
+#[cfg(feature = "chrono")]
+pub(crate) fn tags(repo: &Repo) -> Result<Vec<Tag>, Error> {
+    let mut tags = vec![];
+
+    for reference in repo.references()?.tags()? {
+        let object = match reference?.target().try_id() {
+            Some(id) => repo.find_object(id)?,
+            None => continue,
+        };
+
+        // Lightweight tags point directly at commits, and can't be signed
+        let tag = match object.try_into_tag() {
+            Ok(tag) => tag,
+            Err(_) => continue,
+        };
+
+        let tag_ref = tag.decode()?;
+        let name = tag_ref.name.to_string();
+
+        let tagger = tag_ref
+            .tagger()?
+            .ok_or_else(|| err!(ErrorKind::Repo, "no tagger for tag {}", name))?;
+
+        let target_id = repo
+            .find_object(tag_ref.target())?
+            .peel_to_commit()?
+            .id
+            .to_string();
+
+        tags.push(Tag::new(
+            name,
+            target_id,
+            format!("{} <{}>", tagger.name, tagger.email),
+            Commit::time_from_seconds(tagger.time()?.seconds),
+            &tag.data,
+        )?);
+    }
+
+    Ok(tags)
+}
+
+/// Parse a hex commit ID
+fn parse_commit_id(commit_id: &str) -> Result<gix::ObjectId, Error> {
+    gix::ObjectId::from_hex(commit_id.as_bytes())
+        .map_err(|e| err!(ErrorKind::Repo, "invalid commit ID {}: {}", commit_id, e))
+}
+
+/// Get information about the commit with the given object ID
+fn commit_info(repo: &Repo, oid: gix::ObjectId) -> Result<Commit, Error> {
+    let commit_id = oid.to_string();
+    let commit = repo.find_commit(oid)?;
+
+    let author = {
+        let author = commit.author()?;
+        format!("{} <{}>", author.name, author.email)
+    };
+
+    let summary = commit.message()?.summary().to_string();
+
+    let (signature, signed_data) = match commit.signature()? {
+        Some((sig, data)) => (
+            Some(Signature::new(sig.to_vec())?),
+            Some(data.to_bstring().into()),
+        ),
+        None => (None, None),
+    };
+
+    Ok(Commit {
+        commit_id,
+        author,
+        summary,
+        #[cfg(feature = "chrono")]
+        time: Commit::time_from_seconds(commit.time()?.seconds),
+        signature,
+        signed_data,
+    })
+}
+
+/// Hard reset the repository's state to match the given commit
+#[cfg(feature = "chrono")]
+pub(crate) fn reset(repo: &Repo, commit_id: &str, options: &FetchOptions) -> Result<(), Error> {
+    let tree_id = repo.find_commit(parse_commit_id(commit_id)?)?.tree_id()?;
+
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| err!(ErrorKind::Repo, "bare repository: {}", repo.path().display()))?;
+
+    let old_index = repo.index_or_empty()?;
+    let mut new_index = repo.index_from_tree(&tree_id)?;
+
+    // Mark files outside of a sparse checkout as such, so they aren't checked out
+    for (entry, entry_path) in new_index.entries_mut_with_paths() {
+        if !options.is_checked_out(&gix::path::from_bstr(entry_path)?) {
+            entry.flags.insert(gix::index::entry::Flags::SKIP_WORKTREE);
+        }
+    }
+
+    // Remove any files which are no longer present in the new tree (or which
+    // are now outside of a sparse checkout)
+    for entry in old_index.entries() {
+        let entry_path = entry.path(&old_index);
+        let relative_path = gix::path::from_bstr(entry_path)?;
+
+        if new_index.entry_by_path(entry_path).is_none() || !options.is_checked_out(&relative_path)
+        {
+            let file_path = workdir.join(relative_path);
+
+            if file_path.exists() {
+                fs::remove_file(file_path)?;
+            }
+        }
+    }
+
+    let mut checkout_opts =
+        repo.checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)?;
+    checkout_opts.overwrite_existing = true;
+
+    // gitoxide counts checked out files internally, so only report the start and end
+    let total = new_index
+        .entries()
+        .iter()
+        .filter(|entry| !entry.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE))
+        .count();
+
+    if let Some(ref progress) = options.progress {
+        progress.report(Progress::Checkout {
+            completed: 0,
+            total,
+        });
+    }
+
+    gix::worktree::state::checkout(
+        &mut new_index,
+        workdir,
+        repo.objects.clone().into_arc()?,
+        &gix::progress::Discard,
+        &gix::progress::Discard,
+        &AtomicBool::new(false),
+        checkout_opts,
+    )?;
+
+    new_index.write(Default::default())?;
+
+    if let Some(ref progress) = options.progress {
+        progress.report(Progress::Checkout {
+            completed: total,
+            total,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::shell_quote;
+
+    #[test]
+    fn shell_quote_test() {
+        assert_eq!(shell_quote("git"), "'git'");
+        assert_eq!(shell_quote("/home/me/my key"), "'/home/me/my key'");
+        assert_eq!(
+            shell_quote("x'; rm -rf ~; echo '"),
+            r"'x'\''; rm -rf ~; echo '\'''"
+        );
+    }
+}
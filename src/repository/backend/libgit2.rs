@@ -0,0 +1,822 @@
+//! Backend which uses libgit2 (via the `git2` crate)
+
+use git2;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+#[cfg(feature = "chrono")]
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "chrono")]
+use repository::authentication::{with_authentication, with_credentials};
+#[cfg(feature = "chrono")]
+use repository::tls::CertificateVerifier;
+#[cfg(feature = "chrono")]
+use repository::{
+    git_config::GitConfig, Credentials, FetchOptions, Progress, Tag, SPARSE_CHECKOUT_DIRECTORIES,
+};
+use repository::{Commit, Signature};
+
+use error::{Error, ErrorKind};
+
+/// Repository type for this backend
+pub(crate) type Repo = git2::Repository;
+
+/// How often to check whether a fetch has timed out
+#[cfg(feature = "chrono")]
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Clone or fetch the repository at `url` into `path`, pointing HEAD at the
+/// fetched ref
+#[cfg(feature = "chrono")]
+pub(crate) fn fetch(url: &str, path: &Path, options: &FetchOptions) -> Result<(), Error> {
+    if options.timeout.is_none() && options.connect_timeout.is_none() {
+        return fetch_watched(url, path, options, &Watchdog::default());
+    }
+
+    // libgit2's own timeouts are process-wide settings which can't be changed
+    // safely, and its callbacks aren't called at all while a connection hangs,
+    // so fetch on another thread and abort it once a deadline passes
+    let watchdog = Arc::new(Watchdog::default());
+    let (sender, receiver) = mpsc::channel();
+
+    let fetch_thread = {
+        let url = url.to_owned();
+        let path = path.to_owned();
+        let options = options.clone();
+        let watchdog = watchdog.clone();
+
+        thread::spawn(move || {
+            // The receiver is gone if the fetch has already timed out
+            let _ = sender.send(fetch_watched(&url, &path, &options, &watchdog));
+        })
+    };
+
+    let start = Instant::now();
+
+    loop {
+        match receiver.recv_timeout(WATCHDOG_INTERVAL) {
+            Ok(result) => return result,
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                fail!(ErrorKind::Repo, "fetch of {} panicked", url);
+            }
+        }
+
+        let elapsed = start.elapsed();
+
+        if let Some(timeout) = options.timeout {
+            if elapsed >= timeout {
+                watchdog.stop(fetch_thread);
+                fail!(ErrorKind::Timeout, "fetch of {} timed out", url);
+            }
+        }
+
+        if let Some(connect_timeout) = options.connect_timeout {
+            if elapsed >= connect_timeout && !watchdog.is_connected() {
+                watchdog.stop(fetch_thread);
+                fail!(ErrorKind::Timeout, "timed out connecting to {}", url);
+            }
+        }
+    }
+}
+
+/// Tracks the progress of a fetch, which it aborts once it has timed out
+#[cfg(feature = "chrono")]
+#[derive(Default)]
+struct Watchdog {
+    /// Has the remote responded yet?
+    connected: AtomicBool,
+
+    /// Should the fetch be aborted?
+    aborted: AtomicBool,
+}
+
+#[cfg(feature = "chrono")]
+impl Watchdog {
+    /// Record that libgit2 has called back into us, which it only does once
+    /// the remote has responded, and get whether the fetch should continue
+    fn check(&self) -> bool {
+        self.connected.store(true, Ordering::Relaxed);
+        !self.aborted.load(Ordering::Relaxed)
+    }
+
+    /// Has the remote responded yet?
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Abort the fetch running on `fetch_thread` the next time libgit2 calls
+    /// back into us, and wait for it to stop, so that nothing is still writing
+    /// to the repository (which is unlocked once the fetch returns) afterwards
+    fn stop(&self, fetch_thread: thread::JoinHandle<()>) {
+        self.aborted.store(true, Ordering::Relaxed);
+        let _ = fetch_thread.join();
+    }
+}
+
+/// Error returned from callbacks to abort a fetch
+#[cfg(feature = "chrono")]
+fn aborted() -> git2::Error {
+    git2::Error::from_str("fetch aborted")
+}
+
+/// Clone or fetch the repository, aborting when `watchdog` says to
+#[cfg(feature = "chrono")]
+fn fetch_watched(
+    url: &str,
+    path: &Path,
+    options: &FetchOptions,
+    watchdog: &Watchdog,
+) -> Result<(), Error> {
+    let git_config = git2::Config::new()?;
+    let proxy_url = options.proxy_url();
+    let refs = options.refs();
+
+    let verifier = CertificateVerifier::new(&options.tls)?;
+    let authorization_header = options
+        .credentials
+        .as_ref()
+        .and_then(Credentials::authorization_header);
+
+    let run = |f: &mut git2::Credentials| -> Result<(), Error> {
+        let mut callbacks = git2::RemoteCallbacks::new();
+
+        callbacks.credentials(move |url, username, allowed| {
+            if !watchdog.check() {
+                return Err(aborted());
+            }
+
+            f(url, username, allowed)
+        });
+
+        // Accept certificates libgit2 rejects if our TLS options allow them
+        callbacks.certificate_check(|cert, host| {
+            if !watchdog.check() {
+                return Err(aborted());
+            }
+
+            Ok(match cert.as_x509() {
+                Some(x509) if verifier.accepts(x509.data(), host) => {
+                    git2::CertificateCheckStatus::CertificateOk
+                }
+                _ => git2::CertificateCheckStatus::CertificatePassthrough,
+            })
+        });
+
+        callbacks.transfer_progress(move |stats| {
+            if let Some(ref progress) = options.progress {
+                progress.report(Progress::Transfer {
+                    received_objects: stats.received_objects(),
+                    total_objects: stats.total_objects(),
+                    received_bytes: stats.received_bytes(),
+                });
+            }
+
+            watchdog.check()
+        });
+
+        callbacks.sideband_progress(|_| watchdog.check());
+        callbacks.update_tips(|_, _, _| watchdog.check());
+
+        let mut fetch_opts = git2::FetchOptions::new();
+        fetch_opts.remote_callbacks(callbacks);
+
+        if let Some(ref proxy_url) = proxy_url {
+            let mut proxy_opts = git2::ProxyOptions::new();
+            proxy_opts.url(proxy_url);
+            fetch_opts.proxy_options(proxy_opts);
+        }
+
+        if options.shallow {
+            fetch_opts.depth(1);
+        }
+
+        // Send token-based credentials up front, since public repositories
+        // never ask for them
+        if let Some(ref header) = authorization_header {
+            fetch_opts.custom_headers(&[header.as_str()]);
+        }
+
+        let repo = if path.exists() {
+            git2::Repository::open(path)?
+        } else {
+            git2::Repository::init(path)?
+        };
+
+        // Fetch remote packfiles and update tips
+        let mut remote = repo.remote_anonymous(url)?;
+        remote
+            .fetch(&[refs.refspec().as_str()], Some(&mut fetch_opts), None)
+            .map_err(transfer_error)?;
+
+        // Get the current remote tip (as an updated local reference), or the
+        // target of the most recent matching tag
+        let remote_target = match options.signed_tags {
+            Some(ref pattern) => git2::Oid::from_str(&Tag::latest(&repo, pattern)?.target_id)?,
+            None => repo.find_reference(&refs.tracking)?.peel_to_commit()?.id(),
+        };
+
+        // Check out the pinned commit instead (if any), provided it's reachable
+        let remote_target = match options.commit {
+            Some(ref commit_id) => {
+                let pinned = git2::Oid::from_str(commit_id).map_err(|e| {
+                    err!(ErrorKind::Repo, "invalid commit ID {}: {}", commit_id, e)
+                })?;
+
+                // Errors (e.g. the pinned commit not being present) mean it isn't reachable
+                let reachable = pinned == remote_target
+                    || repo
+                        .graph_descendant_of(remote_target, pinned)
+                        .unwrap_or(false);
+
+                if !reachable {
+                    fail!(
+                        ErrorKind::Repo,
+                        "commit {} is not reachable from {}",
+                        commit_id,
+                        &refs.remote
+                    );
+                }
+
+                pinned
+            }
+            None => remote_target,
+        };
+
+        // Point HEAD at the fetched commit (via the local branch, if any)
+        match refs.branch {
+            Some(ref branch) => {
+                repo.reference(
+                    branch,
+                    remote_target,
+                    true,
+                    &format!(
+                        "rustsec: moving {} to {}: {}",
+                        branch, &refs.tracking, &remote_target
+                    ),
+                )?;
+                repo.set_head(branch)?;
+            }
+            None => repo.set_head_detached(remote_target)?,
+        }
+
+        Ok(())
+    };
+
+    match options.credentials {
+        Some(ref credentials) => with_credentials(credentials, run),
+        None => with_authentication(url, &git_config, run),
+    }
+}
+
+/// Load the relevant settings from the user's global and system Git configuration
+#[cfg(feature = "chrono")]
+pub(crate) fn user_config() -> Result<GitConfig, Error> {
+    let config = git2::Config::open_default()?;
+    let mut instead_of = vec![];
+
+    let mut entries = config.entries(Some(r"url\..*\.insteadof"))?;
+
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+
+        if let (Some(name), Some(prefix)) = (entry.name(), entry.value()) {
+            // Names are of the form `url.<base>.insteadof`
+            let base = &name["url.".len()..name.len() - ".insteadof".len()];
+            instead_of.push((prefix.to_owned(), base.to_owned()));
+        }
+    }
+
+    Ok(GitConfig {
+        instead_of,
+        proxy: config.get_string("http.proxy").ok(),
+    })
+}
+
+/// Convert errors caused by the transfer timing out into `ErrorKind::Timeout`,
+/// and other transient errors into `ErrorKind::Network`
+#[cfg(feature = "chrono")]
+fn transfer_error(error: git2::Error) -> Error {
+    if error.code() == git2::ErrorCode::Timeout {
+        err!(ErrorKind::Timeout, "fetch timed out: {}", error)
+    } else if is_transient(&error) {
+        err!(ErrorKind::Network, "fetch failed: {}", error)
+    } else {
+        error.into()
+    }
+}
+
+/// Might retrying the operation which caused the given error succeed?
+#[cfg(feature = "chrono")]
+fn is_transient(error: &git2::Error) -> bool {
+    match error.class() {
+        // e.g. failing to connect, or the connection being closed early
+        git2::ErrorClass::Net | git2::ErrorClass::Os => true,
+        // Only server errors and rate limiting are worth retrying
+        git2::ErrorClass::Http => http_status(error.message())
+            .map(|status| status >= 500 || status == 429)
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Parse the status code from an unexpected HTTP status error message
+#[cfg(feature = "chrono")]
+fn http_status(message: &str) -> Option<u16> {
+    let prefix = "unexpected http status code: ";
+
+    message
+        .strip_prefix(prefix)
+        .and_then(|status| status.trim().parse().ok())
+}
+
+/// Open the repository at the given path, ensuring it's in a clean state
+pub(crate) fn open(path: &Path) -> Result<Repo, Error> {
+    let repo = git2::Repository::open(path)?;
+
+    match repo.state() {
+        git2::RepositoryState::Clean => Ok(repo),
+        state => fail!(ErrorKind::Repo, "bad repository state: {:?}", state),
+    }
+}
+
+/// Get the path to the repository's Git directory (e.g. `.git`)
+pub(crate) fn git_dir(repo: &Repo) -> &Path {
+    repo.path()
+}
+
+/// Repack the objects reachable from HEAD and the repository's refs into a
+/// single packfile, removing the packfiles and loose objects which existed
+/// beforehand (like `git repack -a -d`, followed by `git prune`). libgit2 has
+/// no garbage collection of its own, so objects are packed with its pack
+/// builder and then removed directly.
+pub(crate) fn gc(repo: &Repo) -> Result<(), Error> {
+    let objects_dir = repo.path().join("objects");
+    let pack_dir = objects_dir.join("pack");
+
+    // Only what's there now is removed, so objects written while repacking
+    // (e.g. by another Git process) are kept
+    let old_packs = pack_files(&pack_dir)?;
+    let loose_objects = loose_object_files(&objects_dir)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.push_glob("refs/*")?;
+
+    let mut pack_builder = repo.packbuilder()?;
+    pack_builder.insert_walk(&mut walk)?;
+
+    // Annotated tags aren't commits, so they aren't included in the walk
+    for reference in repo.references_glob("refs/tags/*")? {
+        if let Some(oid) = reference?.target() {
+            if repo.find_tag(oid).is_ok() {
+                pack_builder.insert_object(oid, None)?;
+            }
+        }
+    }
+
+    if pack_builder.object_count() == 0 {
+        return Ok(());
+    }
+
+    pack_builder.write(&pack_dir, 0)?;
+
+    let pack_prefix = pack_builder
+        .name()
+        .map(|name| format!("pack-{}.", name))
+        .ok_or_else(|| err!(ErrorKind::Repo, "error writing packfile"))?;
+
+    // If the objects were already packed the same way, the pack is unchanged
+    for path in old_packs {
+        if !file_name_starts_with(&path, &pack_prefix) {
+            fs::remove_file(path)?;
+        }
+    }
+
+    for path in loose_objects {
+        fs::remove_file(&path)?;
+
+        // Remove the object's directory once it's empty (which fails otherwise)
+        if let Some(dir) = path.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    repo.odb()?.refresh()?;
+    Ok(())
+}
+
+/// Get the paths of the packfiles (and their indexes, etc) in the given directory
+fn pack_files(pack_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+
+    for entry in fs::read_dir(pack_dir)? {
+        let path = entry?.path();
+
+        if file_name_starts_with(&path, "pack-") {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Get the paths of the loose objects in the given objects directory, which
+/// are kept in directories named after the first two hex digits of their IDs
+fn loose_object_files(objects_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+
+    for entry in fs::read_dir(objects_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if file_name.len() == 2 && file_name.chars().all(|c| c.is_ascii_hexdigit()) {
+            for object in fs::read_dir(entry.path())? {
+                paths.push(object?.path());
+            }
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Does the name of the file at the given path start with the given prefix?
+fn file_name_starts_with(path: &Path, prefix: &str) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with(prefix))
+        .unwrap_or(false)
+}
+
+/// Get information about the commit HEAD points to
+pub(crate) fn head_commit(repo: &Repo, path: &Path) -> Result<Commit, Error> {
+    let head = repo.head()?;
+
+    let oid = head
+        .target()
+        .ok_or_else(|| err!(ErrorKind::Repo, "no ref target for: {}", path.display()))?;
+
+    commit_info(repo, oid)
+}
+
+/// Get information about the commit with the given ID
+pub(crate) fn find_commit(repo: &Repo, commit_id: &str) -> Result<Commit, Error> {
+    let oid = git2::Oid::from_str(commit_id)
+        .map_err(|e| err!(ErrorKind::Repo, "invalid commit ID {}: {}", commit_id, e))?;
+
+    commit_info(repo, oid)
+}
+
+/// Walk the IDs of HEAD and its ancestors, most recent first
+pub(crate) fn history(repo: &Repo) -> Result<History<'_>, Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.push_head()?;
+
+    Ok(History(revwalk))
+}
+
+/// Iterator over commit IDs, which walks the history as it goes
+pub(crate) struct History<'a>(git2::Revwalk<'a>);
+
+impl<'a> Iterator for History<'a> {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Result<String, Error>> {
+        Some(
+            self.0
+                .next()?
+                .map(|oid| oid.to_string())
+                .map_err(Error::from),
+        )
+    }
+}
+
+/// Get the paths (relative to the root of the repository) and blob IDs of
+/// the files under the given directory in the tree of the given commit
+pub(crate) fn tree_files(
+    repo: &Repo,
+    commit_id: &str,
+    dir: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let oid = git2::Oid::from_str(commit_id)
+        .map_err(|e| err!(ErrorKind::Repo, "invalid commit ID {}: {}", commit_id, e))?;
+
+    let tree = repo.find_commit(oid)?.tree()?;
+
+    let dir_tree = match tree.get_path(Path::new(dir)) {
+        Ok(entry) => entry.to_object(repo)?.peel_to_tree()?,
+        Err(ref e) if e.code() == git2::ErrorCode::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut files = vec![];
+
+    dir_tree.walk(git2::TreeWalkMode::PreOrder, |parent, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                files.push((
+                    format!("{}/{}{}", dir, parent, name),
+                    entry.id().to_string(),
+                ));
+            }
+        }
+
+        git2::TreeWalkResult::Ok
+    })?;
+
+    Ok(files)
+}
+
+/// Get the contents of the blob with the given ID
+pub(crate) fn read_blob(repo: &Repo, blob_id: &str) -> Result<Vec<u8>, Error> {
+    let oid = git2::Oid::from_str(blob_id)
+        .map_err(|e| err!(ErrorKind::Repo, "invalid blob ID {}: {}", blob_id, e))?;
+
+    Ok(repo.find_blob(oid)?.content().to_vec())
+}
+
+/// Get information about all of the annotated tags in the repository
+#[cfg(feature = "chrono")]
+pub(crate) fn tags(repo: &Repo) -> Result<Vec<Tag>, Error> {
+    let odb = repo.odb()?;
+    let mut tags = vec![];
+
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+
+        // Lightweight tags point directly at commits, and can't be signed
+        let tag = match reference.peel_to_tag() {
+            Ok(tag) => tag,
+            Err(_) => continue,
+        };
+
+        let name = String::from_utf8_lossy(tag.name_bytes()).into_owned();
+        let target_id = tag.target()?.peel_to_commit()?.id().to_string();
+
+        let (tagger, time) = match tag.tagger() {
+            Some(tagger) => (tagger.to_string(), tagger.when().seconds()),
+            None => fail!(ErrorKind::Repo, "no tagger for tag {}", name),
+        };
+
+        tags.push(Tag::new(
+            name,
+            target_id,
+            tagger,
+            Commit::time_from_seconds(time),
+            odb.read(tag.id())?.data(),
+        )?);
+    }
+
+    Ok(tags)
+}
+
+/// Get information about the commit with the given object ID
+fn commit_info(repo: &Repo, oid: git2::Oid) -> Result<Commit, Error> {
+    let commit_id = oid.to_string();
+    let commit_object = repo.find_object(oid, Some(git2::ObjectType::Commit))?;
+    let commit = commit_object.as_commit().unwrap();
+    let author = commit.author().to_string();
+
+    let summary = commit
+        .summary()
+        .ok_or_else(|| err!(ErrorKind::Repo, "no commit summary for {}", commit_id))?
+        .to_owned();
+
+    let (signature, signed_data) = match repo.extract_signature(&oid, None) {
+        Ok((sig, data)) => (Some(Signature::new(&*sig)?), Some(Vec::from(&*data))),
+        _ => (None, None),
+    };
+
+    Ok(Commit {
+        commit_id,
+        author,
+        summary,
+        #[cfg(feature = "chrono")]
+        time: Commit::time_from_seconds(commit.time().seconds()),
+        signature,
+        signed_data,
+    })
+}
+
+/// Hard reset the repository's state to match the given commit
+#[cfg(feature = "chrono")]
+pub(crate) fn reset(repo: &Repo, commit_id: &str, options: &FetchOptions) -> Result<(), Error> {
+    let commit_object = repo.find_object(
+        git2::Oid::from_str(commit_id).unwrap(),
+        Some(git2::ObjectType::Commit),
+    )?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+
+    if let Some(ref progress) = options.progress {
+        checkout.progress(move |_path, completed, total| {
+            progress.report(Progress::Checkout { completed, total })
+        });
+    }
+
+    // libgit2 doesn't support sparse checkouts, so limit the checkout to the
+    // advisory directories (files outside of them are left as they are)
+    if options.sparse_checkout {
+        for dir in SPARSE_CHECKOUT_DIRECTORIES {
+            checkout.path(dir);
+        }
+    }
+
+    repo.reset(&commit_object, git2::ResetType::Hard, Some(&mut checkout))?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::{fetch, gc, history, loose_object_files};
+    use error::ErrorKind;
+    use git2;
+    use repository::{FetchOptions, RetryPolicy};
+    use std::{
+        env, fs,
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    /// How long `unresponsive_server` holds connections open for
+    const HANG_TIME: Duration = Duration::from_secs(1);
+
+    /// Start a server which accepts connections but never responds, closing
+    /// them after `HANG_TIME`, and get a URL to fetch from it along with the
+    /// times each connection was opened and closed
+    fn unresponsive_server() -> (String, mpsc::Receiver<(Instant, Instant)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://{}/advisory-db.git", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let opened = Instant::now();
+                let sender = sender.clone();
+
+                thread::spawn(move || {
+                    thread::sleep(HANG_TIME);
+                    drop(stream);
+                    let _ = sender.send((opened, Instant::now()));
+                });
+            }
+        });
+
+        (url, receiver)
+    }
+
+    #[test]
+    fn timeout_test() {
+        let (url, _) = unresponsive_server();
+        let path = env::temp_dir().join("rustsec-libgit2-timeout-test");
+
+        let timeouts = vec![
+            FetchOptions {
+                timeout: Some(Duration::from_millis(500)),
+                ..FetchOptions::default()
+            },
+            FetchOptions {
+                connect_timeout: Some(Duration::from_millis(500)),
+                ..FetchOptions::default()
+            },
+        ];
+
+        for options in timeouts {
+            let start = Instant::now();
+            let error = fetch(&url, &path, &options).unwrap_err();
+
+            // The timeout is only returned once the hung connection has failed
+            assert_eq!(error.kind(), ErrorKind::Timeout);
+            assert!(start.elapsed() >= HANG_TIME);
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn timeout_retry_test() {
+        let (url, connections) = unresponsive_server();
+        let path = env::temp_dir().join("rustsec-libgit2-timeout-retry-test");
+
+        let options = FetchOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..FetchOptions::default()
+        };
+
+        let retry = RetryPolicy {
+            max_retries: 1,
+            initial_delay: Duration::from_millis(0),
+            ..RetryPolicy::default()
+        };
+
+        let error = retry.run(|| fetch(&url, &path, &options)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Timeout);
+
+        // The retry only connects once the timed out attempt has stopped
+        let (_, first_closed) = connections.recv().unwrap();
+        let (second_opened, _) = connections.recv().unwrap();
+        assert!(second_opened >= first_closed);
+    }
+
+    #[test]
+    fn gc_test() {
+        let path = env::temp_dir().join("rustsec-libgit2-gc-test");
+        let _ = fs::remove_dir_all(&path);
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let signature = git2::Signature::now("RustSec", "test@example.com").unwrap();
+        let mut parent = None;
+
+        for message in &["First", "Second"] {
+            let blob = repo.blob(message.as_bytes()).unwrap();
+            let mut tree = repo.treebuilder(None).unwrap();
+            tree.insert("README.md", blob, 0o100_644).unwrap();
+            let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+            let parents = parent.iter().collect::<Vec<_>>();
+
+            let commit_id = repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            parent = Some(repo.find_commit(commit_id).unwrap());
+        }
+
+        // An unreachable object, which is removed
+        let unreachable = repo.blob(b"Unreachable").unwrap();
+        let objects_dir = path.join(".git").join("objects");
+        assert!(!loose_object_files(&objects_dir).unwrap().is_empty());
+
+        gc(&repo).unwrap();
+        assert!(loose_object_files(&objects_dir).unwrap().is_empty());
+
+        let repo = git2::Repository::open(&path).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Second"));
+        assert_eq!(head.parent(0).unwrap().summary(), Some("First"));
+        assert!(repo.find_blob(unreachable).is_err());
+
+        // Collecting again replaces the pack with an identical one
+        gc(&repo).unwrap();
+        assert!(repo.head().unwrap().peel_to_commit().is_ok());
+    }
+
+    #[test]
+    fn history_test() {
+        let path = env::temp_dir().join("rustsec-libgit2-history-test");
+        let _ = fs::remove_dir_all(&path);
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let mut commit_ids = vec![];
+
+        for (time, message) in ["First", "Second", "Third"].iter().enumerate() {
+            let time = git2::Time::new(1_500_000_000 + time as i64 * 60, 0);
+            let signature = git2::Signature::new("RustSec", "test@example.com", &time).unwrap();
+            let parent = commit_ids.last().map(|id| repo.find_commit(*id).unwrap());
+            let parents = parent.iter().collect::<Vec<_>>();
+
+            commit_ids.push(
+                repo.commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap(),
+            );
+        }
+
+        let walked = history(&repo)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let expected = commit_ids
+            .iter()
+            .rev()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(walked, expected);
+    }
+}
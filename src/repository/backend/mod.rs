@@ -0,0 +1,16 @@
+//! Git backends used to clone, fetch, and inspect advisory DB repositories
+//!
+//! Each backend provides the same set of functions which operate on its own
+//! `Repo` type, allowing `Repository` to be agnostic of the Git implementation.
+
+#[cfg(feature = "git2")]
+mod libgit2;
+
+#[cfg(feature = "git2")]
+pub(crate) use self::libgit2::*;
+
+#[cfg(all(feature = "gix", not(feature = "git2")))]
+mod gitoxide;
+
+#[cfg(all(feature = "gix", not(feature = "git2")))]
+pub(crate) use self::gitoxide::*;
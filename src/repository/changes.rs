@@ -0,0 +1,81 @@
+//! Changes to the advisories in a repository between two commits
+
+use std::{collections::BTreeMap, path::Path};
+
+use super::{backend, is_advisory_file, Repository};
+use advisory::{AdvisoryId, Collection};
+use error::{Error, ErrorKind};
+
+/// Advisories which were added, modified, or removed between two commits
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AdvisoryChanges {
+    /// Advisories which are present in the newer commit but not the older one
+    pub added: Vec<AdvisoryId>,
+
+    /// Advisories whose files differ between the two commits
+    pub modified: Vec<AdvisoryId>,
+
+    /// Advisories which are present in the older commit but not the newer one
+    pub removed: Vec<AdvisoryId>,
+}
+
+impl AdvisoryChanges {
+    /// Compute the changes to the advisories in the given repository between
+    /// the `from` and `to` commits
+    pub(crate) fn between(repo: &Repository, from: &str, to: &str) -> Result<Self, Error> {
+        let old = advisory_blobs(repo, from)?;
+        let new = advisory_blobs(repo, to)?;
+        let mut changes = AdvisoryChanges::default();
+
+        for (id, blob_id) in &new {
+            match old.get(id) {
+                Some(old_blob_id) if old_blob_id != blob_id => {
+                    changes.modified.push(AdvisoryId::new(id.as_str())?)
+                }
+                Some(_) => (),
+                None => changes.added.push(AdvisoryId::new(id.as_str())?),
+            }
+        }
+
+        for id in old.keys() {
+            if !new.contains_key(id) {
+                changes.removed.push(AdvisoryId::new(id.as_str())?);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Are there no changes?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Get the blob IDs of the advisory files in the given commit, keyed by advisory ID
+fn advisory_blobs(repo: &Repository, commit_id: &str) -> Result<BTreeMap<String, String>, Error> {
+    let mut blobs = BTreeMap::new();
+
+    let mut files = vec![];
+
+    for collection in Collection::all() {
+        files.extend(backend::tree_files(&repo.repo, commit_id, collection.as_str())?);
+    }
+
+    for (path, blob_id) in files {
+        let path = Path::new(&path);
+
+        if !is_advisory_file(path) {
+            continue;
+        }
+
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| err!(ErrorKind::Repo, "invalid advisory path: {}", path.display()))?;
+
+        blobs.insert(id.to_owned(), blob_id);
+    }
+
+    Ok(blobs)
+}
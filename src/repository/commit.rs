@@ -1,10 +1,12 @@
 #[cfg(feature = "chrono")]
-use chrono::{DateTime, Duration, NaiveDateTime, Utc};
-use git2;
+use chrono::{DateTime, Utc};
 
 #[cfg(feature = "chrono")]
-use super::DAYS_UNTIL_STALE;
-use super::{signature::Signature, Repository};
+use super::{freshness::FreshnessPolicy, keyring::Keyring, FetchOptions};
+use super::{
+    allowed_signer::AllowedSigner, backend, signature::Signature, signing_key::SigningKey,
+    Repository,
+};
 use error::{Error, ErrorKind};
 
 /// Information about a commit to the Git repository
@@ -24,56 +26,22 @@ pub struct Commit {
     pub time: DateTime<Utc>,
 
     /// Signature on the commit (mandatory for Repository::fetch)
-    // TODO: actually verify signatures
     pub signature: Option<Signature>,
 
     /// Signed data to verify along with this commit
-    signed_data: Option<Vec<u8>>,
+    pub(super) signed_data: Option<Vec<u8>>,
 }
 
 impl Commit {
     /// Get information about HEAD
     pub(crate) fn from_repo_head(repo: &Repository) -> Result<Self, Error> {
-        let head = repo.repo.head()?;
-
-        let oid = head.target().ok_or_else(|| {
-            err!(
-                ErrorKind::Repo,
-                "no ref target for: {}",
-                repo.path.display()
-            )
-        })?;
-
-        let commit_id = oid.to_string();
-        let commit_object = repo.repo.find_object(oid, Some(git2::ObjectType::Commit))?;
-        let commit = commit_object.as_commit().unwrap();
-        let author = commit.author().to_string();
-
-        let summary = commit
-            .summary()
-            .ok_or_else(|| err!(ErrorKind::Repo, "no commit summary for {}", commit_id))?
-            .to_owned();
-
-        let (signature, signed_data) = match repo.repo.extract_signature(&oid, None) {
-            Ok((sig, data)) => (Some(Signature::new(&*sig)?), Some(Vec::from(&*data))),
-            _ => (None, None),
-        };
+        backend::head_commit(&repo.repo, &repo.path)
+    }
 
-        #[cfg(feature = "chrono")]
-        let time = DateTime::from_utc(
-            NaiveDateTime::from_timestamp(commit.time().seconds(), 0),
-            Utc,
-        );
-
-        Ok(Commit {
-            commit_id,
-            author,
-            summary,
-            #[cfg(feature = "chrono")]
-            time,
-            signature,
-            signed_data,
-        })
+    /// Convert a commit time (in seconds since the UNIX epoch) to a `DateTime`
+    #[cfg(feature = "chrono")]
+    pub(super) fn time_from_seconds(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(seconds, 0).expect("commit time out of range")
     }
 
     /// Get the raw bytes to be verified when verifying a commit signature
@@ -81,37 +49,63 @@ impl Commit {
         self.signed_data.as_ref().map(|bytes| bytes.as_ref())
     }
 
-    /// Reset the repository's state to match this commit
+    /// Ensure this commit is signed by one of the given trusted keys
+    pub fn verify_signature(&self, trusted_keys: &[SigningKey]) -> Result<(), Error> {
+        self.verify_signature_with(trusted_keys, &[])
+    }
+
+    /// Ensure this commit is signed by one of the OpenPGP keys in the keyring
+    /// which is valid at the time it was made, or one of the SSH allowed signers
     #[cfg(feature = "chrono")]
-    pub(crate) fn reset(&self, repo: &Repository) -> Result<(), Error> {
-        let commit_object = repo.repo.find_object(
-            git2::Oid::from_str(&self.commit_id).unwrap(),
-            Some(git2::ObjectType::Commit),
-        )?;
+    pub fn verify_signature_with_keyring(
+        &self,
+        keyring: &Keyring,
+        allowed_signers: &[AllowedSigner],
+    ) -> Result<(), Error> {
+        self.verify_signature_with(&keyring.keys_valid_at(self.time)?, allowed_signers)
+    }
 
-        // Reset the state of the repository to the latest commit
-        repo.repo
-            .reset(&commit_object, git2::ResetType::Hard, None)?;
+    /// Ensure this commit is signed by one of the given trusted OpenPGP keys or
+    /// SSH allowed signers (if none are given, it's an error)
+    pub fn verify_signature_with(
+        &self,
+        trusted_keys: &[SigningKey],
+        allowed_signers: &[AllowedSigner],
+    ) -> Result<(), Error> {
+        let (signature, signed_data) = match (&self.signature, &self.signed_data) {
+            (Some(signature), Some(signed_data)) => (signature, signed_data),
+            _ => fail!(
+                ErrorKind::Repo,
+                "no signature on commit {}: {} ({})",
+                self.commit_id,
+                self.summary,
+                self.author
+            ),
+        };
 
-        Ok(())
+        signature
+            .verify_trusted(trusted_keys, allowed_signers, signed_data)
+            .map_err(|e| {
+                err!(
+                    ErrorKind::Repo,
+                    "bad signature on commit {}: {}",
+                    self.commit_id,
+                    e
+                )
+            })
     }
 
-    /// Determine if the repository is fresh or stale (i.e. has it recently been committed to)
+    /// Reset the repository's state to match this commit, checking it out
+    /// according to the given `FetchOptions`
     #[cfg(feature = "chrono")]
-    pub(crate) fn ensure_fresh(&self) -> Result<(), Error> {
-        let fresh_after_date = Utc::now()
-            .checked_sub_signed(Duration::days(DAYS_UNTIL_STALE as i64))
-            .unwrap();
-
-        if self.time > fresh_after_date {
-            Ok(())
-        } else {
-            fail!(
-                ErrorKind::Repo,
-                "stale repo: not updated for {} days (last commit: {:?})",
-                DAYS_UNTIL_STALE,
-                self.time
-            )
-        }
+    pub(crate) fn reset(&self, repo: &Repository, options: &FetchOptions) -> Result<(), Error> {
+        backend::reset(&repo.repo, &self.commit_id, options)
+    }
+
+    /// Determine if the repository is fresh or stale (i.e. has it recently been
+    /// committed to), acting on the result according to the given `FreshnessPolicy`
+    #[cfg(feature = "chrono")]
+    pub(crate) fn ensure_fresh(&self, policy: &FreshnessPolicy) -> Result<Option<String>, Error> {
+        policy.ensure_fresh(self.time, "last commit")
     }
 }
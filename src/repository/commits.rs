@@ -0,0 +1,91 @@
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use super::{backend, Commit, Repository};
+use error::Error;
+
+/// Iterator over the commits in a repository, walking back from HEAD
+/// (most recent first)
+pub struct Commits<'a> {
+    /// Repository the commits are in
+    repo: &'a Repository,
+
+    /// Walk over the IDs of the remaining commits
+    commit_ids: backend::History<'a>,
+
+    /// Maximum number of remaining commits to return
+    limit: Option<usize>,
+
+    /// Stop at the first commit made before this time
+    #[cfg(feature = "chrono")]
+    since: Option<DateTime<Utc>>,
+}
+
+impl<'a> Commits<'a> {
+    /// Walk the history of the given repository
+    pub(crate) fn new(repo: &'a Repository) -> Result<Self, Error> {
+        let commit_ids = backend::history(&repo.repo)?;
+
+        Ok(Commits {
+            repo,
+            commit_ids,
+            limit: None,
+            #[cfg(feature = "chrono")]
+            since: None,
+        })
+    }
+
+    /// Return at most the given number of commits
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only return commits made at or after the given time
+    #[cfg(feature = "chrono")]
+    pub fn since(mut self, time: DateTime<Utc>) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Stop iterating
+    fn finish(&mut self) {
+        self.limit = Some(0);
+    }
+}
+
+impl<'a> Iterator for Commits<'a> {
+    type Item = Result<Commit, Error>;
+
+    fn next(&mut self) -> Option<Result<Commit, Error>> {
+        if self.limit == Some(0) {
+            return None;
+        }
+
+        let commit = match self
+            .commit_ids
+            .next()?
+            .and_then(|commit_id| backend::find_commit(&self.repo.repo, &commit_id))
+        {
+            Ok(commit) => commit,
+            Err(e) => {
+                self.finish();
+                return Some(Err(e));
+            }
+        };
+
+        #[cfg(feature = "chrono")]
+        {
+            if self.since.map(|since| commit.time < since).unwrap_or(false) {
+                self.finish();
+                return None;
+            }
+        }
+
+        if let Some(ref mut limit) = self.limit {
+            *limit -= 1;
+        }
+
+        Some(Ok(commit))
+    }
+}
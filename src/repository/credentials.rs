@@ -0,0 +1,116 @@
+#[cfg(feature = "chrono")]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::{fmt, path::PathBuf};
+
+/// Username GitHub expects access tokens to be sent with
+const GITHUB_TOKEN_USERNAME: &str = "x-access-token";
+
+/// Credentials used to authenticate to the remote repository (e.g. a private mirror).
+///
+/// When no credentials are given, authentication is attempted using the
+/// SSH agent and any configured Git credential helpers.
+#[derive(Clone, Eq, PartialEq)]
+pub enum Credentials {
+    /// Username and password (e.g. HTTP basic auth)
+    UserPass {
+        /// Username to authenticate as
+        username: String,
+
+        /// Password for the given user
+        password: String,
+    },
+
+    /// SSH private key stored on disk
+    SshKey {
+        /// Username to authenticate as
+        username: String,
+
+        /// Path to the public key (optional, derived from the private key if unset)
+        public_key: Option<PathBuf>,
+
+        /// Path to the private key
+        private_key: PathBuf,
+
+        /// Passphrase used to decrypt the private key (if it's encrypted)
+        passphrase: Option<String>,
+    },
+
+    /// Key held by the running SSH agent
+    SshAgent {
+        /// Username to authenticate as
+        username: String,
+    },
+
+    /// GitHub access token (e.g. a personal access token, or `GITHUB_TOKEN` in
+    /// GitHub Actions), sent with every HTTPS request so that authenticated
+    /// rate limits apply even when fetching public repositories
+    GitHubToken {
+        /// Access token
+        token: String,
+    },
+
+    /// Token sent as a bearer token in the `Authorization` header of every
+    /// HTTPS request
+    BearerToken {
+        /// Bearer token
+        token: String,
+    },
+}
+
+impl Credentials {
+    /// Get the username to authenticate as (empty for bearer tokens)
+    pub fn username(&self) -> &str {
+        match *self {
+            Credentials::UserPass { ref username, .. }
+            | Credentials::SshKey { ref username, .. }
+            | Credentials::SshAgent { ref username } => username,
+            Credentials::GitHubToken { .. } => GITHUB_TOKEN_USERNAME,
+            Credentials::BearerToken { .. } => "",
+        }
+    }
+
+    /// Get the HTTP `Authorization` header to send with every request (for
+    /// token-based credentials)
+    #[cfg(feature = "chrono")]
+    pub(crate) fn authorization_header(&self) -> Option<String> {
+        match *self {
+            Credentials::GitHubToken { ref token } => Some(format!(
+                "Authorization: Basic {}",
+                BASE64.encode(format!("{}:{}", GITHUB_TOKEN_USERNAME, token))
+            )),
+            Credentials::BearerToken { ref token } => {
+                Some(format!("Authorization: Bearer {}", token))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Avoid leaking passwords, passphrases, and tokens into logs
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Credentials::UserPass { ref username, .. } => f
+                .debug_struct("UserPass")
+                .field("username", username)
+                .finish(),
+            Credentials::SshKey {
+                ref username,
+                ref public_key,
+                ref private_key,
+                ..
+            } => f
+                .debug_struct("SshKey")
+                .field("username", username)
+                .field("public_key", public_key)
+                .field("private_key", private_key)
+                .finish(),
+            Credentials::SshAgent { ref username } => f
+                .debug_struct("SshAgent")
+                .field("username", username)
+                .finish(),
+            Credentials::GitHubToken { .. } => f.debug_struct("GitHubToken").finish(),
+            Credentials::BearerToken { .. } => f.debug_struct("BearerToken").finish(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Measuring the disk space used by a repository
+
+use std::{fs, path::Path};
+
+use error::Error;
+
+/// Total size (in bytes) of the files under the given directory
+pub(crate) fn dir_size(path: &Path) -> Result<u64, Error> {
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
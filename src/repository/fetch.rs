@@ -0,0 +1,193 @@
+//! Builder for the options used to fetch an advisory DB repository
+
+use std::{path::PathBuf, time::Duration};
+
+#[cfg(feature = "chrono")]
+use super::Keyring;
+use super::{
+    AllowedSigner, CacheLocation, Credentials, FetchOptions, FreshnessPolicy, PolicyAction,
+    ProgressCallback, RetryPolicy, TlsOptions, ADVISORY_DB_REPO_URL,
+};
+
+/// Where and how to fetch an advisory DB repository (see `Repository::fetch_with`).
+///
+/// By default, the crates.io advisory DB is fetched into `CacheLocation::CargoHome`
+/// using the default `FetchOptions`.
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// URL of the repository to fetch
+    pub(crate) url: String,
+
+    /// Where to fetch the repository into
+    pub(crate) location: CacheLocation,
+
+    /// How to fetch the repository
+    pub(crate) fetch_options: FetchOptions,
+}
+
+impl Options {
+    /// Create the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the repository from the given URL
+    pub fn url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Fetch the repository into the given path
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.location = CacheLocation::Custom(path.into());
+        self
+    }
+
+    /// Fetch the repository into the given cache location
+    pub fn location(mut self, location: CacheLocation) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Check the repository has been committed to recently according to the given policy
+    pub fn freshness(mut self, freshness: FreshnessPolicy) -> Self {
+        self.fetch_options.freshness = freshness;
+        self
+    }
+
+    /// Perform a shallow (i.e. depth 1) clone or fetch
+    pub fn shallow(mut self, shallow: bool) -> Self {
+        self.fetch_options.shallow = shallow;
+        self
+    }
+
+    /// Only check out the directories containing advisories (the whole
+    /// repository is still fetched)
+    pub fn sparse_checkout(mut self, sparse_checkout: bool) -> Self {
+        self.fetch_options.sparse_checkout = sparse_checkout;
+        self
+    }
+
+    /// Fetch through the proxy server with the given URL
+    pub fn proxy<S: Into<String>>(mut self, proxy: S) -> Self {
+        self.fetch_options.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Verify the remote repository's TLS certificate according to the given options
+    pub fn tls(mut self, tls: TlsOptions) -> Self {
+        self.fetch_options.tls = tls;
+        self
+    }
+
+    /// Apply the proxy and URL rewriting settings from the user's Git configuration
+    pub fn use_git_config(mut self, use_git_config: bool) -> Self {
+        self.fetch_options.use_git_config = use_git_config;
+        self
+    }
+
+    /// Authenticate with the given credentials
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.fetch_options.credentials = Some(credentials);
+        self
+    }
+
+    /// Check out the given branch name or full ref
+    pub fn reference<S: Into<String>>(mut self, reference: S) -> Self {
+        self.fetch_options.reference = Some(reference.into());
+        self
+    }
+
+    /// Trust signed, annotated tags matching the given pattern instead of signed commits
+    pub fn signed_tags<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.fetch_options.signed_tags = Some(pattern.into());
+        self
+    }
+
+    /// Check out the commit with the given ID instead of the latest one
+    pub fn commit<S: Into<String>>(mut self, commit_id: S) -> Self {
+        self.fetch_options.commit = Some(commit_id.into());
+        self
+    }
+
+    /// Use the existing checkout as-is instead of fetching anything
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.fetch_options.offline = offline;
+        self
+    }
+
+    /// Trust the given OpenPGP keys (e.g. a `Keyring` or `Vec<SigningKey>`)
+    /// to sign the latest commit (or tag)
+    #[cfg(feature = "chrono")]
+    pub fn trusted_keys<K: Into<Keyring>>(mut self, trusted_keys: K) -> Self {
+        self.fetch_options.trusted_keys = trusted_keys.into();
+        self
+    }
+
+    /// Trust the given SSH keys to sign the latest commit (or tag)
+    pub fn allowed_signers(mut self, allowed_signers: Vec<AllowedSigner>) -> Self {
+        self.fetch_options.allowed_signers = allowed_signers;
+        self
+    }
+
+    /// Report progress to the given callback
+    pub fn progress(mut self, progress: ProgressCallback) -> Self {
+        self.fetch_options.progress = Some(progress);
+        self
+    }
+
+    /// Wait at most the given time when connecting to the remote repository
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.fetch_options.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Allow the transfer from the remote repository to take at most the given time
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.fetch_options.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry fetches which fail with transient network errors according to the given policy
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.fetch_options.retry = retry;
+        self
+    }
+
+    /// Take the given action if the repository has been rolled back
+    pub fn rollback(mut self, rollback: PolicyAction) -> Self {
+        self.fetch_options.rollback = rollback;
+        self
+    }
+
+    /// Garbage collect the repository if it uses more than the given number of bytes
+    pub fn max_disk_usage(mut self, max_disk_usage: u64) -> Self {
+        self.fetch_options.max_disk_usage = Some(max_disk_usage);
+        self
+    }
+
+    /// Get the `FetchOptions` these options fetch the repository with
+    pub fn fetch_options(&self) -> &FetchOptions {
+        &self.fetch_options
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            url: ADVISORY_DB_REPO_URL.to_owned(),
+            location: CacheLocation::default(),
+            fetch_options: FetchOptions::default(),
+        }
+    }
+}
+
+impl From<FetchOptions> for Options {
+    /// Fetch the default repository with the given `FetchOptions`
+    fn from(fetch_options: FetchOptions) -> Self {
+        Self {
+            fetch_options,
+            ..Default::default()
+        }
+    }
+}
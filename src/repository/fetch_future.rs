@@ -0,0 +1,36 @@
+//! Fetching the advisory DB repository from async code
+
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::task::JoinHandle;
+
+use super::{FetchOptions, Repository};
+use error::{Error, ErrorKind};
+
+/// Future which resolves to a fetched `Repository` (see `Repository::fetch_async`)
+pub struct FetchFuture(JoinHandle<Result<Repository, Error>>);
+
+impl FetchFuture {
+    /// Start fetching the repository on tokio's thread pool for blocking tasks
+    pub(crate) fn spawn(url: String, path: PathBuf, options: FetchOptions) -> Self {
+        FetchFuture(tokio::task::spawn_blocking(move || {
+            Repository::fetch_with_options(&url, path, &options)
+        }))
+    }
+}
+
+impl Future for FetchFuture {
+    type Output = Result<Repository, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.0).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(err!(ErrorKind::Repo, "fetch failed: {}", e))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml;
+
+use super::PolicyAction;
+use error::{Error, ErrorKind};
+
+/// Name of the file (within the Git directory) where fetch info is kept
+const FETCH_INFO_FILE: &str = "rustsec-fetch.toml";
+
+/// Information about the last successful fetch of the repository, persisted
+/// alongside the checkout (e.g. so offline runs can report where it came from)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FetchInfo {
+    /// URL the repository was fetched from
+    pub url: String,
+
+    /// When the repository was fetched
+    pub fetched_at: DateTime<Utc>,
+
+    /// ID of the latest commit fetched
+    pub commit_id: String,
+
+    /// Time of the latest commit fetched
+    pub commit_time: DateTime<Utc>,
+}
+
+impl FetchInfo {
+    /// Load the fetch info persisted in the given Git directory (if any)
+    pub(crate) fn load(git_dir: &Path) -> Result<Option<Self>, Error> {
+        let path = fetch_info_path(git_dir);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(toml::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    /// Persist this fetch info in the given Git directory
+    pub(crate) fn save(&self, git_dir: &Path) -> Result<(), Error> {
+        let toml = toml::to_string(self)
+            .map_err(|e| err!(ErrorKind::Io, "couldn't serialize fetch info: {}", e))?;
+
+        fs::write(fetch_info_path(git_dir), toml)?;
+        Ok(())
+    }
+
+    /// Ensure the given commit, which has just been fetched, isn't older than
+    /// the one fetched last time (i.e. that the repository hasn't been rolled
+    /// back), acting on the result according to the given policy
+    pub(crate) fn check_rollback(
+        &self,
+        commit_id: &str,
+        commit_time: DateTime<Utc>,
+        action: PolicyAction,
+    ) -> Result<Option<String>, Error> {
+        if commit_time >= self.commit_time {
+            return Ok(None);
+        }
+
+        action.apply(format!(
+            "rollback detected: commit {} is older than previously fetched commit {}",
+            commit_id, self.commit_id
+        ))
+    }
+}
+
+/// Path to the fetch info file in the given Git directory
+fn fetch_info_path(git_dir: &Path) -> PathBuf {
+    git_dir.join(FETCH_INFO_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchInfo;
+    use chrono::{TimeZone, Utc};
+    use repository::PolicyAction;
+
+    #[test]
+    fn check_rollback_test() {
+        let info = FetchInfo {
+            url: "https://github.com/RustSec/advisory-db.git".to_owned(),
+            fetched_at: Utc.with_ymd_and_hms(2019, 7, 2, 0, 0, 0).unwrap(),
+            commit_id: "b".to_owned(),
+            commit_time: Utc.with_ymd_and_hms(2019, 7, 1, 0, 0, 0).unwrap(),
+        };
+
+        let newer = Utc.with_ymd_and_hms(2019, 7, 3, 0, 0, 0).unwrap();
+        let older = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+
+        for &action in &[PolicyAction::Error, PolicyAction::Warn] {
+            assert_eq!(info.check_rollback("c", newer, action).unwrap(), None);
+            assert_eq!(
+                info.check_rollback("b", info.commit_time, action).unwrap(),
+                None
+            );
+        }
+
+        assert!(info
+            .check_rollback("a", older, PolicyAction::Error)
+            .is_err());
+        assert_eq!(
+            info.check_rollback("a", older, PolicyAction::Warn).unwrap(),
+            Some(
+                "rollback detected: commit a is older than previously fetched commit b".to_owned()
+            )
+        );
+        assert_eq!(
+            info.check_rollback("a", older, PolicyAction::Ignore)
+                .unwrap(),
+            None
+        );
+    }
+}
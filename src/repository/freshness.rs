@@ -0,0 +1,76 @@
+#[cfg(feature = "chrono")]
+use chrono::{self, DateTime, Utc};
+use std::time::Duration;
+
+use super::{PolicyAction, DAYS_UNTIL_STALE};
+#[cfg(feature = "chrono")]
+use error::{Error, ErrorKind};
+
+/// Number of seconds in a day
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// How to check that the repository has been committed to recently
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreshnessPolicy {
+    /// Maximum age of the latest commit before the repository is considered stale
+    pub max_age: Duration,
+
+    /// What to do when the repository is stale
+    pub action: PolicyAction,
+}
+
+impl FreshnessPolicy {
+    /// Policy which never checks whether the repository is stale
+    pub fn ignore() -> Self {
+        Self {
+            action: PolicyAction::Ignore,
+            ..Default::default()
+        }
+    }
+
+    /// Determine if the repository is fresh or stale given the time it was
+    /// last updated (e.g. the time of the latest commit, described by `what`),
+    /// acting on the result according to this policy (and returning a warning
+    /// if it's stale and the policy is to warn)
+    #[cfg(feature = "chrono")]
+    pub(crate) fn ensure_fresh(
+        &self,
+        updated_at: DateTime<Utc>,
+        what: &str,
+    ) -> Result<Option<String>, Error> {
+        if self.action == PolicyAction::Ignore {
+            return Ok(None);
+        }
+
+        let max_age = chrono::Duration::from_std(self.max_age)
+            .map_err(|e| err!(ErrorKind::BadParam, "invalid max age: {}", e))?;
+
+        let fresh_after_date = Utc::now().checked_sub_signed(max_age).ok_or_else(|| {
+            err!(
+                ErrorKind::BadParam,
+                "invalid max age: {:?}",
+                self.max_age
+            )
+        })?;
+
+        if updated_at > fresh_after_date {
+            return Ok(None);
+        }
+
+        self.action.apply(format!(
+            "stale repo: not updated for {} days ({}: {:?})",
+            max_age.num_days(),
+            what,
+            updated_at
+        ))
+    }
+}
+
+impl Default for FreshnessPolicy {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(DAYS_UNTIL_STALE as u64 * SECONDS_PER_DAY),
+            action: PolicyAction::Error,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Settings from the user's Git configuration which apply to fetches
+
+use super::backend;
+use error::Error;
+
+/// Settings read from the user's Git configuration (e.g. `~/.gitconfig`)
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct GitConfig {
+    /// `url.<base>.insteadOf` rules, as `(prefix, base)` pairs
+    pub instead_of: Vec<(String, String)>,
+
+    /// Proxy configured with `http.proxy` (if any)
+    pub proxy: Option<String>,
+}
+
+impl GitConfig {
+    /// Load the user's global and system Git configuration
+    pub fn load() -> Result<Self, Error> {
+        backend::user_config()
+    }
+
+    /// Rewrite the given URL using the longest matching `insteadOf` prefix (if any)
+    pub fn rewrite_url(&self, url: &str) -> String {
+        self.instead_of
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, base)| format!("{}{}", base, &url[prefix.len()..]))
+            .unwrap_or_else(|| url.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitConfig;
+
+    #[test]
+    fn rewrite_url_test() {
+        let config = GitConfig {
+            instead_of: vec![
+                ("https://github.com/".to_owned(), "https://mirror.example.com/".to_owned()),
+                (
+                    "https://github.com/RustSec/".to_owned(),
+                    "ssh://git@git.example.com/rustsec/".to_owned(),
+                ),
+            ],
+            proxy: None,
+        };
+
+        assert_eq!(
+            config.rewrite_url("https://github.com/RustSec/advisory-db.git"),
+            "ssh://git@git.example.com/rustsec/advisory-db.git"
+        );
+        assert_eq!(
+            config.rewrite_url("https://github.com/example/advisory-db.git"),
+            "https://mirror.example.com/example/advisory-db.git"
+        );
+        assert_eq!(
+            config.rewrite_url("https://gitlab.com/example/advisory-db.git"),
+            "https://gitlab.com/example/advisory-db.git"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Sets of trusted OpenPGP keys, each valid for a window of time
+
+use chrono::{DateTime, Utc};
+use std::{fs, path::Path};
+
+use super::signing_key::SigningKey;
+use error::{Error, ErrorKind};
+
+/// OpenPGP keys trusted to sign commits to the repository, each of which may
+/// only be trusted for commits made within a window of time (e.g. until it's
+/// rotated). If it's empty (and there are no SSH allowed signers), no
+/// signatures are trusted.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Keyring {
+    /// Trusted keys and their validity windows
+    entries: Vec<Entry>,
+}
+
+/// Key in a `Keyring`
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Entry {
+    /// The key itself
+    key: SigningKey,
+
+    /// Earliest time a commit signed by the key can be made (if limited)
+    valid_from: Option<DateTime<Utc>>,
+
+    /// Latest time a commit signed by the key can be made (if limited)
+    valid_until: Option<DateTime<Utc>>,
+}
+
+impl Keyring {
+    /// Create an empty keyring
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a keyring containing the keys in `DEFAULT_SIGNING_KEYS`
+    pub fn default_keys() -> Self {
+        SigningKey::default_keys().into()
+    }
+
+    /// Trust the given key for commits made between `valid_from` and
+    /// `valid_until` (either of which may be unlimited)
+    pub fn add(
+        &mut self,
+        key: SigningKey,
+        valid_from: Option<DateTime<Utc>>,
+        valid_until: Option<DateTime<Utc>>,
+    ) {
+        self.entries.push(Entry {
+            key,
+            valid_from,
+            valid_until,
+        });
+    }
+
+    /// Trust all of the keys in the given ASCII-armored OpenPGP keyring for
+    /// commits made between `valid_from` and `valid_until`
+    pub fn add_armored(
+        &mut self,
+        armored: &str,
+        valid_from: Option<DateTime<Utc>>,
+        valid_until: Option<DateTime<Utc>>,
+    ) -> Result<(), Error> {
+        for key in SigningKey::from_armored_keyring(armored)? {
+            self.add(key, valid_from, valid_until);
+        }
+
+        Ok(())
+    }
+
+    /// Trust all of the keys in the ASCII-armored OpenPGP keyring file at the
+    /// given path for commits made between `valid_from` and `valid_until`
+    pub fn add_file<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        valid_from: Option<DateTime<Utc>>,
+        valid_until: Option<DateTime<Utc>>,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+
+        let armored = fs::read_to_string(path).map_err(|e| {
+            err!(
+                ErrorKind::Io,
+                "couldn't read keyring {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+
+        self.add_armored(&armored, valid_from, valid_until)
+    }
+
+    /// Does this keyring contain no keys?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over all of the keys in this keyring (regardless of their validity)
+    pub fn keys(&self) -> impl Iterator<Item = &SigningKey> {
+        self.entries.iter().map(|entry| &entry.key)
+    }
+
+    /// Get the keys trusted for commits made at the given time. If this
+    /// keyring isn't empty but none of its keys are valid then, it's an error.
+    pub fn keys_valid_at(&self, time: DateTime<Utc>) -> Result<Vec<SigningKey>, Error> {
+        let keys = self
+            .entries
+            .iter()
+            .filter(|entry| entry.valid_from.map(|from| time >= from).unwrap_or(true))
+            .filter(|entry| entry.valid_until.map(|until| time <= until).unwrap_or(true))
+            .map(|entry| entry.key.clone())
+            .collect::<Vec<_>>();
+
+        if keys.is_empty() && !self.is_empty() {
+            fail!(ErrorKind::Repo, "no trusted signing keys valid at {}", time);
+        }
+
+        Ok(keys)
+    }
+}
+
+impl From<Vec<SigningKey>> for Keyring {
+    /// Trust the given keys without any limits on their validity
+    fn from(keys: Vec<SigningKey>) -> Self {
+        let mut keyring = Keyring::new();
+
+        for key in keys {
+            keyring.add(key, None, None);
+        }
+
+        keyring
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use error::{Error, ErrorKind};
+
+/// Suffix of the lock file kept alongside a directory
+const LOCK_FILE_SUFFIX: &str = ".lock";
+
+/// Advisory lock which serializes updates to a directory (e.g. the advisory DB
+/// repository) across processes. The lock is released when this is dropped.
+pub(crate) struct DirectoryLock {
+    /// Open lock file (the lock is held for as long as it's open)
+    _file: File,
+}
+
+impl DirectoryLock {
+    /// Acquire the lock for the given directory, blocking until any other
+    /// process holding it releases it (using `File::lock`, which is why this
+    /// crate requires Rust 1.89)
+    pub fn acquire(path: &Path) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_file_path(path)?)?;
+
+        file.lock()?;
+        Ok(DirectoryLock { _file: file })
+    }
+}
+
+/// Path to the lock file for the given directory (a sibling of it, since the
+/// directory itself may not exist yet)
+fn lock_file_path(path: &Path) -> Result<PathBuf, Error> {
+    let mut lock_name = path
+        .file_name()
+        .ok_or_else(|| err!(ErrorKind::BadParam, "invalid directory: {}", path.display()))?
+        .to_owned();
+
+    lock_name.push(LOCK_FILE_SUFFIX);
+    Ok(path.with_file_name(lock_name))
+}
@@ -1,40 +1,261 @@
 //! Git repository handling for the RustSec advisory DB
+//!
+//! Git operations are performed by one of two backends selected via Cargo
+//! features: `git2` (libgit2, the default) or `gix` (pure Rust gitoxide).
+//! If both are enabled, `git2` is used.
 
-use git2;
-use std::{env, fs, path::PathBuf, vec};
-
+#[cfg(feature = "chrono")]
+use chrono::Utc;
+#[cfg(feature = "chrono")]
+use std::borrow::Cow;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+    vec,
+};
+
+use advisory::Collection;
 use error::{Error, ErrorKind};
 
+mod allowed_signer;
+#[cfg(all(feature = "git2", feature = "chrono"))]
 mod authentication;
+mod backend;
+mod changes;
 mod commit;
+mod commits;
+mod credentials;
+mod disk_usage;
+pub mod fetch;
+#[cfg(all(feature = "tokio", feature = "chrono"))]
+mod fetch_future;
+#[cfg(feature = "chrono")]
+mod fetch_info;
 mod file;
+mod freshness;
+#[cfg(feature = "chrono")]
+mod git_config;
+#[cfg(feature = "chrono")]
+mod keyring;
+mod lock;
+mod paths;
+mod policy;
+mod progress;
+#[cfg(feature = "chrono")]
+mod refs;
+mod retry;
 mod signature;
+mod signing_key;
+#[cfg(feature = "chrono")]
+mod tag;
+mod tls;
+mod vendored;
 
+pub use self::allowed_signer::AllowedSigner;
+pub use self::changes::AdvisoryChanges;
 pub use self::commit::Commit;
+pub use self::commits::Commits;
+pub use self::credentials::Credentials;
+#[cfg(all(feature = "tokio", feature = "chrono"))]
+pub use self::fetch_future::FetchFuture;
+#[cfg(feature = "chrono")]
+pub use self::fetch_info::FetchInfo;
 pub(crate) use self::file::RepoFile;
-pub use self::signature::Signature;
+pub use self::freshness::FreshnessPolicy;
+#[cfg(feature = "chrono")]
+pub use self::keyring::Keyring;
+pub(crate) use self::lock::DirectoryLock;
+pub use self::paths::CacheLocation;
+pub use self::policy::PolicyAction;
+pub use self::progress::{Progress, ProgressCallback};
+pub use self::retry::RetryPolicy;
+pub use self::signature::{Signature, SignatureKind};
+pub use self::signing_key::{SigningKey, DEFAULT_SIGNING_KEYS};
+#[cfg(feature = "chrono")]
+pub use self::tag::Tag;
+pub use self::tls::TlsOptions;
+pub use self::vendored::{VendoredDirectory, LAST_UPDATED_FILE};
 
-use self::authentication::with_authentication;
+#[cfg(feature = "chrono")]
+use self::git_config::GitConfig;
+#[cfg(feature = "chrono")]
+use self::refs::FetchRefs;
 
 /// Location of the RustSec advisory database for crates.io
 pub const ADVISORY_DB_REPO_URL: &str = "https://github.com/RustSec/advisory-db.git";
 
-/// Number of days after which the repo will be considered stale
+/// Number of days after which the repo will be considered stale by default
 pub const DAYS_UNTIL_STALE: usize = 90;
 
-/// Directory under ~/.cargo where the advisory-db repo will be kept
-const ADVISORY_DB_DIRECTORY: &str = "advisory-db";
-
 /// Directory within a repository where crate advisories are stored
 const CRATE_ADVISORY_DIRECTORY: &str = "crates";
 
-/// Ref for master in the local repository
+/// Directories checked out when performing a sparse checkout
 #[cfg(feature = "chrono")]
-const LOCAL_MASTER_REF: &str = "refs/heads/master";
+pub(crate) const SPARSE_CHECKOUT_DIRECTORIES: &[&str] = &[CRATE_ADVISORY_DIRECTORY, "rust"];
+
+/// Environment variables consulted (in order) for a proxy URL
+const PROXY_ENV_VARS: &[&str] = &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"];
+
+/// Options which control how the advisory database repository is fetched
+#[derive(Clone, Debug)]
+pub struct FetchOptions {
+    /// How to check the repository has been committed to recently
+    pub freshness: FreshnessPolicy,
+
+    /// Perform a shallow (i.e. depth 1) clone or fetch, since only HEAD is used
+    pub shallow: bool,
+
+    /// Only check out the directories containing advisories (i.e. `crates/`
+    /// and `rust/`) into the working directory. This only affects the
+    /// checkout: every object is still fetched, since neither backend
+    /// supports partial clones.
+    pub sparse_checkout: bool,
+
+    /// URL of a proxy server to fetch through (e.g. `http://proxy.example.com:8080`).
+    /// If unset, the `HTTPS_PROXY` and `ALL_PROXY` environment variables are used.
+    pub proxy: Option<String>,
+
+    /// How to verify the remote repository's TLS certificate (unsupported by
+    /// the `gix` backend, which uses the system's TLS configuration)
+    pub tls: TlsOptions,
+
+    /// Apply the `http.proxy` and `url.<base>.insteadOf` settings from the
+    /// user's Git configuration (e.g. `~/.gitconfig`). An explicitly
+    /// configured `proxy` still takes precedence.
+    pub use_git_config: bool,
+
+    /// Credentials to authenticate with (e.g. for private mirrors). If unset,
+    /// credentials are discovered from the SSH agent and Git credential helpers.
+    pub credentials: Option<Credentials>,
+
+    /// Branch name (e.g. `main`) or full ref (e.g. `refs/tags/v1`) to check
+    /// out instead of the `master` branch
+    pub reference: Option<String>,
+
+    /// Trust signed, annotated tags matching this pattern (e.g. `release-*`)
+    /// instead of signed commits: the most recent matching tag is verified
+    /// and its target checked out (overriding `reference`)
+    pub signed_tags: Option<String>,
+
+    /// ID of a commit to check out instead of the latest one, for a reproducible
+    /// database state. It must be reachable from the fetched ref, and isn't
+    /// subject to the `freshness` or `rollback` checks.
+    pub commit: Option<String>,
+
+    /// Don't fetch anything: use the existing checkout at the given path
+    /// as-is (e.g. a pre-populated cache on a machine without network access)
+    pub offline: bool,
+
+    /// OpenPGP keys trusted to sign the latest commit, or tag if `signed_tags`
+    /// is set (see `DEFAULT_SIGNING_KEYS`), at the time it was made. If empty
+    /// (and so are the `allowed_signers`), no signature is trusted.
+    #[cfg(feature = "chrono")]
+    pub trusted_keys: Keyring,
+
+    /// SSH keys trusted to sign the latest commit (or tag), from an allowed
+    /// signers file (see `AllowedSigner::parse_file`). SSH signatures are only
+    /// accepted from these.
+    pub allowed_signers: Vec<AllowedSigner>,
+
+    /// Callback to report progress to while fetching and checking out the repository
+    pub progress: Option<ProgressCallback>,
+
+    /// Maximum time to wait when connecting to the remote repository. The
+    /// `git2` backend waits until the remote first responds (e.g. with its
+    /// TLS certificate), and the `gix` backend only applies it to HTTP(S).
+    pub connect_timeout: Option<Duration>,
+
+    /// Maximum time the transfer from the remote repository may take. With the
+    /// `git2` backend, a fetch which times out aborts the next time libgit2
+    /// reports back on it (or its connection fails), and the timeout is only
+    /// returned once it has stopped.
+    pub timeout: Option<Duration>,
+
+    /// How to retry fetches which fail with transient network errors
+    pub retry: RetryPolicy,
+
+    /// What to do if the latest commit is older than one previously fetched
+    /// into the same path (i.e. the repository has been rolled back)
+    pub rollback: PolicyAction,
+
+    /// Maximum size (in bytes) of the repository's Git directory: if a fetch
+    /// leaves it larger than this, it's garbage collected (see `Repository::gc`),
+    /// with a warning if it's still too large. Unsupported by the `gix` backend,
+    /// whose fetches fail with `ErrorKind::BadParam` if they'd need collecting.
+    pub max_disk_usage: Option<u64>,
+}
 
-/// Ref for master in the remote repository
-#[cfg(feature = "chrono")]
-const REMOTE_MASTER_REF: &str = "refs/remotes/origin/master";
+impl FetchOptions {
+    /// Is the given path (relative to the root of the repository) checked out
+    /// with these options?
+    #[cfg(all(feature = "chrono", feature = "gix", not(feature = "git2")))]
+    pub(crate) fn is_checked_out(&self, path: &Path) -> bool {
+        !self.sparse_checkout
+            || SPARSE_CHECKOUT_DIRECTORIES
+                .iter()
+                .any(|dir| path.starts_with(dir))
+    }
+
+    /// Refs to use when fetching the configured reference
+    #[cfg(feature = "chrono")]
+    pub(crate) fn refs(&self) -> FetchRefs {
+        let mut refs = if let Some(ref pattern) = self.signed_tags {
+            FetchRefs::tags(pattern)
+        } else {
+            match self.reference {
+                Some(ref reference) => FetchRefs::new(reference),
+                None => FetchRefs::default(),
+            }
+        };
+
+        // Pinned commits are checked out with a detached HEAD
+        if self.commit.is_some() {
+            refs.branch = None;
+        }
+
+        refs
+    }
+
+    /// Get the URL of the proxy to fetch through (if any), either as
+    /// explicitly configured or taken from the environment
+    pub fn proxy_url(&self) -> Option<String> {
+        self.proxy.clone().or_else(|| {
+            PROXY_ENV_VARS
+                .iter()
+                .filter_map(|var| env::var(var).ok())
+                .find(|url| !url.is_empty())
+        })
+    }
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            freshness: FreshnessPolicy::default(),
+            shallow: false,
+            sparse_checkout: false,
+            proxy: None,
+            tls: TlsOptions::default(),
+            use_git_config: false,
+            credentials: None,
+            reference: None,
+            signed_tags: None,
+            commit: None,
+            offline: false,
+            #[cfg(feature = "chrono")]
+            trusted_keys: Keyring::default_keys(),
+            allowed_signers: vec![],
+            progress: None,
+            connect_timeout: None,
+            timeout: None,
+            retry: RetryPolicy::default(),
+            rollback: PolicyAction::Error,
+            max_disk_usage: None,
+        }
+    }
+}
 
 /// Git repository for a Rust advisory DB
 pub struct Repository {
@@ -42,23 +263,54 @@ pub struct Repository {
     path: PathBuf,
 
     /// Repository object
-    repo: git2::Repository,
+    repo: backend::Repo,
+
+    /// URL the repository was fetched from (if it was fetched)
+    url: Option<String>,
+
+    /// Information about the last successful fetch (if any)
+    #[cfg(feature = "chrono")]
+    fetch_info: Option<FetchInfo>,
+
+    /// Commit checked out as requested by `FetchOptions::commit` (if any)
+    #[cfg(feature = "chrono")]
+    pinned_commit: Option<Commit>,
+
+    /// Warnings from checks on the repository when it was fetched
+    #[cfg(feature = "chrono")]
+    warnings: Vec<String>,
 }
 
 impl Repository {
     /// Location of the default `advisory-db` repository for crates.io
+    /// (i.e. `advisory-db` under `$CARGO_HOME`, or `~/.cargo` if it's unset)
     pub fn default_path() -> PathBuf {
-        if let Some(path) = env::var_os("CARGO_HOME") {
-            PathBuf::from(path).join(ADVISORY_DB_DIRECTORY)
-        } else {
-            panic!("Can't locate CARGO_HOME!");
-        }
+        CacheLocation::default()
+            .repo_path()
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
     /// Fetch the default repository
     #[cfg(feature = "chrono")]
     pub fn fetch_default_repo() -> Result<Self, Error> {
-        Self::fetch(ADVISORY_DB_REPO_URL, Repository::default_path(), true)
+        Self::fetch_default_repo_at(None)
+    }
+
+    /// Fetch the default repository into the given path, or the default path if `None`
+    #[cfg(feature = "chrono")]
+    pub fn fetch_default_repo_at(path: Option<PathBuf>) -> Result<Self, Error> {
+        Self::fetch_default_repo_in(&CacheLocation::from(path))
+    }
+
+    /// Fetch the default repository into the given cache location
+    #[cfg(feature = "chrono")]
+    pub fn fetch_default_repo_in(location: &CacheLocation) -> Result<Self, Error> {
+        Self::fetch(ADVISORY_DB_REPO_URL, location.repo_path()?, true)
+    }
+
+    /// Open the default repository at the given path, or the default path if `None`
+    pub fn open_default(path: Option<PathBuf>) -> Result<Self, Error> {
+        Self::open(CacheLocation::from(path).repo_path()?)
     }
 
     /// Create a new `Repository` with the given URL and path
@@ -68,11 +320,45 @@ impl Repository {
         into_path: P,
         ensure_fresh: bool,
     ) -> Result<Self, Error> {
-        if !url.starts_with("https://") {
+        let freshness = if ensure_fresh {
+            FreshnessPolicy::default()
+        } else {
+            FreshnessPolicy::ignore()
+        };
+
+        let options = FetchOptions {
+            freshness,
+            ..Default::default()
+        };
+
+        Self::fetch_with_options(url, into_path, &options)
+    }
+
+    /// Create a new `Repository` with the given URL and path, using the given `FetchOptions`
+    #[cfg(feature = "chrono")]
+    pub fn fetch_with_options<P: Into<PathBuf>>(
+        url: &str,
+        into_path: P,
+        options: &FetchOptions,
+    ) -> Result<Self, Error> {
+        let (fetch_url, options) = if options.use_git_config {
+            let git_config = GitConfig::load()?;
+            let mut options = options.clone();
+
+            if options.proxy.is_none() {
+                options.proxy = git_config.proxy.clone();
+            }
+
+            (git_config.rewrite_url(url), Cow::Owned(options))
+        } else {
+            (url.to_owned(), Cow::Borrowed(options))
+        };
+
+        if !fetch_url.starts_with("https://") && !fetch_url.starts_with("ssh://") {
             fail!(
                 ErrorKind::BadParam,
-                "expected {} to start with https://",
-                url
+                "expected {} to start with https:// or ssh://",
+                fetch_url
             );
         }
 
@@ -86,79 +372,231 @@ impl Repository {
             fail!(ErrorKind::BadParam, "invalid directory: {}", path.display())
         }
 
-        let git_config = git2::Config::new()?;
+        // Serialize concurrent fetches into the same directory
+        let _lock = DirectoryLock::acquire(&path)?;
 
-        with_authentication(url, &git_config, |f| {
-            let mut callbacks = git2::RemoteCallbacks::new();
-            callbacks.credentials(f);
+        let mut repo = if options.offline {
+            Self::open(path)?
+        } else {
+            options
+                .retry
+                .run(|| backend::fetch(&fetch_url, &path, &options))?;
+
+            let repo = Self::open(path)?;
+            repo.latest_commit()?.reset(&repo, &options)?;
+            repo
+        };
+
+        if options.tls.danger_accept_invalid_certs && !options.offline {
+            repo.warnings.push(format!(
+                "TLS certificate verification is disabled for {}",
+                fetch_url
+            ));
+        }
 
-            let mut fetch_opts = git2::FetchOptions::new();
-            fetch_opts.remote_callbacks(callbacks);
+        let latest_commit = repo.latest_commit()?;
 
-            if path.exists() {
-                let repo = git2::Repository::open(&path)?;
-                let refspec = LOCAL_MASTER_REF.to_owned() + ":" + REMOTE_MASTER_REF;
+        if let Some(ref commit_id) = options.commit {
+            if latest_commit.commit_id != *commit_id {
+                fail!(
+                    ErrorKind::Repo,
+                    "expected HEAD to be at pinned commit {}, but it's at {}",
+                    commit_id,
+                    latest_commit.commit_id
+                );
+            }
+        }
 
-                // Fetch remote packfiles and update tips
-                let mut remote = repo.remote_anonymous(url)?;
-                remote.fetch(&[refspec.as_str()], Some(&mut fetch_opts), None)?;
+        match options.signed_tags {
+            // The checked out commit must be the target of a signed tag
+            Some(ref pattern) => {
+                let tag = repo.latest_tag(pattern)?;
+                tag.verify_signature_with_keyring(
+                    &options.trusted_keys,
+                    &options.allowed_signers,
+                )?;
 
-                // Get the current remote tip (as an updated local reference)
-                let remote_master_ref = repo.find_reference(REMOTE_MASTER_REF)?;
-                let remote_target = remote_master_ref.target().unwrap();
+                if tag.target_id != latest_commit.commit_id {
+                    fail!(
+                        ErrorKind::Repo,
+                        "expected HEAD to be at tag {} ({}), but it's at {}",
+                        tag.name,
+                        tag.target_id,
+                        latest_commit.commit_id
+                    );
+                }
+            }
+            // Any commits we fetch must always be signed by a trusted key
+            None => latest_commit
+                .verify_signature_with_keyring(&options.trusted_keys, &options.allowed_signers)?,
+        }
 
-                // Set the local master ref to match the remote
-                let mut local_master_ref = repo.find_reference(LOCAL_MASTER_REF)?;
-                local_master_ref.set_target(
-                    remote_target,
-                    &format!(
-                        "rustsec: moving master to {}: {}",
-                        REMOTE_MASTER_REF, &remote_target
-                    ),
+        // Pinned commits are expected to be old, so skip checking their age
+        if options.commit.is_none() {
+            // Ensure we haven't been served an older commit than previously fetched
+            if let Some(ref previous) = repo.fetch_info {
+                let warning = previous.check_rollback(
+                    &latest_commit.commit_id,
+                    latest_commit.time,
+                    options.rollback,
                 )?;
-            } else {
-                git2::build::RepoBuilder::new()
-                    .fetch_options(fetch_opts)
-                    .clone(url, &path)?;
+
+                repo.warnings.extend(warning);
             }
 
-            Ok(())
-        })?;
+            // Ensure that the upstream repository hasn't gone stale
+            let warning = latest_commit.ensure_fresh(&options.freshness)?;
+            repo.warnings.extend(warning);
+        }
 
-        let repo = Self::open(path)?;
-        let latest_commit = repo.latest_commit()?;
-        latest_commit.reset(&repo)?;
+        let is_newer = repo
+            .fetch_info
+            .as_ref()
+            .map(|previous| latest_commit.time >= previous.commit_time)
+            .unwrap_or(true);
+
+        if !options.offline && is_newer {
+            let fetch_info = FetchInfo {
+                url: url.to_owned(),
+                fetched_at: Utc::now(),
+                commit_id: latest_commit.commit_id.clone(),
+                commit_time: latest_commit.time,
+            };
+
+            fetch_info.save(backend::git_dir(&repo.repo))?;
+            repo.fetch_info = Some(fetch_info);
+        }
 
-        // Any commits we fetch should always be signed
-        // TODO: verify signatures against GitHub's public key
-        if latest_commit.signature.is_none() {
-            fail!(
-                ErrorKind::Repo,
-                "no signature on commit {}: {} ({})",
-                latest_commit.commit_id,
-                latest_commit.summary,
-                latest_commit.author
-            );
+        if options.commit.is_some() {
+            repo.pinned_commit = Some(latest_commit);
         }
 
-        // Ensure that the upstream repository hasn't gone stale
-        if ensure_fresh {
-            latest_commit.ensure_fresh()?;
+        if let Some(max_disk_usage) = options.max_disk_usage {
+            if !options.offline && repo.disk_usage()? > max_disk_usage {
+                // The directory is already locked, so `Repository::gc` can't be used
+                backend::gc(&repo.repo)?;
+
+                let disk_usage = repo.disk_usage()?;
+
+                if disk_usage > max_disk_usage {
+                    repo.warnings.push(format!(
+                        "{} uses {} bytes after garbage collection (limit: {} bytes)",
+                        repo.path.display(),
+                        disk_usage,
+                        max_disk_usage
+                    ));
+                }
+            }
         }
 
+        repo.url = Some(url.to_owned());
         Ok(repo)
     }
 
+    /// Create a new `Repository` from the URL and into the location given by
+    /// the `fetch::Options`, fetching it as they configure
+    #[cfg(feature = "chrono")]
+    pub fn fetch_with(options: &fetch::Options) -> Result<Self, Error> {
+        Self::fetch_with_options(
+            &options.url,
+            options.location.repo_path()?,
+            &options.fetch_options,
+        )
+    }
+
+    /// Create a new `Repository` with the given URL and path, using the given
+    /// `FetchOptions`, without blocking the async runtime. The fetch runs on
+    /// tokio's thread pool for blocking tasks, so this must be called from
+    /// within a tokio runtime.
+    #[cfg(all(feature = "tokio", feature = "chrono"))]
+    pub fn fetch_async<P: Into<PathBuf>>(
+        url: &str,
+        into_path: P,
+        options: &FetchOptions,
+    ) -> FetchFuture {
+        FetchFuture::spawn(url.to_owned(), into_path.into(), options.clone())
+    }
+
+    /// Create a new `Repository` at the given path from the first of the given
+    /// URLs (e.g. the upstream repository followed by its mirrors) which can be
+    /// successfully fetched, using the given `FetchOptions`
+    #[cfg(feature = "chrono")]
+    pub fn fetch_from_mirrors<P: Into<PathBuf>>(
+        urls: &[&str],
+        into_path: P,
+        options: &FetchOptions,
+    ) -> Result<Self, Error> {
+        let path = into_path.into();
+        let mut last_error = None;
+
+        for url in urls {
+            match Self::fetch_with_options(url, path.clone(), options) {
+                Ok(repo) => return Ok(repo),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| err!(ErrorKind::BadParam, "no repository URLs given")))
+    }
+
     /// Open a repository at the given path
     pub fn open<P: Into<PathBuf>>(into_path: P) -> Result<Self, Error> {
         let path = into_path.into();
-        let repo = git2::Repository::open(&path)?;
+        let repo = backend::open(&path)?;
+
+        #[cfg(feature = "chrono")]
+        let fetch_info = FetchInfo::load(backend::git_dir(&repo))?;
+
+        Ok(Repository {
+            path,
+            repo,
+            url: None,
+            #[cfg(feature = "chrono")]
+            fetch_info,
+            #[cfg(feature = "chrono")]
+            pinned_commit: None,
+            #[cfg(feature = "chrono")]
+            warnings: vec![],
+        })
+    }
 
-        // Ensure the repo is in a clean state
-        match repo.state() {
-            git2::RepositoryState::Clean => Ok(Repository { path, repo }),
-            state => fail!(ErrorKind::Repo, "bad repository state: {:?}", state),
-        }
+    /// Warnings from checks on the repository when it was fetched, e.g. that it's
+    /// stale when `FreshnessPolicy::action` is `PolicyAction::Warn`, that TLS
+    /// certificates weren't verified, or that it's larger than `max_disk_usage`
+    #[cfg(feature = "chrono")]
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Information about the last successful fetch of this repository (if any),
+    /// e.g. where it was fetched from and when
+    #[cfg(feature = "chrono")]
+    pub fn fetch_info(&self) -> Option<&FetchInfo> {
+        self.fetch_info.as_ref()
+    }
+
+    /// Commit checked out as requested by `FetchOptions::commit`, if the
+    /// repository was fetched with one
+    #[cfg(feature = "chrono")]
+    pub fn pinned_commit(&self) -> Option<&Commit> {
+        self.pinned_commit.as_ref()
+    }
+
+    /// Path to the repository's working tree
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// URL this repository was fetched from (if it was fetched, as opposed to opened)
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Ensure the repository has been committed to recently, according to the
+    /// given policy, returning a warning if it's stale and the policy is to warn
+    #[cfg(feature = "chrono")]
+    pub fn ensure_fresh(&self, policy: &FreshnessPolicy) -> Result<Option<String>, Error> {
+        self.latest_commit()?.ensure_fresh(policy)
     }
 
     /// Get information about the latest commit to the repo
@@ -166,24 +604,138 @@ impl Repository {
         Commit::from_repo_head(self)
     }
 
-    /// Iterate over all of the crate advisories in this repo
+    /// Size (in bytes) of the files in the repository's Git directory,
+    /// i.e. the disk space used by the repository besides its checkout
+    pub fn disk_usage(&self) -> Result<u64, Error> {
+        disk_usage::dir_size(backend::git_dir(&self.repo))
+    }
+
+    /// Garbage collect the repository: repack the objects reachable from its
+    /// refs into a single packfile, removing the packfiles and loose objects
+    /// which existed beforehand. This waits for any fetch into the repository
+    /// to finish first. The `gix` backend doesn't support it, and returns an
+    /// `ErrorKind::BadParam` error.
+    pub fn gc(&self) -> Result<(), Error> {
+        let _lock = DirectoryLock::acquire(&self.path)?;
+        backend::gc(&self.repo)
+    }
+
+    /// Get information about the most recent annotated tag whose name matches
+    /// the given pattern (e.g. `release-*`)
+    #[cfg(feature = "chrono")]
+    pub fn latest_tag(&self, pattern: &str) -> Result<Tag, Error> {
+        Tag::latest(&self.repo, pattern)
+    }
+
+    /// Iterate over the commits in this repo, walking back from HEAD
+    pub fn commits(&self) -> Result<Commits<'_>, Error> {
+        Commits::new(self)
+    }
+
+    /// Get the advisories which were added, modified, or removed between
+    /// the commits with the given IDs (e.g. the one last audited against and HEAD)
+    pub fn advisory_changes(
+        &self,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> Result<AdvisoryChanges, Error> {
+        AdvisoryChanges::between(self, from_commit, to_commit)
+    }
+
+    /// Iterate over all of the advisories in this repo, in every collection
     pub(crate) fn crate_advisories(&self) -> Result<Iter, Error> {
+        Iter::new(&self.path)
+    }
+
+    /// Get the paths (relative to the root of the repository) and contents
+    /// of the advisory files in the given commit, in every collection
+    pub(crate) fn crate_advisories_at(
+        &self,
+        commit_id: &str,
+    ) -> Result<Vec<(PathBuf, String)>, Error> {
         let mut advisory_files = vec![];
 
-        // Iterate over the individual crates in the `crates/` directory
-        for crate_entry in fs::read_dir(self.path.join(CRATE_ADVISORY_DIRECTORY))? {
-            for advisory_entry in fs::read_dir(crate_entry?.path())? {
-                advisory_files.push(RepoFile::new(advisory_entry?.path())?);
+        for collection in Collection::all() {
+            let files = backend::tree_files(&self.repo, commit_id, collection.as_str())?;
+
+            for (path, blob_id) in files {
+                let path = PathBuf::from(path);
+
+                if !is_advisory_file(&path) {
+                    continue;
+                }
+
+                let contents = String::from_utf8(backend::read_blob(&self.repo, &blob_id)?)
+                    .map_err(|e| err!(ErrorKind::Repo, "invalid {}: {}", path.display(), e))?;
+
+                advisory_files.push((path, contents));
             }
         }
 
-        Ok(Iter(advisory_files.into_iter()))
+        Ok(advisory_files)
     }
 }
 
+/// Is the file at the given path an advisory (i.e. a `.toml` or `.md` file)?
+pub(crate) fn is_advisory_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext == "toml" || ext == "md")
+        .unwrap_or(false)
+}
+
 /// Iterator over the advisory database
 pub(crate) struct Iter(vec::IntoIter<RepoFile>);
 
+impl Iter {
+    /// Find all of the advisories in an advisory DB checkout at the given path
+    pub(crate) fn new(db_path: &Path) -> Result<Self, Error> {
+        let crates_path = db_path.join(CRATE_ADVISORY_DIRECTORY);
+
+        if !crates_path.is_dir() {
+            fail!(
+                ErrorKind::BadParam,
+                "not an advisory DB (missing {} directory): {}",
+                CRATE_ADVISORY_DIRECTORY,
+                db_path.display()
+            );
+        }
+
+        let mut advisory_files = vec![];
+
+        // Iterate over the individual packages in each collection's directory
+        // (e.g. `crates/`), of which only `crates/` is required to exist.
+        // Other files (e.g. READMEs) are ignored, so any directory laid out
+        // like the advisory DB can be loaded.
+        for collection in Collection::all() {
+            let collection_path = db_path.join(collection.as_str());
+
+            if !collection_path.is_dir() {
+                continue;
+            }
+
+            for package_entry in fs::read_dir(collection_path)? {
+                let package_path = package_entry?.path();
+
+                if !package_path.is_dir() {
+                    continue;
+                }
+
+                for advisory_entry in fs::read_dir(package_path)? {
+                    let advisory_path = advisory_entry?.path();
+
+                    if !is_advisory_file(&advisory_path) {
+                        continue;
+                    }
+
+                    advisory_files.push(RepoFile::new(advisory_path)?);
+                }
+            }
+        }
+
+        Ok(Iter(advisory_files.into_iter()))
+    }
+}
+
 impl Iterator for Iter {
     type Item = RepoFile;
 
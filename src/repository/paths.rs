@@ -0,0 +1,78 @@
+//! Resolution of the default locations of the advisory DB on disk
+
+use std::{env, path::PathBuf};
+
+use error::{Error, ErrorKind};
+
+/// Directory under ~/.cargo where the advisory-db repo will be kept
+const ADVISORY_DB_DIRECTORY: &str = "advisory-db";
+
+/// Directory under `$XDG_CACHE_HOME` containing the advisory-db repo
+const XDG_CACHE_DIRECTORY: &str = "rustsec";
+
+/// Where the advisory DB repository is kept on disk
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum CacheLocation {
+    /// `advisory-db` under `$CARGO_HOME` (or `~/.cargo` if it's unset)
+    #[default]
+    CargoHome,
+
+    /// `rustsec/advisory-db` under `$XDG_CACHE_HOME` (or `~/.cache` if it's unset)
+    XdgCache,
+
+    /// The given path
+    Custom(PathBuf),
+}
+
+impl CacheLocation {
+    /// Path to the `advisory-db` repository in this location
+    pub fn repo_path(&self) -> Result<PathBuf, Error> {
+        match *self {
+            CacheLocation::CargoHome => Ok(cargo_home()?.join(ADVISORY_DB_DIRECTORY)),
+            CacheLocation::XdgCache => Ok(xdg_cache_home()?
+                .join(XDG_CACHE_DIRECTORY)
+                .join(ADVISORY_DB_DIRECTORY)),
+            CacheLocation::Custom(ref path) => Ok(path.clone()),
+        }
+    }
+}
+
+impl From<Option<PathBuf>> for CacheLocation {
+    /// Use the given path if any, or the default location otherwise
+    fn from(path: Option<PathBuf>) -> Self {
+        path.map(CacheLocation::Custom).unwrap_or_default()
+    }
+}
+
+/// Locate Cargo's home directory: `$CARGO_HOME` if set, or `~/.cargo` otherwise
+/// (the same as Cargo itself)
+pub(crate) fn cargo_home() -> Result<PathBuf, Error> {
+    if let Some(path) = env::var_os("CARGO_HOME").filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    home_dir("CARGO_HOME").map(|home| home.join(".cargo"))
+}
+
+/// Locate the user's cache directory: `$XDG_CACHE_HOME` if set to an absolute
+/// path (as the XDG Base Directory spec requires), or `~/.cache` otherwise
+fn xdg_cache_home() -> Result<PathBuf, Error> {
+    if let Some(path) = env::var_os("XDG_CACHE_HOME").map(PathBuf::from) {
+        if path.is_absolute() {
+            return Ok(path);
+        }
+    }
+
+    home_dir("XDG_CACHE_HOME").map(|home| home.join(".cache"))
+}
+
+/// Locate the user's home directory, as a fallback when `var` is unset
+fn home_dir(var: &str) -> Result<PathBuf, Error> {
+    env::home_dir().ok_or_else(|| {
+        err!(
+            ErrorKind::BadParam,
+            "can't locate the advisory DB ({} and HOME are unset)",
+            var
+        )
+    })
+}
@@ -0,0 +1,29 @@
+#[cfg(feature = "chrono")]
+use error::{Error, ErrorKind};
+
+/// Action to take when a check on the fetched repository fails (e.g. it's stale)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PolicyAction {
+    /// Fail with an error
+    Error,
+
+    /// Continue, but return a warning to the caller (e.g. see
+    /// `Repository::warnings`)
+    Warn,
+
+    /// Don't perform the check
+    Ignore,
+}
+
+impl PolicyAction {
+    /// Act on a failed check with the given message, which is returned as a
+    /// warning if this is `Warn`
+    #[cfg(feature = "chrono")]
+    pub(crate) fn apply(self, message: String) -> Result<Option<String>, Error> {
+        match self {
+            PolicyAction::Error => Err(err!(ErrorKind::Repo, message)),
+            PolicyAction::Warn => Ok(Some(message)),
+            PolicyAction::Ignore => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::{fmt, sync::Arc};
+
+/// Progress made while fetching the repository
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Progress {
+    /// Objects are being received from the remote repository
+    Transfer {
+        /// Number of objects received so far
+        received_objects: usize,
+
+        /// Total number of objects to receive (if known, otherwise 0)
+        total_objects: usize,
+
+        /// Number of bytes received so far
+        received_bytes: usize,
+    },
+
+    /// Files are being checked out into the working directory
+    Checkout {
+        /// Number of files checked out so far
+        completed: usize,
+
+        /// Total number of files to check out
+        total: usize,
+    },
+}
+
+/// Callback which is invoked as progress is made fetching the repository
+/// (e.g. to render a progress bar)
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Create a new progress callback from the given function
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        ProgressCallback(Arc::new(f))
+    }
+
+    /// Report the given progress to the callback
+    pub fn report(&self, progress: Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ProgressCallback")
+    }
+}
@@ -0,0 +1,76 @@
+//! Refs used when fetching the advisory DB repository
+
+/// Branch which is fetched and checked out by default
+pub(crate) const DEFAULT_BRANCH: &str = "master";
+
+/// Prefix of local branch refs
+const BRANCH_REF_PREFIX: &str = "refs/heads/";
+
+/// Prefix of tag refs
+const TAG_REF_PREFIX: &str = "refs/tags/";
+
+/// Prefix of local refs which track branches in the remote repository
+const REMOTE_BRANCH_REF_PREFIX: &str = "refs/remotes/origin/";
+
+/// Refs involved in fetching a particular ref from the remote repository
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct FetchRefs {
+    /// Full name of the ref in the remote repository
+    pub remote: String,
+
+    /// Local ref which is updated to match the remote ref
+    pub tracking: String,
+
+    /// Local branch to check out, if the remote ref is a branch (otherwise
+    /// HEAD is detached at the fetched commit)
+    pub branch: Option<String>,
+}
+
+impl FetchRefs {
+    /// Determine the refs to use when fetching the given ref, which is either
+    /// a branch name (e.g. `main`) or a full ref name (e.g. `refs/tags/v1`)
+    pub fn new(reference: &str) -> Self {
+        let remote = if reference.starts_with("refs/") {
+            reference.to_owned()
+        } else {
+            format!("{}{}", BRANCH_REF_PREFIX, reference)
+        };
+
+        if let Some(branch_name) = remote.strip_prefix(BRANCH_REF_PREFIX) {
+            FetchRefs {
+                tracking: format!("{}{}", REMOTE_BRANCH_REF_PREFIX, branch_name),
+                branch: Some(remote.clone()),
+                remote,
+            }
+        } else {
+            FetchRefs {
+                tracking: remote.clone(),
+                branch: None,
+                remote,
+            }
+        }
+    }
+
+    /// Determine the refs to use when fetching the tags matching the given
+    /// pattern (e.g. `release-*`)
+    pub fn tags(pattern: &str) -> Self {
+        let remote = format!("{}{}", TAG_REF_PREFIX, pattern);
+
+        FetchRefs {
+            tracking: remote.clone(),
+            branch: None,
+            remote,
+        }
+    }
+
+    /// Refspec which fetches the remote ref into the tracking ref
+    pub fn refspec(&self) -> String {
+        format!("{}:{}", self.remote, self.tracking)
+    }
+}
+
+impl Default for FetchRefs {
+    fn default() -> Self {
+        FetchRefs::new(DEFAULT_BRANCH)
+    }
+}
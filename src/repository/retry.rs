@@ -0,0 +1,72 @@
+//! Retrying network operations which fail transiently
+
+use std::time::Duration;
+#[cfg(feature = "chrono")]
+use std::{cmp, thread};
+
+#[cfg(feature = "chrono")]
+use error::{Error, ErrorKind};
+
+/// How to retry fetches which fail with transient errors (i.e. those of kind
+/// `ErrorKind::Network` or `ErrorKind::Timeout`), waiting exponentially
+/// longer between each attempt
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of times to retry (by default, failed fetches aren't retried)
+    pub max_retries: u32,
+
+    /// Time to wait before the first retry, which doubles after each attempt
+    pub initial_delay: Duration,
+
+    /// Maximum time to wait between attempts
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Retry up to the given number of times, with the default delays
+    pub fn retries(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Run the given operation, retrying it according to this policy
+    #[cfg(feature = "chrono")]
+    pub(crate) fn run<T, F>(&self, mut operation: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Result<T, Error>,
+    {
+        let mut delay = self.initial_delay;
+        let mut retries = 0;
+
+        loop {
+            match operation() {
+                Err(ref e) if retries < self.max_retries && is_transient(e) => {
+                    thread::sleep(delay);
+                    delay = cmp::min(delay * 2, self.max_delay);
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Might retrying the operation which caused the given error succeed? Fetches
+/// which time out have stopped by the time they fail, so retrying them never
+/// runs two fetches into the same repository at once.
+#[cfg(feature = "chrono")]
+fn is_transient(error: &Error) -> bool {
+    matches!(error.kind(), ErrorKind::Network | ErrorKind::Timeout)
+}
@@ -1,19 +1,285 @@
-use error::Error;
+use pgp::composed::{Deserializable, DetachedSignature};
+use ssh_key::SshSig;
+use std::{fmt, str};
 
-/// Digital signatures (in OpenPGP format) on commits to the repository
+use super::{allowed_signer::AllowedSigner, signing_key::SigningKey};
+use error::{Error, ErrorKind};
+
+/// Armor headers which begin each kind of signature Git supports
+const ARMOR_HEADERS: &[(&[u8], SignatureKind)] = &[
+    (b"-----BEGIN PGP SIGNATURE-----", SignatureKind::OpenPgp),
+    (b"-----BEGIN PGP MESSAGE-----", SignatureKind::OpenPgp),
+    (b"-----BEGIN SSH SIGNATURE-----", SignatureKind::Ssh),
+    (b"-----BEGIN SIGNED MESSAGE-----", SignatureKind::X509),
+];
+
+/// Digital signatures on commits (or tags) to the repository
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Signature(Vec<u8>);
+pub struct Signature {
+    /// Format of the signature
+    kind: SignatureKind,
+
+    /// Raw (ASCII armored) signature
+    bytes: Vec<u8>,
+}
+
+/// Formats of signatures Git can make (see `gpg.format` in `git-config(1)`)
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SignatureKind {
+    /// OpenPGP signatures (made by e.g. GnuPG)
+    OpenPgp,
+
+    /// SSH signatures (made by `ssh-keygen -Y sign`)
+    Ssh,
+
+    /// X.509 (i.e. S/MIME) signatures (made by e.g. `gpgsm`)
+    X509,
+}
+
+impl SignatureKind {
+    /// Detect the kind of the given (ASCII armored) signature from its armor header
+    pub(crate) fn detect(signature: &[u8]) -> Option<Self> {
+        let start = signature
+            .iter()
+            .position(|byte| !byte.is_ascii_whitespace())
+            .unwrap_or(0);
+
+        ARMOR_HEADERS
+            .iter()
+            .find(|&&(header, _)| signature[start..].starts_with(header))
+            .map(|&(_, kind)| kind)
+    }
+
+    /// Find where the signature (of any kind) appended to the given data
+    /// begins, i.e. the last line which starts with an armor header (as Git
+    /// does, since the message before it may contain armor headers too)
+    #[cfg(feature = "chrono")]
+    pub(crate) fn find_start(data: &[u8]) -> Option<usize> {
+        ARMOR_HEADERS
+            .iter()
+            .filter_map(|&(header, _)| {
+                data.windows(header.len())
+                    .enumerate()
+                    .rposition(|(pos, window)| {
+                        window == header && (pos == 0 || data[pos - 1] == b'\n')
+                    })
+            })
+            .max()
+    }
+}
+
+impl fmt::Display for SignatureKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            SignatureKind::OpenPgp => "OpenPGP",
+            SignatureKind::Ssh => "SSH",
+            SignatureKind::X509 => "X.509",
+        })
+    }
+}
 
 impl Signature {
     /// Parse a signature from a Git commit
     // TODO: actually verify the signature is well-structured
     pub fn new<T: Into<Vec<u8>>>(into_vec: T) -> Result<Self, Error> {
-        Ok(Signature(into_vec.into()))
+        let bytes = into_vec.into();
+
+        let kind = SignatureKind::detect(&bytes)
+            .ok_or_else(|| err!(ErrorKind::Parse, "unrecognized signature format"))?;
+
+        Ok(Signature { kind, bytes })
+    }
+
+    /// Get the format of this signature
+    pub fn kind(&self) -> SignatureKind {
+        self.kind
+    }
+
+    /// Verify this signature over the given data was made by one of the given
+    /// keys of its kind, i.e. OpenPGP keys or SSH allowed signers. If no keys
+    /// of either kind are given, no signature is trusted.
+    pub fn verify_trusted(
+        &self,
+        trusted_keys: &[SigningKey],
+        allowed_signers: &[AllowedSigner],
+        signed_data: &[u8],
+    ) -> Result<(), Error> {
+        if trusted_keys.is_empty() && allowed_signers.is_empty() {
+            fail!(
+                ErrorKind::Repo,
+                "no trusted signing keys or allowed signers are configured"
+            );
+        }
+
+        match self.kind {
+            SignatureKind::Ssh => self.verify_ssh(allowed_signers, signed_data),
+            _ => self.verify(trusted_keys, signed_data),
+        }
+    }
+
+    /// Verify this (SSH) signature over the given data was made by one of the
+    /// given allowed signers
+    pub fn verify_ssh(
+        &self,
+        allowed_signers: &[AllowedSigner],
+        signed_data: &[u8],
+    ) -> Result<(), Error> {
+        if self.kind != SignatureKind::Ssh {
+            fail!(ErrorKind::Repo, "not an SSH signature: {}", self.kind);
+        }
+
+        let signature = SshSig::from_pem(&self.bytes)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid SSH signature: {}", e))?;
+
+        if !allowed_signers
+            .iter()
+            .any(|signer| signer.verify(&signature, signed_data))
+        {
+            fail!(
+                ErrorKind::Repo,
+                "signature not made by any of the allowed signers"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Verify this (OpenPGP) signature over the given data was made by one of the given keys
+    pub fn verify(&self, keys: &[SigningKey], signed_data: &[u8]) -> Result<(), Error> {
+        if self.kind != SignatureKind::OpenPgp {
+            fail!(
+                ErrorKind::Repo,
+                "verifying {} signatures is unsupported",
+                self.kind
+            );
+        }
+
+        let (signature, _) = DetachedSignature::from_string(str::from_utf8(&self.bytes)?)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid OpenPGP signature: {}", e))?;
+
+        if keys
+            .iter()
+            .any(|key| key.verify(&signature.signature, signed_data))
+        {
+            Ok(())
+        } else {
+            fail!(
+                ErrorKind::Repo,
+                "signature not made by any of the trusted signing keys"
+            )
+        }
     }
 }
 
 impl AsRef<[u8]> for Signature {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        self.bytes.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Signature, SignatureKind};
+    use repository::signing_key::SigningKey;
+    use std::slice;
+
+    /// Key which signed `SIGNED_COMMIT`
+    const TRUSTED_KEY: &str = "-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEas864xYJKwYBBAHaRw8BAQdA8q3rbnXpQmgW09Mk4uZ2cw9innesj4zvM0v8
+ZQ8UrX20H1J1c3RTZWMgVGVzdCA8dGVzdEBleGFtcGxlLmNvbT6IkAQTFggAOBYh
+BANUPn/LoYNQH0FwKpp+aZcG6zIcBQJqzzrjAhsDBQsJCAcCBhUKCQgLAgQWAgMB
+Ah4BAheAAAoJEJp+aZcG6zIcAqEBAILIiqNPBTXN30g8phcNmIVNeL3c2xhGZZQv
+b7wWoFS/AP43ag/lM0LfAx3nZdlwS5JmfB0V0mDvct/eOsel3eBsBw==
+=r0kv
+-----END PGP PUBLIC KEY BLOCK-----
+";
+
+    /// Key which didn't sign `SIGNED_COMMIT`
+    const OTHER_KEY: &str = "-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEas864xYJKwYBBAHaRw8BAQdA7Z4EMQhiUThWNL1SeFPMY3DU9Nz5Ua8UuIVc
+bbPKQvS0Hk90aGVyIFRlc3QgPG90aGVyQGV4YW1wbGUuY29tPoiQBBMWCAA4FiEE
+DzeM+CaTjYUgS8neftFgPyXIOxgFAmrPOuMCGwMFCwkIBwIGFQoJCAsCBBYCAwEC
+HgECF4AACgkQftFgPyXIOxjFxwD/bHOAbc9pOmF85IbInzZhpARfQgSK0VMP3qfM
+xOtMdIkBAKfBjuvHiEkM/uLeCOE1fAMs9YhBSqVCiMuwCM33yAQC
+=xe2v
+-----END PGP PUBLIC KEY BLOCK-----
+";
+
+    /// Commit object, without its `gpgsig` header (i.e. the data it signs)
+    const SIGNED_COMMIT: &str = "tree aaff74984cccd156a469afa7d9ab10e4777beb24
+author RustSec Test <test@example.com> 1561939200 +0000
+committer RustSec Test <test@example.com> 1561939200 +0000
+
+Signed commit
+";
+
+    /// The commit's `gpgsig` header
+    const COMMIT_SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----
+
+iIcEABYIAC8WIQQDVD5/y6GDUB9BcCqafmmXBusyHAUCas864xEcdGVzdEBleGFt
+cGxlLmNvbQAKCRCafmmXBusyHDEfAQDOLPpR+mUQJyDSBou+/bLWXEbqWWPs3pc8
+TJfYCJqWnQD8DfrNW1b4xpyQ+8BEhfFZ/5/vWbFv3WgG4b5Im/q1WwY=
+=L/++
+-----END PGP SIGNATURE-----
+";
+
+    #[test]
+    fn verify_trusted_test() {
+        let signature = Signature::new(COMMIT_SIGNATURE).unwrap();
+        let trusted = SigningKey::from_armored(TRUSTED_KEY).unwrap();
+        let other = SigningKey::from_armored(OTHER_KEY).unwrap();
+        let data = SIGNED_COMMIT.as_bytes();
+
+        assert!(signature
+            .verify_trusted(slice::from_ref(&trusted), &[], data)
+            .is_ok());
+        assert!(signature
+            .verify_trusted(&[other.clone(), trusted.clone()], &[], data)
+            .is_ok());
+        assert!(signature.verify_trusted(&[other], &[], data).is_err());
+        assert!(signature
+            .verify_trusted(&[trusted], &[], b"tampered commit")
+            .is_err());
+
+        // With nothing trusted, no signature is accepted
+        assert!(signature.verify_trusted(&[], &[], data).is_err());
+    }
+
+    #[test]
+    fn kind_test() {
+        assert_eq!(
+            SignatureKind::detect(b"-----BEGIN PGP SIGNATURE-----\n\nabc"),
+            Some(SignatureKind::OpenPgp)
+        );
+        assert_eq!(
+            SignatureKind::detect(b"-----BEGIN SSH SIGNATURE-----\nabc"),
+            Some(SignatureKind::Ssh)
+        );
+        assert_eq!(
+            SignatureKind::detect(b"-----BEGIN SIGNED MESSAGE-----\nabc"),
+            Some(SignatureKind::X509)
+        );
+        assert_eq!(SignatureKind::detect(b"not a signature"), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn find_start_test() {
+        assert_eq!(
+            SignatureKind::find_start(b"tag v1\n\n-----BEGIN SSH SIGNATURE-----\n"),
+            Some(8)
+        );
+        assert_eq!(
+            SignatureKind::find_start(
+                b"tag v1\n\n-----BEGIN PGP SIGNATURE-----\n-----BEGIN SSH SIGNATURE-----\n"
+            ),
+            Some(38)
+        );
+        assert_eq!(
+            SignatureKind::find_start(b"tag v1\n\nnot -----BEGIN SSH SIGNATURE-----\n"),
+            None
+        );
     }
 }
@@ -0,0 +1,69 @@
+use pgp::{
+    composed::{Deserializable, SignedPublicKey},
+    packet,
+    types::KeyDetails,
+};
+
+use error::{Error, ErrorKind};
+
+/// ASCII-armored OpenPGP keys trusted to sign commits to the RustSec advisory
+/// DB by default. None are bundled yet, so unless trusted keys (or SSH allowed
+/// signers) are configured, `Repository::fetch` trusts no signatures and fails.
+pub const DEFAULT_SIGNING_KEYS: &[&str] = &[];
+
+/// OpenPGP public key trusted to sign commits to the repository
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SigningKey(SignedPublicKey);
+
+impl SigningKey {
+    /// Parse an ASCII-armored OpenPGP public key
+    pub fn from_armored(armored: &str) -> Result<Self, Error> {
+        let (key, _) = SignedPublicKey::from_string(armored)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid signing key: {}", e))?;
+
+        key.verify_bindings()
+            .map_err(|e| err!(ErrorKind::Parse, "invalid signing key: {}", e))?;
+
+        Ok(SigningKey(key))
+    }
+
+    /// Parse all of the keys in an ASCII-armored OpenPGP keyring (e.g. as
+    /// exported by `gpg --armor --export`)
+    pub fn from_armored_keyring(armored: &str) -> Result<Vec<Self>, Error> {
+        let (keys, _) = SignedPublicKey::from_string_many(armored)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid signing key: {}", e))?;
+
+        keys.map(|key| {
+            let key = key.map_err(|e| err!(ErrorKind::Parse, "invalid signing key: {}", e))?;
+
+            key.verify_bindings()
+                .map_err(|e| err!(ErrorKind::Parse, "invalid signing key: {}", e))?;
+
+            Ok(SigningKey(key))
+        })
+        .collect()
+    }
+
+    /// Parse the keys in `DEFAULT_SIGNING_KEYS`
+    pub fn default_keys() -> Vec<Self> {
+        DEFAULT_SIGNING_KEYS
+            .iter()
+            .map(|armored| Self::from_armored(armored).expect("invalid default signing key"))
+            .collect()
+    }
+
+    /// Get the fingerprint of this key (as a hex string)
+    pub fn fingerprint(&self) -> String {
+        format!("{:X}", self.0.fingerprint())
+    }
+
+    /// Is the given signature over `data` made by this key (or one of its subkeys)?
+    pub(crate) fn verify(&self, signature: &packet::Signature, data: &[u8]) -> bool {
+        signature.verify(&self.0.primary_key, data).is_ok()
+            || self
+                .0
+                .public_subkeys
+                .iter()
+                .any(|subkey| signature.verify(&subkey.key, data).is_ok())
+    }
+}
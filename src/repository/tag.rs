@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+
+use super::{
+    allowed_signer::AllowedSigner,
+    backend,
+    keyring::Keyring,
+    signature::{Signature, SignatureKind},
+    signing_key::SigningKey,
+};
+use error::{Error, ErrorKind};
+
+/// Information about an annotated tag in the Git repository
+#[derive(Debug)]
+pub struct Tag {
+    /// Name of the tag (e.g. `release-2018-07-31`)
+    pub name: String,
+
+    /// ID (i.e. SHA-1 hash) of the commit the tag points to
+    pub target_id: String,
+
+    /// Information about the creator of the tag
+    pub tagger: String,
+
+    /// Time the tag was created
+    pub time: DateTime<Utc>,
+
+    /// Signature on the tag (mandatory when fetching signed tags)
+    pub signature: Option<Signature>,
+
+    /// Signed data to verify along with this tag
+    signed_data: Option<Vec<u8>>,
+}
+
+impl Tag {
+    /// Create a `Tag` from its raw object data, which may have a signature
+    /// (of any kind) appended to it
+    pub(super) fn new(
+        name: String,
+        target_id: String,
+        tagger: String,
+        time: DateTime<Utc>,
+        raw_object: &[u8],
+    ) -> Result<Self, Error> {
+        let (signature, signed_data) = match SignatureKind::find_start(raw_object) {
+            Some(pos) => (
+                Some(Signature::new(&raw_object[pos..])?),
+                Some(raw_object[..pos].to_vec()),
+            ),
+            None => (None, None),
+        };
+
+        Ok(Tag {
+            name,
+            target_id,
+            tagger,
+            time,
+            signature,
+            signed_data,
+        })
+    }
+
+    /// Find the most recent annotated tag whose name matches the given pattern
+    pub(crate) fn latest(repo: &backend::Repo, pattern: &str) -> Result<Self, Error> {
+        backend::tags(repo)?
+            .into_iter()
+            .filter(|tag| matches_pattern(pattern, &tag.name))
+            .max_by(|a, b| (a.time, &a.name).cmp(&(b.time, &b.name)))
+            .ok_or_else(|| err!(ErrorKind::Repo, "no annotated tags matching: {}", pattern))
+    }
+
+    /// Get the raw bytes to be verified when verifying a tag signature
+    pub fn raw_signed_bytes(&self) -> Option<&[u8]> {
+        self.signed_data.as_ref().map(|bytes| bytes.as_ref())
+    }
+
+    /// Ensure this tag is signed by one of the given trusted keys
+    pub fn verify_signature(&self, trusted_keys: &[SigningKey]) -> Result<(), Error> {
+        self.verify_signature_with(trusted_keys, &[])
+    }
+
+    /// Ensure this tag is signed by one of the OpenPGP keys in the keyring
+    /// which is valid at the time it was made, or one of the SSH allowed signers
+    #[cfg(feature = "chrono")]
+    pub fn verify_signature_with_keyring(
+        &self,
+        keyring: &Keyring,
+        allowed_signers: &[AllowedSigner],
+    ) -> Result<(), Error> {
+        self.verify_signature_with(&keyring.keys_valid_at(self.time)?, allowed_signers)
+    }
+
+    /// Ensure this tag is signed by one of the given trusted OpenPGP keys or
+    /// SSH allowed signers (if none are given, it's an error)
+    pub fn verify_signature_with(
+        &self,
+        trusted_keys: &[SigningKey],
+        allowed_signers: &[AllowedSigner],
+    ) -> Result<(), Error> {
+        let (signature, signed_data) = match (&self.signature, &self.signed_data) {
+            (Some(signature), Some(signed_data)) => (signature, signed_data),
+            _ => fail!(
+                ErrorKind::Repo,
+                "no signature on tag {} ({})",
+                self.name,
+                self.tagger
+            ),
+        };
+
+        signature
+            .verify_trusted(trusted_keys, allowed_signers, signed_data)
+            .map_err(|e| err!(ErrorKind::Repo, "bad signature on tag {}: {}", self.name, e))
+    }
+}
+
+/// Does the given tag name match a pattern containing at most one `*`
+/// wildcard (as in a refspec)?
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.find('*') {
+        Some(pos) => {
+            let (prefix, suffix) = (&pattern[..pos], &pattern[pos + 1..]);
+
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches_pattern, Tag};
+    use chrono::DateTime;
+    use repository::{allowed_signer::AllowedSigner, signature::SignatureKind};
+
+    /// SSH key which signed `SIGNED_TAG`
+    const ALLOWED_SIGNER: &str = "test@example.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGdez2g1RAueBbMo67FjDQyCYhIQnRo4josm4rieNugj";
+
+    /// Tag object (as created by `git tag -s`) whose message contains an
+    /// armor header before the signature appended to it
+    const SIGNED_TAG: &str = "object fe878915aaf6791923c3f38dc1485746b53966cc
+type commit
+tag v1
+tagger RustSec Test <test@example.com> 1561939200 +0000
+
+Release v1
+
+Quoting a signature:
+-----BEGIN PGP SIGNATURE-----
+-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAgZ17PaDVEC54FsyjrsWMNDIJiEh
+CdGjiOiybiuJ426CMAAAADZ2l0AAAAAAAAAAZzaGE1MTIAAABTAAAAC3NzaC1lZDI1NTE5
+AAAAQAGNrtI6e7QnOQBuLZ4qx9SSMHbu8ukyqrDg+Of2wiRcpZcM7uut5B4KApF8Y5yQfb
+irPIOt5x947BaQ//JoeQw=
+-----END SSH SIGNATURE-----
+";
+
+    #[test]
+    fn signature_test() {
+        let tag = Tag::new(
+            "v1".to_owned(),
+            "fe878915aaf6791923c3f38dc1485746b53966cc".to_owned(),
+            "RustSec Test <test@example.com>".to_owned(),
+            DateTime::from_timestamp(1_561_939_200, 0).unwrap(),
+            SIGNED_TAG.as_bytes(),
+        )
+        .unwrap();
+
+        // Only the signature appended to the tag is split off
+        assert_eq!(tag.signature.as_ref().unwrap().kind(), SignatureKind::Ssh);
+        assert!(tag
+            .raw_signed_bytes()
+            .unwrap()
+            .ends_with(b"Quoting a signature:\n-----BEGIN PGP SIGNATURE-----\n"));
+
+        let signer = AllowedSigner::parse(ALLOWED_SIGNER).unwrap();
+        assert!(tag.verify_signature_with(&[], &[signer]).is_ok());
+    }
+
+    #[test]
+    fn pattern_test() {
+        assert!(matches_pattern("release-*", "release-2018-07-31"));
+        assert!(matches_pattern("release-*", "release-"));
+        assert!(matches_pattern("v*-signed", "v1-signed"));
+        assert!(matches_pattern("v1", "v1"));
+        assert!(!matches_pattern("release-*", "v1"));
+        assert!(!matches_pattern("v*-signed", "v1"));
+        assert!(!matches_pattern("ab*ba", "aba"));
+    }
+}
@@ -0,0 +1,114 @@
+//! TLS configuration for fetching the advisory DB repository over HTTPS
+
+#[cfg(all(feature = "git2", feature = "chrono"))]
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+#[cfg(all(feature = "git2", feature = "chrono"))]
+use rustls_pki_types::{pem::PemObject, CertificateDer, ServerName, UnixTime};
+#[cfg(all(feature = "git2", feature = "chrono"))]
+use webpki;
+
+#[cfg(all(feature = "git2", feature = "chrono"))]
+use error::{Error, ErrorKind};
+
+/// Options which control how the remote repository's TLS certificate is verified
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TlsOptions {
+    /// PEM file containing additional CA certificates to trust (e.g. that of a
+    /// TLS-intercepting proxy). The remote repository's certificate must be
+    /// signed directly by one of them (i.e. without intermediate certificates).
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Accept the remote repository's certificate even if it's invalid. This
+    /// is dangerous, and fetches using it warn about it (see `Repository::warnings`).
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl TlsOptions {
+    /// Are these the default options (i.e. the system's TLS configuration is used as-is)?
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Verifies TLS certificates according to `TlsOptions`, in addition to the
+/// verification libgit2 performs itself
+#[cfg(all(feature = "git2", feature = "chrono"))]
+pub(crate) struct CertificateVerifier {
+    /// Additional CA certificates from `TlsOptions::ca_bundle`
+    ca_certificates: Vec<CertificateDer<'static>>,
+
+    /// Accept any certificate
+    accept_invalid_certs: bool,
+}
+
+#[cfg(all(feature = "git2", feature = "chrono"))]
+impl CertificateVerifier {
+    /// Create a verifier for the given options, loading any CA bundle
+    pub fn new(options: &TlsOptions) -> Result<Self, Error> {
+        let ca_certificates = match options.ca_bundle {
+            Some(ref path) => CertificateDer::pem_file_iter(path)
+                .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| {
+                    err!(
+                        ErrorKind::BadParam,
+                        "invalid CA bundle {}: {}",
+                        path.display(),
+                        e
+                    )
+                })?,
+            None => vec![],
+        };
+
+        Ok(CertificateVerifier {
+            ca_certificates,
+            accept_invalid_certs: options.danger_accept_invalid_certs,
+        })
+    }
+
+    /// Should the given (DER-encoded) certificate be accepted for the given
+    /// host, regardless of whether libgit2 considers it valid?
+    pub fn accepts(&self, certificate: &[u8], host: &str) -> bool {
+        if self.accept_invalid_certs {
+            return true;
+        }
+
+        if self.ca_certificates.is_empty() {
+            return false;
+        }
+
+        let trust_anchors = self
+            .ca_certificates
+            .iter()
+            .filter_map(|cert| webpki::anchor_from_trusted_cert(cert).ok())
+            .collect::<Vec<_>>();
+
+        let certificate = CertificateDer::from(certificate);
+
+        let end_entity = match webpki::EndEntityCert::try_from(&certificate) {
+            Ok(end_entity) => end_entity,
+            Err(_) => return false,
+        };
+
+        let server_name = match ServerName::try_from(host) {
+            Ok(server_name) => server_name,
+            Err(_) => return false,
+        };
+
+        end_entity
+            .verify_for_usage(
+                webpki::ALL_VERIFICATION_ALGS,
+                &trust_anchors,
+                &[],
+                UnixTime::now(),
+                webpki::KeyUsage::server_auth(),
+                None,
+                None,
+            )
+            .is_ok()
+            && end_entity
+                .verify_is_valid_for_subject_name(&server_name)
+                .is_ok()
+    }
+}
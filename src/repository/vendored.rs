@@ -0,0 +1,120 @@
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+#[cfg(feature = "chrono")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "chrono")]
+use super::FreshnessPolicy;
+use super::{Iter, CRATE_ADVISORY_DIRECTORY};
+#[cfg(feature = "chrono")]
+use db::AdvisoryDatabase;
+use error::{Error, ErrorKind};
+
+/// File within a vendored advisory DB containing the time it was last updated
+/// (as an RFC 3339 timestamp or `YYYY-MM-DD` date)
+pub const LAST_UPDATED_FILE: &str = "last-updated";
+
+/// Copy of the advisory DB without any Git metadata, e.g. as shipped by
+/// distribution packages
+#[derive(Debug)]
+pub struct VendoredDirectory {
+    /// Path to the directory
+    path: PathBuf,
+}
+
+impl VendoredDirectory {
+    /// Open the vendored advisory DB at the given path
+    pub fn open<P: Into<PathBuf>>(into_path: P) -> Result<Self, Error> {
+        let path = into_path.into();
+
+        if !path.join(CRATE_ADVISORY_DIRECTORY).is_dir() {
+            fail!(
+                ErrorKind::BadParam,
+                "not an advisory DB (missing {} directory): {}",
+                CRATE_ADVISORY_DIRECTORY,
+                path.display()
+            );
+        }
+
+        Ok(VendoredDirectory { path })
+    }
+
+    /// Path to the vendored advisory DB
+    pub fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
+
+    /// Time the vendored advisory DB was last updated, according to its
+    /// `last-updated` file if present, or otherwise the date of its newest advisory
+    #[cfg(feature = "chrono")]
+    pub fn last_updated(&self) -> Result<DateTime<Utc>, Error> {
+        let last_updated_path = self.path.join(LAST_UPDATED_FILE);
+
+        if last_updated_path.exists() {
+            return parse_last_updated(fs::read_to_string(&last_updated_path)?.trim());
+        }
+
+        let mut last_updated = None;
+
+        for advisory in AdvisoryDatabase::open(&self.path)?.advisories() {
+            let date = parse_last_updated(advisory.date.as_str())?;
+
+            if last_updated.map(|newest| date > newest).unwrap_or(true) {
+                last_updated = Some(date);
+            }
+        }
+
+        last_updated.ok_or_else(|| {
+            err!(
+                ErrorKind::Repo,
+                "no advisories in {}",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Ensure the vendored advisory DB has been updated recently, according to
+    /// the given policy, returning a warning if it's stale and the policy is to warn
+    #[cfg(feature = "chrono")]
+    pub fn ensure_fresh(&self, policy: &FreshnessPolicy) -> Result<Option<String>, Error> {
+        policy.ensure_fresh(self.last_updated()?, "last updated")
+    }
+
+    /// Iterate over all of the advisories in this directory, in every collection
+    pub(crate) fn crate_advisories(&self) -> Result<Iter, Error> {
+        Iter::new(&self.path)
+    }
+}
+
+/// Parse the contents of a `last-updated` file (or an advisory date)
+#[cfg(feature = "chrono")]
+fn parse_last_updated(string: &str) -> Result<DateTime<Utc>, Error> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(string) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(string, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| err!(ErrorKind::Parse, "invalid {} time: {}", LAST_UPDATED_FILE, string))
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::parse_last_updated;
+
+    #[test]
+    fn parse_last_updated_test() {
+        assert_eq!(
+            parse_last_updated("2019-03-01T12:00:00+01:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2019-03-01T11:00:00+00:00"
+        );
+        assert_eq!(
+            parse_last_updated("2019-03-01").unwrap().to_rfc3339(),
+            "2019-03-01T00:00:00+00:00"
+        );
+        assert!(parse_last_updated("March 1st").is_err());
+    }
+}
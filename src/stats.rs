@@ -0,0 +1,141 @@
+//! Aggregate statistics about the advisories in an `AdvisoryDatabase` (e.g.
+//! for reports of how many advisories were issued each year)
+
+use std::collections::BTreeMap;
+
+use advisory::{Advisory, Collection, Informational};
+use cvss::Severity;
+use package::PackageName;
+
+/// Aggregate statistics about a set of advisories. Withdrawn advisories are
+/// only counted in `withdrawn`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    /// Number of advisories which haven't been withdrawn
+    pub total: usize,
+
+    /// Number of advisories which have been withdrawn
+    pub withdrawn: usize,
+
+    /// Number of advisories issued each year
+    pub by_year: BTreeMap<u32, usize>,
+
+    /// Number of advisories about packages in each collection
+    pub by_collection: BTreeMap<Collection, usize>,
+
+    /// Number of informational advisories of each kind
+    pub by_informational: BTreeMap<Informational, usize>,
+
+    /// Number of advisories about vulnerabilities with each severity (see
+    /// `Advisory::severity`)
+    pub by_severity: BTreeMap<Severity, usize>,
+
+    /// Number of advisories about vulnerabilities with no known severity
+    pub unknown_severity: usize,
+
+    /// Number of advisories about each package
+    pub by_package: BTreeMap<PackageName, usize>,
+}
+
+impl Statistics {
+    /// Compute statistics about the given advisories
+    pub fn new<'a, I>(advisories: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Advisory>,
+    {
+        let mut stats = Statistics::default();
+
+        for advisory in advisories {
+            if advisory.is_withdrawn() {
+                stats.withdrawn += 1;
+                continue;
+            }
+
+            stats.total += 1;
+            *stats.by_year.entry(advisory.date.year()).or_insert(0) += 1;
+            *stats.by_collection.entry(advisory.collection).or_insert(0) += 1;
+
+            *stats
+                .by_package
+                .entry(advisory.package.clone())
+                .or_insert(0) += 1;
+
+            if let Some(ref informational) = advisory.informational {
+                *stats
+                    .by_informational
+                    .entry(informational.clone())
+                    .or_insert(0) += 1;
+            } else if let Some(severity) = advisory.severity() {
+                *stats.by_severity.entry(severity).or_insert(0) += 1;
+            } else {
+                stats.unknown_severity += 1;
+            }
+        }
+
+        stats
+    }
+
+    /// Number of advisories about vulnerabilities (i.e. not informational ones)
+    pub fn vulnerabilities(&self) -> usize {
+        self.by_severity.values().sum::<usize>() + self.unknown_severity
+    }
+
+    /// Get the (at most) `n` packages with the most advisories, along with
+    /// how many they have, most first (and in order of name for ties)
+    pub fn most_affected_packages(&self, n: usize) -> Vec<(&PackageName, usize)> {
+        let mut packages = self
+            .by_package
+            .iter()
+            .map(|(name, count)| (name, *count))
+            .collect::<Vec<_>>();
+
+        packages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        packages.truncate(n);
+        packages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Statistics;
+    use advisory::{AdvisoryBuilder, Collection, Informational};
+    use cvss::Severity;
+
+    #[test]
+    fn statistics_test() {
+        let build = |id, package, date| {
+            AdvisoryBuilder::fixture(id, package)
+                .and_then(|b| b.date(date))
+                .and_then(|b| b.patched_version(">= 1.0.0"))
+                .unwrap()
+        };
+
+        let advisories = vec![
+            build("RUSTSEC-2018-0001", "a", "2018-01-01")
+                .cvss("CVSS:3.0/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H")
+                .unwrap()
+                .build()
+                .unwrap(),
+            build("RUSTSEC-2019-0001", "a", "2019-01-01")
+                .build()
+                .unwrap(),
+            build("RUSTSEC-2019-0002", "b", "2019-02-01")
+                .informational(Informational::Unmaintained)
+                .build()
+                .unwrap(),
+        ];
+
+        let stats = Statistics::new(&advisories);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.by_year[&2019], 2);
+        assert_eq!(stats.by_collection[&Collection::Crates], 3);
+        assert_eq!(stats.by_informational[&Informational::Unmaintained], 1);
+        assert_eq!(stats.by_severity[&Severity::Critical], 1);
+        assert_eq!(stats.unknown_severity, 1);
+        assert_eq!(stats.vulnerabilities(), 2);
+
+        let most_affected = stats.most_affected_packages(1);
+        assert_eq!(most_affected.len(), 1);
+        assert_eq!((most_affected[0].0.as_str(), most_affected[0].1), ("a", 2));
+    }
+}
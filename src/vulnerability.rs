@@ -5,7 +5,7 @@
 use std::{ops::Index, slice};
 
 use advisory::Advisory;
-use db::AdvisoryDatabase;
+use db::{AdvisoryDatabase, MatchOptions};
 use lockfile::Lockfile;
 use package::Package;
 
@@ -16,11 +16,26 @@ pub struct Vulnerabilities(Vec<Vulnerability>);
 impl Vulnerabilities {
     /// Find all vulnerabilities for a given `AdvisoryDatabase` and `Lockfile`
     pub fn find(db: &AdvisoryDatabase, lockfile: &Lockfile) -> Self {
+        Self::find_with(db, lockfile, &MatchOptions::default())
+    }
+
+    /// Find all vulnerabilities for a given `AdvisoryDatabase` and `Lockfile`,
+    /// according to the given options
+    pub fn find_with(db: &AdvisoryDatabase, lockfile: &Lockfile, options: &MatchOptions) -> Self {
         let mut vulns = vec![];
 
         for package in &lockfile.packages {
-            for advisory in db.advisories_for_crate(&package.name, &package.version) {
-                vulns.push(Vulnerability::new(advisory, package))
+            for advisory in db.advisories_for_crate_with(&package.name, &package.version, options)
+            {
+                let affects_features = package
+                    .features
+                    .as_ref()
+                    .map(|features| advisory.affects_features(features))
+                    .unwrap_or(true);
+
+                if affects_features {
+                    vulns.push(Vulnerability::new(advisory, package))
+                }
             }
         }
 
@@ -0,0 +1,53 @@
+//! Warnings are informational advisories (e.g. about unmaintained or unsound
+//! crates) matching crates in a particular `Lockfile`, which are reported
+//! separately from vulnerabilities
+
+use advisory::{Advisory, Informational};
+use db::AdvisoryDatabase;
+use lockfile::Lockfile;
+use package::Package;
+
+/// A package with an associated informational advisory
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Warning {
+    /// Kind of informational advisory
+    pub kind: Informational,
+
+    /// The informational advisory
+    pub advisory: Advisory,
+
+    /// The package the advisory applies to
+    pub package: Package,
+}
+
+impl Warning {
+    /// Find all warnings for a given `AdvisoryDatabase` and `Lockfile`
+    pub fn find(db: &AdvisoryDatabase, lockfile: &Lockfile) -> Vec<Self> {
+        let mut warnings = vec![];
+
+        for package in &lockfile.packages {
+            for advisory in db.informational_for_crate(&package.name, &package.version) {
+                warnings.push(Warning {
+                    kind: advisory.informational.clone().unwrap(),
+                    advisory: advisory.clone(),
+                    package: package.clone(),
+                });
+            }
+        }
+
+        warnings
+    }
+
+    /// Find the warnings of the given kind (e.g. only those about
+    /// unmaintained crates) for a given `AdvisoryDatabase` and `Lockfile`
+    pub fn find_kind(
+        db: &AdvisoryDatabase,
+        lockfile: &Lockfile,
+        kind: &Informational,
+    ) -> Vec<Self> {
+        Self::find(db, lockfile)
+            .into_iter()
+            .filter(|warning| warning.kind == *kind)
+            .collect()
+    }
+}
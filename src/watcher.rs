@@ -0,0 +1,121 @@
+//! Watching the advisory database for new advisories about a set of crates
+//! (e.g. for long-running services which fetch the database periodically)
+
+use std::collections::BTreeSet;
+
+use advisory::{Advisory, AdvisoryId};
+use db::AdvisoryDatabase;
+use package::PackageName;
+
+/// Watches for new advisories about a set of crates, remembering which
+/// advisories it has already reported
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Watcher {
+    /// Crates to watch for advisories about
+    crates: BTreeSet<PackageName>,
+
+    /// IDs of the advisories which have already been seen
+    seen: BTreeSet<AdvisoryId>,
+}
+
+impl Watcher {
+    /// Watch the given crates, reporting every advisory about them the
+    /// first time `check` is called
+    pub fn new<I, N>(crates: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<PackageName>,
+    {
+        Watcher {
+            crates: crates.into_iter().map(Into::into).collect(),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Treat the advisories with the given IDs as already seen (e.g. ones
+    /// saved from `seen` before a restart)
+    pub fn with_seen<I: IntoIterator<Item = AdvisoryId>>(mut self, ids: I) -> Self {
+        self.seen.extend(ids);
+        self
+    }
+
+    /// Start watching another crate
+    pub fn watch<N: Into<PackageName>>(&mut self, name: N) {
+        self.crates.insert(name.into());
+    }
+
+    /// Stop watching a crate
+    pub fn unwatch<N: AsRef<PackageName>>(&mut self, name: N) {
+        self.crates.remove(name.as_ref());
+    }
+
+    /// Get the crates being watched
+    pub fn crates(&self) -> &BTreeSet<PackageName> {
+        &self.crates
+    }
+
+    /// Get the IDs of the advisories which have already been seen
+    pub fn seen(&self) -> &BTreeSet<AdvisoryId> {
+        &self.seen
+    }
+
+    /// Get the advisories in the given (e.g. freshly fetched) database about
+    /// the watched crates which haven't been seen before, in order of their
+    /// IDs, and remember them as seen. Withdrawn advisories are skipped.
+    pub fn check<'a>(&mut self, db: &'a AdvisoryDatabase) -> Vec<&'a Advisory> {
+        let mut new = vec![];
+
+        for name in &self.crates {
+            for advisory in db.find_by_package(name) {
+                if !advisory.is_withdrawn() && self.seen.insert(advisory.id.clone()) {
+                    new.push(advisory);
+                }
+            }
+        }
+
+        new.sort_by(|a, b| a.id.cmp(&b.id));
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Watcher;
+    use advisory::{Advisory, AdvisoryBuilder};
+    use db::AdvisoryDatabase;
+
+    fn advisory(id: &str, package: &str) -> Advisory {
+        AdvisoryBuilder::fixture(id, package)
+            .and_then(|b| b.patched_version(">= 1.0.0"))
+            .and_then(|b| b.build())
+            .unwrap()
+    }
+
+    #[test]
+    fn check_test() {
+        let mut watcher = Watcher::new(vec!["a", "b"]);
+
+        let db = AdvisoryDatabase::from_advisories(vec![
+            advisory("RUSTSEC-2019-0001", "a"),
+            advisory("RUSTSEC-2019-0002", "c"),
+        ])
+        .unwrap();
+
+        let new = watcher.check(&db);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id.as_str(), "RUSTSEC-2019-0001");
+        assert!(watcher.check(&db).is_empty());
+
+        let db = AdvisoryDatabase::from_advisories(vec![
+            advisory("RUSTSEC-2019-0001", "a"),
+            advisory("RUSTSEC-2019-0002", "c"),
+            advisory("RUSTSEC-2019-0003", "b"),
+        ])
+        .unwrap();
+
+        let new = watcher.check(&db);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id.as_str(), "RUSTSEC-2019-0003");
+        assert_eq!(watcher.seen().len(), 2);
+    }
+}
@@ -0,0 +1,210 @@
+//! Detection of yanked crates in a `Lockfile`, by consulting the crates.io
+//! registry index (either the sparse HTTP index, or a local checkout of the
+//! Git index). Depending on a yanked version isn't a vulnerability, but is
+//! worth warning about alongside them.
+
+use serde_json;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use ureq;
+
+use error::{Error, ErrorKind};
+use lockfile::Lockfile;
+use package::{Package, PackageName};
+
+/// URL of the crates.io sparse registry index
+pub const CRATES_IO_SPARSE_INDEX_URL: &str = "https://index.crates.io/";
+
+/// Sources of packages from crates.io in `Cargo.lock` files (via the Git
+/// and sparse indexes, respectively)
+const CRATES_IO_SOURCES: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// The crates.io registry index, which says which versions of crates have
+/// been yanked
+pub struct RegistryIndex(IndexSource);
+
+/// Where the registry index is read from
+enum IndexSource {
+    /// Sparse HTTP index at the given URL
+    Sparse { agent: ureq::Agent, url: String },
+
+    /// Local checkout of the Git index at the given path
+    Local(PathBuf),
+}
+
+impl RegistryIndex {
+    /// Use the crates.io sparse index
+    pub fn sparse() -> Self {
+        Self::sparse_with_url(CRATES_IO_SPARSE_INDEX_URL)
+    }
+
+    /// Use the sparse index at the given URL (e.g. a mirror of crates.io's)
+    pub fn sparse_with_url<S: Into<String>>(url: S) -> Self {
+        // Honor the `HTTPS_PROXY`/`ALL_PROXY` environment variables like `Repository::fetch`
+        let agent = ureq::AgentBuilder::new().try_proxy_from_env(true).build();
+        let mut url = url.into();
+
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+
+        RegistryIndex(IndexSource::Sparse { agent, url })
+    }
+
+    /// Use a local checkout of the crates.io Git index at the given path
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, Error> {
+        let path = path.into();
+
+        if !path.join("config.json").is_file() {
+            fail!(
+                ErrorKind::BadParam,
+                "not a registry index (missing config.json): {}",
+                path.display()
+            );
+        }
+
+        Ok(RegistryIndex(IndexSource::Local(path)))
+    }
+
+    /// Get the versions of the crate with the given name which have been
+    /// yanked (or none if the index doesn't have the crate)
+    pub fn yanked_versions(&self, name: &PackageName) -> Result<Vec<String>, Error> {
+        let path = index_path(name);
+
+        let contents = match self.0 {
+            IndexSource::Sparse { ref agent, ref url } => {
+                match agent.get(&format!("{}{}", url, path)).call() {
+                    Ok(response) => response.into_string()?,
+                    Err(ureq::Error::Status(404, _)) => return Ok(vec![]),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            IndexSource::Local(ref index_path) => {
+                match fs::read_to_string(index_path.join(Path::new(&path))) {
+                    Ok(contents) => contents,
+                    Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        };
+
+        parse_yanked_versions(name, &contents)
+    }
+}
+
+/// A package in a lockfile whose version has been yanked from crates.io
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YankedCrate {
+    /// The yanked package
+    pub package: Package,
+}
+
+impl YankedCrate {
+    /// Find all of the packages from crates.io in the given `Lockfile` whose
+    /// versions have been yanked, according to the given index
+    pub fn find(index: &RegistryIndex, lockfile: &Lockfile) -> Result<Vec<Self>, Error> {
+        let mut yanked_versions = BTreeMap::new();
+        let mut yanked = vec![];
+
+        for package in &lockfile.packages {
+            let from_crates_io = package
+                .source
+                .as_ref()
+                .map(|source| CRATES_IO_SOURCES.contains(&source.as_str()))
+                .unwrap_or(false);
+
+            if !from_crates_io {
+                continue;
+            }
+
+            if !yanked_versions.contains_key(&package.name) {
+                let versions = index.yanked_versions(&package.name)?;
+                yanked_versions.insert(package.name.clone(), versions);
+            }
+
+            let version = package.version.to_string();
+
+            if yanked_versions[&package.name].contains(&version) {
+                yanked.push(YankedCrate {
+                    package: package.clone(),
+                });
+            }
+        }
+
+        Ok(yanked)
+    }
+}
+
+/// Version of a crate in the registry index
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// Path to the index file for the crate with the given name (e.g.
+/// `se/rd/serde`), which is the same in the sparse and Git indexes
+fn index_path(name: &PackageName) -> String {
+    let name = name.as_str().to_lowercase();
+
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Parse the yanked versions out of an index file, which has a JSON object
+/// describing each version of the crate on each line
+fn parse_yanked_versions(name: &PackageName, contents: &str) -> Result<Vec<String>, Error> {
+    let mut yanked = vec![];
+
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: IndexEntry = serde_json::from_str(line)
+            .map_err(|e| err!(ErrorKind::Parse, "invalid index entry for {}: {}", name, e))?;
+
+        if entry.yanked {
+            yanked.push(entry.vers);
+        }
+    }
+
+    Ok(yanked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{index_path, parse_yanked_versions};
+    use package::PackageName;
+
+    #[test]
+    fn index_path_test() {
+        let path = |name: &str| index_path(&PackageName::from(name));
+
+        assert_eq!(path("a"), "1/a");
+        assert_eq!(path("ab"), "2/ab");
+        assert_eq!(path("abc"), "3/a/abc");
+        assert_eq!(path("Serde"), "se/rd/serde");
+    }
+
+    #[test]
+    fn parse_yanked_versions_test() {
+        let name = PackageName::from("a");
+        let contents = r#"{"name":"a","vers":"0.1.0","yanked":false}
+{"name":"a","vers":"0.1.1","yanked":true}
+"#;
+
+        assert_eq!(
+            parse_yanked_versions(&name, contents).unwrap(),
+            vec!["0.1.1"]
+        );
+        assert!(parse_yanked_versions(&name, "not json").is_err());
+    }
+}
@@ -15,12 +15,12 @@ fn test_integration() {
     assert_eq!(example_advisory.id, example_advisory_id);
     assert_eq!(example_advisory.package, example_package);
     assert_eq!(
-        example_advisory.patched_versions[0],
+        example_advisory.versions.patched[0],
         VersionReq::parse(">= 0.0.14").unwrap()
     );
     assert_eq!(example_advisory.date.as_str(), "2017-01-26");
     assert_eq!(
-        example_advisory.url.as_ref().unwrap(),
+        example_advisory.url.as_ref().unwrap().as_str(),
         "https://github.com/dnaq/sodiumoxide/issues/154"
     );
     assert_eq!(